@@ -0,0 +1,191 @@
+//! DataFusion scalar UDF for `ngram_rs`.
+//!
+//! Registers an `ngrams(col, n_min, n_max[, delimiter])` function backed by the Arrow
+//! kernels of `ngram_rs`. `col` is either a `List<Utf8>` of tokens or a `Utf8` column of
+//! raw text, which is tokenized on whitespace first:
+//!
+//! ```no_run
+//! # async fn example() -> datafusion::common::Result<()> {
+//! use datafusion::prelude::SessionContext;
+//!
+//! let ctx = SessionContext::new();
+//! ctx.register_udf(ngram_datafusion::ngrams_udf());
+//! let df = ctx.sql("SELECT ngrams(body, 1, 2, ' ') FROM documents").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, AsArray};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::{Result, ScalarValue, exec_err, plan_err};
+use datafusion::logical_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+
+/// Returns the `ngrams` UDF, ready for `SessionContext::register_udf`.
+pub fn ngrams_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(NGramsUdf::new())
+}
+
+/// Implementation of the `ngrams(col, n_min, n_max[, delimiter])` scalar function.
+///
+/// `n_min`, `n_max` and `delimiter` must be literals; the delimiter defaults to a single
+/// space. Null rows produce null lists and null tokens are skipped.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct NGramsUdf {
+    signature: Signature,
+}
+
+impl NGramsUdf {
+    /// Creates the UDF.
+    pub fn new() -> Self {
+        NGramsUdf {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl Default for NGramsUdf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn list_of_utf8() -> DataType {
+    DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+    )
+}
+
+/// Reads a non-negative integer literal argument.
+fn literal_usize(value: &ColumnarValue, name: &str) -> Result<usize> {
+    match value {
+        ColumnarValue::Scalar(ScalarValue::Int64(Some(v))) if *v >= 0 => Ok(*v as usize),
+        _ => exec_err!("ngrams: `{name}` must be a non-negative integer literal"),
+    }
+}
+
+impl ScalarUDFImpl for NGramsUdf {
+    fn name(&self) -> &str {
+        "ngrams"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if !(3..=4).contains(&arg_types.len()) {
+            return plan_err!(
+                "ngrams expects (col, n_min, n_max[, delimiter]), got {} arguments",
+                arg_types.len()
+            );
+        }
+
+        let column = match &arg_types[0] {
+            t if is_string(t) => DataType::Utf8,
+            DataType::List(f) | DataType::LargeList(f) if is_string(f.data_type()) => {
+                list_of_utf8()
+            }
+            other => return plan_err!("ngrams expects a string or list of strings, got {other}"),
+        };
+
+        let mut coerced = vec![column];
+        for t in &arg_types[1..3] {
+            if !t.is_integer() {
+                return plan_err!("ngrams expects integer n-gram sizes, got {t}");
+            }
+            coerced.push(DataType::Int64);
+        }
+        if let Some(t) = arg_types.get(3) {
+            if !is_string(t) {
+                return plan_err!("ngrams expects a string delimiter, got {t}");
+            }
+            coerced.push(DataType::Utf8);
+        }
+        Ok(coerced)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(list_of_utf8())
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let n_min = literal_usize(&args.args[1], "n_min")?;
+        let n_max = literal_usize(&args.args[2], "n_max")?;
+        if n_min > n_max {
+            return exec_err!("ngrams: n_min ({n_min}) is greater than n_max ({n_max})");
+        }
+        let n_range: Vec<usize> = (n_min..=n_max).collect();
+
+        let delimiter = match args.args.get(3) {
+            None => " ",
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(d)))) => d.as_str(),
+            Some(_) => return exec_err!("ngrams: `delimiter` must be a string literal"),
+        };
+
+        let column = args.args[0].to_array(args.number_rows)?;
+        let output = match column.data_type() {
+            DataType::Utf8 => {
+                ngram_rs::ngrams_string_array(column.as_string::<i32>(), &n_range, delimiter)?
+            }
+            DataType::List(_) => {
+                ngram_rs::ngrams_list_array(column.as_list::<i32>(), &n_range, delimiter)?
+            }
+            other => return exec_err!("ngrams: unsupported input type {other}"),
+        };
+
+        Ok(ColumnarValue::Array(Arc::new(output)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion::arrow::array::{ArrayRef, RecordBatch, StringArray};
+    use datafusion::prelude::SessionContext;
+
+    /// Tests calling the registered UDF from SQL on raw text
+    #[tokio::test]
+    async fn test_ngrams_sql() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ngrams_udf());
+
+        let text: ArrayRef = Arc::new(StringArray::from(vec![Some("the quick fox"), None]));
+        ctx.register_batch("docs", RecordBatch::try_from_iter([("text", text)])?)?;
+
+        let batches = ctx
+            .sql("SELECT ngrams(text, 1, 2, '_') AS grams FROM docs")
+            .await?
+            .collect()
+            .await?;
+        let grams = batches[0].column(0).as_list::<i32>();
+
+        let first = grams.value(0);
+        let first: Vec<&str> = first.as_string::<i32>().iter().flatten().collect();
+        assert_eq!(first, ["the", "quick", "fox", "the_quick", "quick_fox"]);
+        assert!(grams.is_null(1));
+        Ok(())
+    }
+
+    /// Tests that reversed n-gram bounds are reported as an error
+    #[tokio::test]
+    async fn test_ngrams_sql_bad_range() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.register_udf(ngrams_udf());
+
+        let result = match ctx.sql("SELECT ngrams('a b', 3, 1)").await {
+            Ok(df) => df.collect().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+#ifndef NGRAM_FFI_H
+#define NGRAM_FFI_H
+
+/* Generated by cbindgen from ngram_ffi/src/lib.rs; regenerate with `make ngram_ffi_header`. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status code returned by every fallible function of the C API.
+ */
+typedef enum NgramStatus {
+  /**
+   * The call succeeded.
+   */
+  NGRAM_STATUS_OK = 0,
+  /**
+   * A required pointer argument was NULL.
+   */
+  NGRAM_STATUS_NULL_POINTER = 1,
+  /**
+   * An input string was not valid UTF-8.
+   */
+  NGRAM_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The output array is too small; `out_len` holds the required capacity.
+   */
+  NGRAM_STATUS_BUFFER_TOO_SMALL = 3,
+} NgramStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the number of n-grams `ngram_generate` produces for `num_words` tokens.
+ *
+ * Use it to size the output array before calling `ngram_generate`.
+ *
+ * # Safety
+ *
+ * `n_range` must point to `n_range_len` readable values, or be NULL when `n_range_len` is 0.
+ */
+size_t ngram_count(size_t num_words, const size_t *n_range, size_t n_range_len);
+
+/**
+ * Generates n-grams into a caller-provided array of string pointers.
+ *
+ * On success `*out_len` is the number of strings written to `out`. When `out_capacity`
+ * is too small nothing is written, `*out_len` is set to the required capacity and
+ * `NGRAM_STATUS_BUFFER_TOO_SMALL` is returned. A NULL `delimiter` means a single space.
+ *
+ * # Safety
+ *
+ * * `words` must point to `num_words` valid NUL-terminated strings (or be NULL when 0)
+ * * `n_range` must point to `n_range_len` values (or be NULL when 0)
+ * * `delimiter` must be NULL or a valid NUL-terminated string
+ * * `out` must point to `out_capacity` writable pointers (or be NULL when 0)
+ * * `out_len` must be a valid writable pointer
+ */
+enum NgramStatus ngram_generate(const char *const *words,
+                                size_t num_words,
+                                const size_t *n_range,
+                                size_t n_range_len,
+                                const char *delimiter,
+                                char **out,
+                                size_t out_capacity,
+                                size_t *out_len);
+
+/**
+ * Frees strings written by `ngram_generate` and sets each entry to NULL.
+ *
+ * The array itself stays owned by the caller. NULL entries are skipped, so calling this
+ * twice on the same array is harmless.
+ *
+ * # Safety
+ *
+ * `strings` must point to `len` entries, each NULL or a pointer returned through
+ * `ngram_generate` that has not been freed yet.
+ */
+void ngram_free_strings(char **strings, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* NGRAM_FFI_H */
@@ -0,0 +1,275 @@
+//! C ABI for `ngram_rs`.
+//!
+//! Strings cross the boundary as NUL-terminated UTF-8. Generated n-grams are written
+//! into an array of pointers owned by the caller; each string is allocated by Rust and
+//! must be released with [`ngram_free_strings`]. The matching C header lives in
+//! `include/ngram_ffi.h` and is generated with cbindgen.
+
+use std::ffi::{CStr, CString, c_char};
+use std::slice;
+
+/// Status code returned by every fallible function of the C API.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NgramStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was NULL.
+    NullPointer = 1,
+    /// An input string was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The output array is too small; `out_len` holds the required capacity.
+    BufferTooSmall = 3,
+}
+
+/// Reads `len` values from a possibly NULL pointer, treating NULL as empty when `len` is 0.
+unsafe fn slice_from_raw<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], NgramStatus> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(NgramStatus::NullPointer);
+    }
+    // SAFETY: the caller guarantees `ptr` points to `len` initialized values.
+    Ok(unsafe { slice::from_raw_parts(ptr, len) })
+}
+
+/// Converts a NUL-terminated C string into a `&str`.
+unsafe fn str_from_raw<'a>(ptr: *const c_char) -> Result<&'a str, NgramStatus> {
+    if ptr.is_null() {
+        return Err(NgramStatus::NullPointer);
+    }
+    // SAFETY: the caller guarantees `ptr` is a valid NUL-terminated string.
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| NgramStatus::InvalidUtf8)
+}
+
+/// Returns the number of n-grams `ngram_generate` produces for `num_words` tokens.
+///
+/// Use it to size the output array before calling `ngram_generate`.
+///
+/// # Safety
+///
+/// `n_range` must point to `n_range_len` readable values, or be NULL when `n_range_len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ngram_count(
+    num_words: usize,
+    n_range: *const usize,
+    n_range_len: usize,
+) -> usize {
+    // SAFETY: forwarded from the caller's contract.
+    match unsafe { slice_from_raw(n_range, n_range_len) } {
+        Ok(n_range) => ngram_rs::ngram_count(num_words, n_range),
+        Err(_) => 0,
+    }
+}
+
+/// Generates n-grams into a caller-provided array of string pointers.
+///
+/// On success `*out_len` is the number of strings written to `out`. When `out_capacity`
+/// is too small nothing is written, `*out_len` is set to the required capacity and
+/// `NGRAM_STATUS_BUFFER_TOO_SMALL` is returned. A NULL `delimiter` means a single space.
+///
+/// # Safety
+///
+/// * `words` must point to `num_words` valid NUL-terminated strings (or be NULL when 0)
+/// * `n_range` must point to `n_range_len` values (or be NULL when 0)
+/// * `delimiter` must be NULL or a valid NUL-terminated string
+/// * `out` must point to `out_capacity` writable pointers (or be NULL when 0)
+/// * `out_len` must be a valid writable pointer
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ngram_generate(
+    words: *const *const c_char,
+    num_words: usize,
+    n_range: *const usize,
+    n_range_len: usize,
+    delimiter: *const c_char,
+    out: *mut *mut c_char,
+    out_capacity: usize,
+    out_len: *mut usize,
+) -> NgramStatus {
+    if out_len.is_null() {
+        return NgramStatus::NullPointer;
+    }
+
+    // SAFETY: every pointer is forwarded under the contract documented above.
+    let result = unsafe {
+        generate(
+            words,
+            num_words,
+            n_range,
+            n_range_len,
+            delimiter,
+            out,
+            out_capacity,
+        )
+    };
+    match result {
+        Ok(written) => {
+            // SAFETY: checked non-NULL above.
+            unsafe { *out_len = written };
+            NgramStatus::Ok
+        }
+        Err((status, required)) => {
+            // SAFETY: checked non-NULL above.
+            unsafe { *out_len = required };
+            status
+        }
+    }
+}
+
+/// Body of `ngram_generate`, returning the count written or the error and required size.
+#[allow(clippy::too_many_arguments)]
+unsafe fn generate(
+    words: *const *const c_char,
+    num_words: usize,
+    n_range: *const usize,
+    n_range_len: usize,
+    delimiter: *const c_char,
+    out: *mut *mut c_char,
+    out_capacity: usize,
+) -> Result<usize, (NgramStatus, usize)> {
+    // SAFETY: forwarded from `ngram_generate`.
+    let (word_ptrs, n_range) = unsafe {
+        (
+            slice_from_raw(words, num_words).map_err(|e| (e, 0))?,
+            slice_from_raw(n_range, n_range_len).map_err(|e| (e, 0))?,
+        )
+    };
+
+    let required = ngram_rs::ngram_count(num_words, n_range);
+    if required > out_capacity {
+        return Err((NgramStatus::BufferTooSmall, required));
+    }
+    if required > 0 && out.is_null() {
+        return Err((NgramStatus::NullPointer, required));
+    }
+
+    let delimiter = if delimiter.is_null() {
+        " "
+    } else {
+        // SAFETY: non-NULL delimiter is a valid C string per the contract.
+        unsafe { str_from_raw(delimiter) }.map_err(|e| (e, 0))?
+    };
+    let words = word_ptrs
+        .iter()
+        // SAFETY: each pointer is a valid C string per the contract.
+        .map(|&w| unsafe { str_from_raw(w) }.map(String::from))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| (e, 0))?;
+
+    let ngrams = ngram_rs::generate_ngrams(&words, n_range, Some(delimiter));
+    for (i, ngram) in ngrams.into_iter().enumerate() {
+        // Inputs are C strings and therefore contain no interior NUL bytes
+        let ngram = CString::new(ngram.into_owned()).expect("n-gram contains a NUL byte");
+        // SAFETY: `i < required <= out_capacity` and `out` is non-NULL here.
+        unsafe { *out.add(i) = ngram.into_raw() };
+    }
+
+    Ok(required)
+}
+
+/// Frees strings written by `ngram_generate` and sets each entry to NULL.
+///
+/// The array itself stays owned by the caller. NULL entries are skipped, so calling this
+/// twice on the same array is harmless.
+///
+/// # Safety
+///
+/// `strings` must point to `len` entries, each NULL or a pointer returned through
+/// `ngram_generate` that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ngram_free_strings(strings: *mut *mut c_char, len: usize) {
+    if strings.is_null() {
+        return;
+    }
+    for i in 0..len {
+        // SAFETY: `strings` points to `len` entries per the contract.
+        let entry = unsafe { &mut *strings.add(i) };
+        if !entry.is_null() {
+            // SAFETY: the pointer came from `CString::into_raw` in `ngram_generate`.
+            drop(unsafe { CString::from_raw(*entry) });
+            *entry = std::ptr::null_mut();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    /// Tests the size query, generation and freeing round trip
+    #[test]
+    fn test_generate_round_trip() {
+        let owned = [c"the", c"quick", c"fox"];
+        let words: Vec<*const c_char> = owned.iter().map(|w| w.as_ptr()).collect();
+        let n_range = [2usize];
+        let mut out_len = 0;
+
+        let status = unsafe {
+            ngram_generate(
+                words.as_ptr(),
+                words.len(),
+                n_range.as_ptr(),
+                n_range.len(),
+                c"_".as_ptr(),
+                ptr::null_mut(),
+                0,
+                &mut out_len,
+            )
+        };
+        assert_eq!(status, NgramStatus::BufferTooSmall);
+        assert_eq!(out_len, 2);
+
+        let mut out = vec![ptr::null_mut(); out_len];
+        let status = unsafe {
+            ngram_generate(
+                words.as_ptr(),
+                words.len(),
+                n_range.as_ptr(),
+                n_range.len(),
+                c"_".as_ptr(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut out_len,
+            )
+        };
+        assert_eq!(status, NgramStatus::Ok);
+        let ngrams: Vec<&str> = out
+            .iter()
+            .map(|&p| unsafe { CStr::from_ptr(p) }.to_str().unwrap())
+            .collect();
+        assert_eq!(ngrams, ["the_quick", "quick_fox"]);
+
+        unsafe { ngram_free_strings(out.as_mut_ptr(), out.len()) };
+        assert!(out.iter().all(|p| p.is_null()));
+    }
+
+    /// Tests that invalid UTF-8 input is reported instead of panicking
+    #[test]
+    fn test_invalid_utf8() {
+        let bad = [0xffu8, 0];
+        let words = [bad.as_ptr() as *const c_char];
+        let n_range = [1usize];
+        let mut out = [ptr::null_mut()];
+        let mut out_len = 0;
+
+        let status = unsafe {
+            ngram_generate(
+                words.as_ptr(),
+                1,
+                n_range.as_ptr(),
+                1,
+                ptr::null(),
+                out.as_mut_ptr(),
+                1,
+                &mut out_len,
+            )
+        };
+        assert_eq!(status, NgramStatus::InvalidUtf8);
+        assert!(out[0].is_null());
+    }
+}
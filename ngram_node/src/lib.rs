@@ -0,0 +1,129 @@
+//! Node.js bindings for `ngram_rs`, built with napi-rs.
+//!
+//! ```js
+//! const { ngrams, charNgrams, NGramIndex } = require("ngram_node");
+//!
+//! ngrams(["the", "quick", "fox"], [2]);   // ["the quick", "quick fox"]
+//! const index = new NGramIndex(3);
+//! index.insert("jonathan");
+//! index.search("johnathan", 5, 0.3);       // [{ id: 0, text: "jonathan", score: ... }]
+//! ```
+
+use napi_derive::napi;
+
+fn to_usize(values: Vec<u32>) -> Vec<usize> {
+    values.into_iter().map(|v| v as usize).collect()
+}
+
+/// Generates word n-grams; `delimiter` defaults to a single space.
+#[napi]
+pub fn ngrams(words: Vec<String>, n_range: Vec<u32>, delimiter: Option<String>) -> Vec<String> {
+    ngram_rs::generate_ngrams_owned(
+        &words,
+        &to_usize(n_range),
+        delimiter.as_deref().unwrap_or(" "),
+    )
+}
+
+/// Generates character n-grams from a string.
+#[napi]
+pub fn char_ngrams(text: String, n_range: Vec<u32>) -> Vec<String> {
+    ngram_rs::generate_char_ngrams(&text, &to_usize(n_range))
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// A search result returned by `NGramIndex.search`.
+#[napi(object)]
+pub struct NGramMatch {
+    pub id: u32,
+    pub text: String,
+    pub score: f64,
+}
+
+/// Fuzzy string index over character n-grams.
+#[napi(js_name = "NGramIndex")]
+pub struct JsNGramIndex {
+    inner: ngram_rs::NGramIndex,
+}
+
+#[napi]
+impl JsNGramIndex {
+    /// Creates an empty index over character n-grams of size `n`.
+    #[napi(constructor)]
+    pub fn new(n: u32) -> Self {
+        JsNGramIndex {
+            inner: ngram_rs::NGramIndex::new(n as usize),
+        }
+    }
+
+    /// Number of indexed strings.
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.inner.len() as u32
+    }
+
+    /// Adds a string and returns its id.
+    #[napi]
+    pub fn insert(&mut self, text: String) -> u32 {
+        self.inner.insert(&text) as u32
+    }
+
+    /// Returns up to `limit` matches scoring at least `min_score` (default 0), best first.
+    #[napi]
+    pub fn search(&self, query: String, limit: u32, min_score: Option<f64>) -> Vec<NGramMatch> {
+        self.inner
+            .search(&query, limit as usize, min_score.unwrap_or(0.0))
+            .into_iter()
+            .map(|(id, score)| NGramMatch {
+                id: id as u32,
+                text: self.inner.get(id).unwrap_or_default().to_string(),
+                score,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// Tests that word and character n-grams convert the JavaScript n-values
+    #[test]
+    fn test_ngrams() {
+        let words = strings(&["the", "quick", "fox"]);
+        assert_eq!(
+            ngrams(words.clone(), vec![2], None),
+            ["the quick", "quick fox"]
+        );
+        assert_eq!(
+            ngrams(words, vec![1, 3], Some("_".to_string())),
+            ["the", "quick", "fox", "the_quick_fox"]
+        );
+        assert_eq!(char_ngrams("día".to_string(), vec![2]), ["dí", "ía"]);
+        assert!(char_ngrams("night".to_string(), vec![0, 6]).is_empty());
+    }
+
+    /// Tests that index matches carry their id, text and score
+    #[test]
+    fn test_index_search() {
+        let mut index = JsNGramIndex::new(3);
+        assert_eq!(index.insert("jonathan".to_string()), 0);
+        assert_eq!(index.insert("mary".to_string()), 1);
+        assert_eq!(index.size(), 2);
+
+        let matches = index.search("johnathan".to_string(), 5, Some(0.3));
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].id, matches[0].text.as_str()), (0, "jonathan"));
+        let expected = index.inner.search("johnathan", 5, 0.3)[0].1;
+        assert_eq!(matches[0].score, expected);
+
+        assert_eq!(index.search("mary".to_string(), 1, None)[0].text, "mary");
+        assert!(index.search("mary".to_string(), 0, None).is_empty());
+    }
+}
@@ -1,18 +1,496 @@
+from __future__ import annotations
+
+from collections.abc import Mapping
 from pathlib import Path
+from typing import TYPE_CHECKING, Any, TypedDict
 
 import polars as pl
 from polars.plugins import register_plugin_function
 from polars._typing import IntoExpr
 
+if TYPE_CHECKING:
+    from typing_extensions import Unpack
+
 PLUGIN_PATH = Path(__file__).parent
 
-def ngrams(expr: IntoExpr, n_range: list[int] =[1], delimiter : str = " ") -> pl.Expr:
-    """Return a list of n-grams given a list of strings"""
+# A single size, a list of sizes, a `(min, max)` tuple or a `{"min": ..., "max": ...}`
+# mapping, both bounds included
+NRange = int | list[int] | tuple[int, int] | dict[str, int]
+
+
+def _n_range(n_range: NRange) -> int | list[int] | dict[str, int]:
+    if isinstance(n_range, tuple):
+        min_n, max_n = n_range
+        return {"min": min_n, "max": max_n}
+    return n_range
+
+
+def _delimiter_args(expr: IntoExpr, delimiter: str | pl.Expr) -> tuple[list, str]:
+    """Split `delimiter` into the plugin inputs and the `delimiter` kwarg."""
+    if isinstance(delimiter, pl.Expr):
+        return [expr, delimiter], " "
+    return [expr], delimiter
+
+
+class TokenOptions(TypedDict, total=False):
+    """Tokenizing and normalization options shared by the n-gram expressions.
+
+    Each option works as described in `ngrams`.
+    """
+
+    tokenizer: str
+    null_policy: str
+    cast_numeric: bool
+    placeholders: bool | dict[str, str | None]
+    lowercase: bool
+    locale: str | None
+    unicode_normalize: str | None
+    strip_diacritics: bool
+    stopwords: str | list[str] | None
+    stopword_mode: str
+    phonetic: str | None
+    pad: bool
+    bos: str
+    eos: str
+
+
+class RowOptions(TokenOptions, total=False):
+    """`TokenOptions` plus the row options of the expressions that build n-gram strings."""
+
+    short_row: str
+    inner_nulls: str
+    null_token: str
+
+
+_OPTION_DEFAULTS: dict[str, Any] = {
+    "tokenizer": "whitespace",
+    "null_policy": "propagate",
+    "short_row": "empty",
+    "inner_nulls": "skip",
+    "null_token": "<null>",
+    "cast_numeric": False,
+    "placeholders": False,
+    "lowercase": False,
+    "locale": None,
+    "unicode_normalize": None,
+    "strip_diacritics": False,
+    "stopwords": None,
+    "stopword_mode": "tokens",
+    "phonetic": None,
+    "pad": False,
+    "bos": "<s>",
+    "eos": "</s>",
+}
+
+
+def _ngram_kwargs(
+    function_name: str,
+    n_range: NRange,
+    options: Mapping[str, Any],
+    accepted: type = RowOptions,
+    **kwargs: Any,
+) -> dict[str, Any]:
+    """Build the plugin kwargs of an n-gram expression.
+
+    `options` are the shared options the caller got, checked against the keys of
+    `accepted` and completed with their defaults; `kwargs` are the expression's own.
+    """
+    for name in options:
+        if name not in accepted.__optional_keys__:
+            raise TypeError(f"{function_name}() got an unexpected keyword argument {name!r}")
+    return {"n_range": _n_range(n_range), **_OPTION_DEFAULTS, **options, **kwargs}
+
+
+def ngrams(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    delimiter: str | pl.Expr = " ",
+    output: str = "list",
+    min_count: int = 1,
+    parallel: bool = False,
+    **options: Unpack[RowOptions],
+) -> pl.Expr:
+    """Return a list of n-grams given a list of strings or a string.
+
+    `n_range` is a single size, a list of sizes, or a `(min, max)` tuple or
+    `{"min": ..., "max": ...}` mapping covering every size between the bounds.
+    `delimiter` is a string, or a `String` expression giving each row its own
+    delimiter; null delimiters fall back to a space.
+
+    String columns are split with `tokenizer`: "whitespace", "unicode" (Unicode word
+    boundaries, dropping punctuation), "cjk" (whitespace, with Chinese and Japanese
+    characters as single tokens and Korean runs split from other scripts) or a regex
+    pattern whose matches are the tokens.
+    A null row becomes null with `null_policy="propagate"`, an empty list with "skip",
+    and raises with "error". A row with fewer tokens than the smallest n gets an empty
+    list with `short_row="empty"`, null with "null", its tokens joined as a single
+    n-gram with "partial", and raises with "error"; padded rows are never short, so
+    `pad` raises with any `short_row` but "empty". Null items inside a list are
+    dropped with `inner_nulls="skip"`, end one window run and start the next with
+    "break" so no n-gram spans them, and become `null_token` with "placeholder".
+    With "break", `short_row` counts the tokens of every run together.
+    Lists of other types raise unless `cast_numeric` is set
+    and the items are numbers, which are then cast to strings. With `placeholders`,
+    number, URL and email tokens become "<NUM>", "<URL>" and "<EMAIL>"; a dict such
+    as `{"number": "#", "email": None}` changes the placeholder of the "number", "url"
+    or "email" kind, or disables it with None. Other tokens are converted
+    to the `unicode_normalize` form ("nfc", "nfd", "nfkc" or "nfkd"), stripped of
+    accents and other combining marks with `strip_diacritics`, then lowercased
+    with `lowercase`, in the same pass as n-gram generation, following the case rules
+    of the `locale` language tag: "tr" or "az" lowercase "I" to the dotless "ı" and
+    "İ" to "i", other languages use the Unicode default mapping. `stopwords` is a
+    list of words or the name of a builtin list ("english"), matched against the
+    normalized tokens: stop words are dropped before windowing with
+    `stopword_mode="tokens"`, and n-grams starting or ending with one are dropped with
+    "boundary". With `phonetic` ("soundex" or "metaphone"), the remaining tokens are
+    replaced with their phonetic codes, so spelling variants of a name give the same
+    n-grams; tokens without ASCII letters are dropped, and "boundary" stop words are
+    not supported. With `pad`, the
+    tokens of each row are surrounded by `n - 1` `bos` and `eos` tokens before the
+    n-grams of size `n` are formed, as language models expect. With `output="struct"`
+    every size gets its own `List(String)` field: `unigrams`, `bigrams`, `trigrams`,
+    then `4grams` and so on. With `min_count`, n-grams occurring fewer times over the
+    whole column are dropped; counting needs the full column, so the expression is
+    then no longer elementwise. With `parallel`, the rows are split across the Polars
+    thread pool, which pays off on large frames evaluated in a single call.
+    """
+    args, delimiter = _delimiter_args(expr, delimiter)
     return register_plugin_function(
         plugin_path=PLUGIN_PATH,
         function_name="ngrams",
+        args=args,
+        kwargs=_ngram_kwargs(
+            "ngrams",
+            n_range,
+            options,
+            delimiter=delimiter,
+            output=output,
+            min_count=min_count,
+            parallel=parallel,
+        ),
+        is_elementwise=min_count <= 1,
+    )
+
+
+def ngrams_exploded(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    delimiter: str | pl.Expr = " ",
+    min_count: int = 1,
+    parallel: bool = False,
+    **options: Unpack[RowOptions],
+) -> pl.Expr:
+    """Return one row per n-gram, as a `{row, ngram}` struct.
+
+    `row` is the index of the input row the n-gram comes from, so the result can be
+    joined back to its source with `with_row_index` without exploding an intermediate
+    list column. Null rows produce no rows unless `null_policy="error"`, which raises.
+    The other arguments work as in `ngrams`.
+    """
+    args, delimiter = _delimiter_args(expr, delimiter)
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="ngrams_exploded",
+        args=args,
+        kwargs=_ngram_kwargs(
+            "ngrams_exploded",
+            n_range,
+            options,
+            delimiter=delimiter,
+            min_count=min_count,
+            parallel=parallel,
+        ),
+        changes_length=True,
+    )
+
+
+def ngram_count(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    tokenizer: str = "whitespace",
+    cast_numeric: bool = False,
+) -> pl.Expr:
+    """Return the number of n-grams of each row as a `UInt32`, without building them.
+
+    The count follows from the number of tokens: list and array lengths, less null
+    items, or the tokens of strings split with `tokenizer`. It matches `ngrams`
+    without normalization, stop words or padding. Null rows stay null.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="ngram_count",
         args=[expr],
-        kwargs={"n_range": n_range, "delimiter": delimiter},
+        kwargs={
+            "n_range": _n_range(n_range),
+            "tokenizer": tokenizer,
+            "cast_numeric": cast_numeric,
+        },
+        is_elementwise=True,
+    )
+
+
+def ngram_counts(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    delimiter: str | pl.Expr = " ",
+    parallel: bool = False,
+    **options: Unpack[RowOptions],
+) -> pl.Expr:
+    """Return the distinct n-grams of each list of strings or string with their counts.
+
+    Each row becomes a list of `{ngram, count}` structs, in order of first occurrence,
+    counting the n-grams `ngrams` would return for it. The other arguments work as in
+    `ngrams`.
+    """
+    args, delimiter = _delimiter_args(expr, delimiter)
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="ngram_counts",
+        args=args,
+        kwargs=_ngram_kwargs(
+            "ngram_counts", n_range, options, delimiter=delimiter, parallel=parallel
+        ),
+        is_elementwise=True,
+    )
+
+
+def top_ngrams(
+    expr: IntoExpr,
+    k: int = 10,
+    n_range: NRange = [1],
+    delimiter: str | pl.Expr = " ",
+    **options: Unpack[RowOptions],
+) -> pl.Expr:
+    """Return the `k` most frequent n-grams over all rows, with their counts.
+
+    This is an aggregation: it returns a single list of `{ngram, count}` structs, most
+    frequent first with ties in order of first occurrence, and one per group in
+    `group_by().agg(...)`. Null rows are ignored unless `null_policy="error"`. The
+    other arguments work as in `ngrams`.
+    """
+    args, delimiter = _delimiter_args(expr, delimiter)
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="top_ngrams",
+        args=args,
+        kwargs=_ngram_kwargs("top_ngrams", n_range, options, k=k, delimiter=delimiter),
+        returns_scalar=True,
+    )
+
+
+def tfidf(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    delimiter: str | pl.Expr = " ",
+    **options: Unpack[RowOptions],
+) -> pl.Expr:
+    """Return the distinct n-grams of each row weighted by TF-IDF.
+
+    Each row becomes a list of `{ngram, tfidf}` structs, in order of first occurrence.
+    The weight is the n-gram's count in the row times `ln((1 + N) / (1 + df)) + 1`,
+    where `N` is the number of non-null rows and `df` the number containing the
+    n-gram, as in scikit-learn's `TfidfVectorizer(norm=None)`. Document frequencies
+    are taken over the whole column, or over each group in a `group_by` or `over`.
+    The other arguments work as in `ngrams`.
+    """
+    args, delimiter = _delimiter_args(expr, delimiter)
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="tfidf",
+        args=args,
+        kwargs=_ngram_kwargs("tfidf", n_range, options, delimiter=delimiter),
+    )
+
+
+def ngrams_hashed(
+    expr: IntoExpr,
+    n_range: NRange = [1],
+    seed: int = 0,
+    parallel: bool = False,
+    **options: Unpack[TokenOptions],
+) -> pl.Expr:
+    """Return the 64-bit hashes of the n-grams of each list of strings or string.
+
+    No n-gram string is built: each n-gram is hashed from its tokens, so the result
+    does not depend on a delimiter. The same tokens and `seed` always give the same
+    hash. The other arguments work as in `ngrams`.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="ngrams_hashed",
+        args=[expr],
+        kwargs=_ngram_kwargs(
+            "ngrams_hashed", n_range, options, TokenOptions, seed=seed, parallel=parallel
+        ),
+        is_elementwise=True,
+    )
+
+
+def char_ngrams(
+    expr: IntoExpr,
+    n_range: NRange = [3],
+    pad: str | None = None,
+    grapheme: bool = False,
+    punctuation: str = "keep",
+    whitespace: str = "keep",
+    emoji: str = "keep",
+) -> pl.Expr:
+    """Return the character n-grams of each string.
+
+    With `pad`, the string is wrapped in `n - 1` copies of that character on each side,
+    so the first and last characters appear in as many n-grams as the others. With
+    `grapheme`, windows count grapheme clusters rather than code points, so accented
+    letters written with combining marks and emoji sequences are never split.
+    `punctuation`, `whitespace` and `emoji` are each "keep", "drop", or a single
+    character that replaces every run of those characters, applied before padding.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="char_ngrams",
+        args=[expr],
+        kwargs={
+            "n_range": _n_range(n_range),
+            "pad": pad,
+            "grapheme": grapheme,
+            "punctuation": punctuation,
+            "whitespace": whitespace,
+            "emoji": emoji,
+        },
+        is_elementwise=True,
+    )
+
+
+def byte_ngrams(expr: IntoExpr, n_range: NRange = [3]) -> pl.Expr:
+    """Return the byte n-grams of each binary value as a `List(Binary)`.
+
+    Windows are taken over raw bytes, for hashes, DNA sequences or identifiers whose
+    encoding must not be interpreted. `String` columns are read as their UTF-8 bytes.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="byte_ngrams",
+        args=[expr],
+        kwargs={"n_range": _n_range(n_range)},
+        is_elementwise=True,
+    )
+
+
+def skipgrams(
+    expr: IntoExpr, n: int = 2, k: int = 1, delimiter: str = " ", cast_numeric: bool = False
+) -> pl.Expr:
+    """Return the k-skip-n-grams of each list of strings.
+
+    Each skip-gram keeps `n` tokens in order and skips at most `k` tokens in total.
+    `cast_numeric` works as in `ngrams`.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="skipgrams",
+        args=[expr],
+        kwargs={"n": n, "k": k, "delimiter": delimiter, "cast_numeric": cast_numeric},
+        is_elementwise=True,
+    )
+
+
+def ngram_similarity(
+    expr_a: IntoExpr, expr_b: IntoExpr, n: int = 3, metric: str = "jaccard"
+) -> pl.Expr:
+    """Return the n-gram similarity of the paired rows of two columns as a `Float64`.
+
+    Strings are compared by their character n-grams and lists or arrays of strings by
+    their word n-grams of size `n`. `metric` is `"jaccard"` (default) or `"dice"`. A
+    single-row side, such as `pl.lit("query")`, is compared with every row of the
+    other side; a row is null if either side is null.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="ngram_similarity",
+        args=[expr_a, expr_b],
+        kwargs={"n": n, "metric": metric},
+        is_elementwise=True,
+    )
+
+
+def blocking_keys(
+    expr: IntoExpr, n: int = 3, num_keys: int = 4, band_size: int = 1, seed: int = 0
+) -> pl.Expr:
+    """Return MinHash blocking keys of the character n-grams of each string.
+
+    Each string gets `num_keys` `UInt64` keys, each combining `band_size` MinHash
+    values, so similar strings are likely to share at least one key. Explode the keys
+    on both sides and join on them to find candidate pairs for fuzzy matching, then
+    score the pairs with `ngram_similarity`. Strings shorter than `n` get no keys.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="blocking_keys",
+        args=[expr],
+        kwargs={"n": n, "num_keys": num_keys, "band_size": band_size, "seed": seed},
+        is_elementwise=True,
+    )
+
+
+def bleu(
+    candidate: IntoExpr, reference: IntoExpr, max_n: int = 4, tokenizer: str = "whitespace"
+) -> pl.Expr:
+    """Return the sentence-level BLEU score of each candidate against its reference.
+
+    Strings are split with `tokenizer`, as in `ngrams`; lists or arrays of strings are
+    used as tokens. The score is the geometric mean of the clipped n-gram precisions
+    up to `max_n` times a brevity penalty, without smoothing. A single-row side is
+    compared with every row of the other side; a row is null if either side is null.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="bleu",
+        args=[candidate, reference],
+        kwargs={"max_n": max_n, "tokenizer": tokenizer},
+        is_elementwise=True,
+    )
+
+
+def rouge_n(
+    candidate: IntoExpr, reference: IntoExpr, n: int = 2, tokenizer: str = "whitespace"
+) -> pl.Expr:
+    """Return the ROUGE-N overlap of each candidate with its reference.
+
+    Each row becomes a `{precision, recall, f1}` struct of the shared n-grams of size
+    `n`. Inputs, broadcasting and nulls work as in `bleu`.
+    """
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="rouge_n",
+        args=[candidate, reference],
+        kwargs={"n": n, "tokenizer": tokenizer},
+        is_elementwise=True,
+    )
+
+
+def detect_language(
+    expr: IntoExpr,
+    samples: Mapping[str, str | list[str]] | None = None,
+    profiles: str | Path | None = None,
+    n_range: list[int] = [1, 2, 3],
+) -> pl.Expr:
+    """Return the most likely language of each string, with its probability.
+
+    The detector is a naive Bayes model over character n-grams of the sizes in
+    `n_range`, trained on `samples`, a mapping from language to sample texts, and on
+    the `LANG.txt` files of the `profiles` directory. Each row becomes a
+    `{lang, confidence}` struct; null strings stay null.
+    """
+    samples = {
+        language: [texts] if isinstance(texts, str) else list(texts)
+        for language, texts in (samples or {}).items()
+    }
+    return register_plugin_function(
+        plugin_path=PLUGIN_PATH,
+        function_name="detect_language",
+        args=[expr],
+        kwargs={
+            "samples": samples,
+            "profiles": None if profiles is None else str(profiles),
+            "n_range": n_range,
+        },
         is_elementwise=True,
-        changes_length=True,
     )
@@ -1,62 +1,2026 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
 use polars::prelude::*;
+use polars_core::POOL;
+use polars_core::chunked_array::builder::get_list_builder;
 use pyo3_polars::derive::polars_expr;
-use serde::Deserialize;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct NGramsKwargs {
+    #[serde(deserialize_with = "deserialize_n_range")]
     n_range: Vec<usize>,
     #[serde(default = "default_delimiter")]
     delimiter: String,
+    /// How `String` inputs are split: "whitespace", "unicode" or a regex matching tokens
+    #[serde(default = "default_tokenizer")]
+    tokenizer: String,
+    #[serde(default)]
+    null_policy: NullPolicy,
+    /// Cast numeric list items to strings instead of raising
+    #[serde(default)]
+    cast_numeric: bool,
+    /// Replace number, URL and email tokens before any other normalization
+    #[serde(default)]
+    placeholders: Option<PlaceholdersKwarg>,
+    #[serde(default)]
+    lowercase: bool,
+    /// BCP 47 language tag whose case rules `lowercase` follows, such as "tr"
+    #[serde(default)]
+    locale: Option<String>,
+    /// "nfc", "nfd", "nfkc" or "nfkd"
+    #[serde(default)]
+    unicode_normalize: Option<String>,
+    /// Remove accents and other combining marks from every token
+    #[serde(default)]
+    strip_diacritics: bool,
+    #[serde(default)]
+    stopwords: Option<StopWords>,
+    #[serde(default)]
+    stopword_mode: StopWordMode,
+    /// "soundex" or "metaphone", replacing every token with its phonetic code
+    #[serde(default)]
+    phonetic: Option<String>,
+    /// Pad every row with `n - 1` BOS and EOS tokens before forming n-grams of size `n`
+    #[serde(default)]
+    pad: bool,
+    #[serde(default = "default_bos")]
+    bos: String,
+    #[serde(default = "default_eos")]
+    eos: String,
+    /// Only used by `ngrams`
+    #[serde(default)]
+    output: NGramsOutput,
+    #[serde(default)]
+    short_row: ShortRowPolicy,
+    #[serde(default)]
+    inner_nulls: InnerNullPolicy,
+    /// Token standing in for null items with `inner_nulls="placeholder"`
+    #[serde(default = "default_null_token")]
+    null_token: String,
+    /// Only keep n-grams occurring at least this often over the whole column
+    #[serde(default)]
+    min_count: u32,
+    /// Split the rows across the Polars thread pool
+    #[serde(default)]
+    parallel: bool,
+    /// The n-grams kept by `min_count`, counted over the whole column before it is
+    /// split across threads
+    #[serde(skip)]
+    frequent: Option<Arc<HashSet<String>>>,
+    /// Hash seed, only used by `ngrams_hashed`
+    #[serde(default)]
+    seed: u64,
+}
+
+/// The `stopwords` kwarg: the name of a builtin list or the stop words themselves.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum StopWords {
+    Builtin(String),
+    List(Vec<String>),
+}
+
+/// The `placeholders` kwarg: whether to use the default placeholders, or the
+/// placeholders to change by kind, `None` disabling one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum PlaceholdersKwarg {
+    Enabled(bool),
+    Custom(HashMap<String, Option<String>>),
+}
+
+impl PlaceholdersKwarg {
+    fn placeholders(&self) -> PolarsResult<Option<ngram_rs::Placeholders>> {
+        let custom = match self {
+            PlaceholdersKwarg::Enabled(enabled) => {
+                return Ok(enabled.then(ngram_rs::Placeholders::new));
+            }
+            PlaceholdersKwarg::Custom(custom) => custom,
+        };
+        let mut placeholders = ngram_rs::Placeholders::new();
+        for (kind, placeholder) in custom {
+            let placeholder = placeholder.as_deref();
+            placeholders = match kind.as_str() {
+                "number" => placeholders.number(placeholder),
+                "url" => placeholders.url(placeholder),
+                "email" => placeholders.email(placeholder),
+                _ => polars_bail!(
+                    InvalidOperation: "unknown placeholder kind {:?}; expected \"number\", \"url\" or \"email\"",
+                    kind
+                ),
+            };
+        }
+        Ok(Some(placeholders))
+    }
+}
+
+/// How stop words are removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StopWordMode {
+    /// Stop words are dropped from the tokens before windowing
+    #[default]
+    Tokens,
+    /// N-grams starting or ending with a stop word are dropped
+    Boundary,
+}
+
+/// The shape of the `ngrams` output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NGramsOutput {
+    /// One list with the n-grams of every size
+    #[default]
+    List,
+    /// A struct with one list field per size, named after the order
+    Struct,
+}
+
+/// What a row with fewer tokens than the smallest n becomes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ShortRowPolicy {
+    /// An empty list
+    #[default]
+    Empty,
+    /// A null list
+    Null,
+    /// A single n-gram of all its tokens
+    Partial,
+    /// An error naming the row
+    Error,
+}
+
+/// How null items inside a list row are handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum InnerNullPolicy {
+    /// Null items are dropped and the tokens around them become adjacent
+    #[default]
+    Skip,
+    /// No n-gram spans a null item
+    Break,
+    /// Null items become the `null_token`
+    Placeholder,
+}
+
+/// What a null input row becomes.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum NullPolicy {
+    /// An empty list
+    Skip,
+    /// A null list
+    #[default]
+    Propagate,
+    /// An error naming the first null row
+    Error,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CharNGramsKwargs {
+    #[serde(deserialize_with = "deserialize_n_range")]
+    n_range: Vec<usize>,
+    /// Single character repeated `n - 1` times on each side of the text
+    #[serde(default)]
+    pad: Option<String>,
+    /// Window over grapheme clusters instead of Unicode scalar values
+    #[serde(default)]
+    grapheme: bool,
+    /// "keep", "drop" or a single placeholder character, for each symbol class
+    #[serde(default)]
+    punctuation: Option<String>,
+    #[serde(default)]
+    whitespace: Option<String>,
+    #[serde(default)]
+    emoji: Option<String>,
+}
+
+/// Parses the `name` kwarg of `char_ngrams`: "keep", "drop" or a placeholder character.
+fn symbol_action(name: &str, value: Option<&str>) -> PolarsResult<ngram_rs::SymbolAction> {
+    use ngram_rs::SymbolAction;
+
+    Ok(match value {
+        None | Some("keep") => SymbolAction::Keep,
+        Some("drop") => SymbolAction::Drop,
+        Some(value) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(placeholder), None) => SymbolAction::Placeholder(placeholder),
+                _ => polars_bail!(
+                    InvalidOperation: "`{}` must be \"keep\", \"drop\" or a single placeholder character, got {:?}",
+                    name,
+                    value
+                ),
+            }
+        }
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NGramCountKwargs {
+    #[serde(deserialize_with = "deserialize_n_range")]
+    n_range: Vec<usize>,
+    #[serde(default = "default_tokenizer")]
+    tokenizer: String,
+    #[serde(default)]
+    cast_numeric: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimilarityKwargs {
+    n: usize,
+    #[serde(default)]
+    metric: SimilarityMetric,
+}
+
+/// The set similarity used by `ngram_similarity`.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SimilarityMetric {
+    #[default]
+    Jaccard,
+    Dice,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockingKwargs {
+    #[serde(default = "default_blocking_n")]
+    n: usize,
+    /// Number of keys per string, one per band of MinHash values
+    #[serde(default = "default_num_keys")]
+    num_keys: usize,
+    /// Number of MinHash values combined into each key
+    #[serde(default = "default_band_size")]
+    band_size: usize,
+    #[serde(default)]
+    seed: u64,
+}
+
+fn default_blocking_n() -> usize {
+    3
+}
+
+fn default_num_keys() -> usize {
+    4
+}
+
+fn default_band_size() -> usize {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DetectLanguageKwargs {
+    /// Sample texts of every language
+    #[serde(default)]
+    samples: HashMap<String, Vec<String>>,
+    /// Directory of `LANG.txt` sample files, one per language
+    #[serde(default)]
+    profiles: Option<String>,
+    #[serde(default = "default_detect_n_range")]
+    n_range: Vec<usize>,
+}
+
+fn default_detect_n_range() -> Vec<usize> {
+    vec![1, 2, 3]
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BleuKwargs {
+    #[serde(default = "default_max_n")]
+    max_n: usize,
+    #[serde(default = "default_tokenizer")]
+    tokenizer: String,
+}
+
+fn default_max_n() -> usize {
+    4
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RougeKwargs {
+    n: usize,
+    #[serde(default = "default_tokenizer")]
+    tokenizer: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ByteNGramsKwargs {
+    #[serde(deserialize_with = "deserialize_n_range")]
+    n_range: Vec<usize>,
+}
+
+/// The kwargs of `top_ngrams`: `k` on top of the usual n-gram kwargs.
+#[derive(Debug, Deserialize)]
+pub struct TopNGramsKwargs {
+    k: usize,
+    #[serde(flatten)]
+    ngrams: NGramsKwargs,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkipGramsKwargs {
+    n: usize,
+    k: usize,
+    #[serde(default = "default_delimiter")]
+    delimiter: String,
+    #[serde(default)]
+    cast_numeric: bool,
+}
+
+/// The accepted shapes of the `n_range` kwarg.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NRange {
+    One(usize),
+    List(Vec<usize>),
+    /// Every size from `min` to `max`, inclusive
+    Bounds {
+        min: usize,
+        max: usize,
+    },
+}
+
+/// Reads `n_range` from a single size, a list of sizes or a `{min, max}` mapping.
+fn deserialize_n_range<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<usize>, D::Error> {
+    Ok(match NRange::deserialize(deserializer)? {
+        NRange::One(n) => vec![n],
+        NRange::List(n_range) => n_range,
+        NRange::Bounds { min, max } if min <= max => (min..=max).collect(),
+        NRange::Bounds { min, max } => {
+            return Err(serde::de::Error::custom(format!(
+                "`n_range` min {min} is larger than max {max}"
+            )));
+        }
+    })
 }
 
 fn default_delimiter() -> String {
     " ".to_string()
 }
 
+fn default_null_token() -> String {
+    "<null>".to_string()
+}
+
+fn default_bos() -> String {
+    "<s>".to_string()
+}
+
+fn default_eos() -> String {
+    "</s>".to_string()
+}
+
+fn default_tokenizer() -> String {
+    "whitespace".to_string()
+}
+
+/// Splits the rows of `String` inputs into tokens.
+enum Tokenizer {
+    Whitespace,
+    Unicode,
+    /// Whitespace, with Han and Kana split into characters and Hangul into runs
+    Cjk,
+    /// Every match of the pattern is a token
+    Pattern(Regex),
+}
+
+impl Tokenizer {
+    fn from_kwarg(tokenizer: &str) -> PolarsResult<Self> {
+        Ok(match tokenizer {
+            "whitespace" => Tokenizer::Whitespace,
+            "unicode" => Tokenizer::Unicode,
+            "cjk" => Tokenizer::Cjk,
+            pattern => Tokenizer::Pattern(Regex::new(pattern).map_err(
+                |e| polars_err!(ComputeError: "invalid tokenizer pattern {:?}: {}", pattern, e),
+            )?),
+        })
+    }
+
+    fn tokens<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Whitespace => ngram_rs::tokenize_whitespace(text).collect(),
+            Tokenizer::Unicode => ngram_rs::tokenize_unicode_words(text).collect(),
+            Tokenizer::Cjk => ngram_rs::tokenize_cjk(text, Default::default()).collect(),
+            Tokenizer::Pattern(regex) => regex.find_iter(text).map(|m| m.as_str()).collect(),
+        }
+    }
+}
+
+/// Applies a transform that borrows unchanged text to `text`, keeping the borrow or
+/// the owned string when nothing changes.
+fn map_cow<'a>(text: Cow<'a, str>, transform: impl FnOnce(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match text {
+        Cow::Borrowed(text) => transform(text),
+        Cow::Owned(text) => match transform(&text) {
+            Cow::Owned(changed) => Cow::Owned(changed),
+            Cow::Borrowed(_) => Cow::Owned(text),
+        },
+    }
+}
+
+/// Token normalization, stop-word removal and padding applied before n-grams are
+/// formed.
+struct Preprocessor {
+    placeholders: Option<ngram_rs::Placeholders>,
+    /// The case rules to lowercase with, if tokens are lowercased
+    lowercase: Option<ngram_rs::CaseLocale>,
+    form: Option<ngram_rs::NormalizationForm>,
+    strip_diacritics: bool,
+    stopwords: HashSet<String>,
+    stopword_mode: StopWordMode,
+    phonetic: Option<ngram_rs::PhoneticCode>,
+    /// The BOS and EOS tokens, when rows are padded
+    padding: Option<(String, String)>,
+}
+
+impl Preprocessor {
+    fn from_kwargs(kwargs: &NGramsKwargs) -> PolarsResult<Self> {
+        use ngram_rs::NormalizationForm;
+
+        let form = match kwargs.unicode_normalize.as_deref() {
+            None => None,
+            Some("nfc") => Some(NormalizationForm::Nfc),
+            Some("nfd") => Some(NormalizationForm::Nfd),
+            Some("nfkc") => Some(NormalizationForm::Nfkc),
+            Some("nfkd") => Some(NormalizationForm::Nfkd),
+            Some(form) => polars_bail!(
+                InvalidOperation: "`unicode_normalize` must be \"nfc\", \"nfd\", \"nfkc\" or \"nfkd\", got {:?}",
+                form
+            ),
+        };
+        let stopwords = match &kwargs.stopwords {
+            None => HashSet::new(),
+            Some(StopWords::List(words)) => words.iter().cloned().collect(),
+            Some(StopWords::Builtin(name)) => match crate::stopwords::builtin(name) {
+                Some(words) => words.iter().map(|word| word.to_string()).collect(),
+                None => polars_bail!(
+                    InvalidOperation: "unknown builtin stop-word list {:?}; expected \"english\" or a list of words",
+                    name
+                ),
+            },
+        };
+        let phonetic = match kwargs.phonetic.as_deref() {
+            None => None,
+            Some("soundex") => Some(ngram_rs::PhoneticCode::Soundex),
+            Some("metaphone") => Some(ngram_rs::PhoneticCode::Metaphone),
+            Some(code) => polars_bail!(
+                InvalidOperation: "`phonetic` must be \"soundex\" or \"metaphone\", got {:?}",
+                code
+            ),
+        };
+        // Boundary mode matches stop words inside the final n-grams, where only the
+        // codes are left
+        if phonetic.is_some()
+            && kwargs.stopword_mode == StopWordMode::Boundary
+            && kwargs.stopwords.is_some()
+        {
+            polars_bail!(
+                InvalidOperation: "`phonetic` can't be combined with stopword_mode=\"boundary\""
+            );
+        }
+        // Padding gives every row at least one n-gram per n, so no row is ever short
+        if kwargs.pad && kwargs.short_row != ShortRowPolicy::Empty {
+            polars_bail!(
+                InvalidOperation: "`short_row` can't be combined with `pad`, padded rows are never short"
+            );
+        }
+        let placeholders = match &kwargs.placeholders {
+            Some(placeholders) => placeholders.placeholders()?,
+            None => None,
+        };
+        Ok(Preprocessor {
+            placeholders,
+            lowercase: kwargs.lowercase.then(|| {
+                kwargs
+                    .locale
+                    .as_deref()
+                    .map_or_else(Default::default, ngram_rs::CaseLocale::from_language)
+            }),
+            form,
+            strip_diacritics: kwargs.strip_diacritics,
+            stopwords,
+            stopword_mode: kwargs.stopword_mode,
+            phonetic,
+            padding: kwargs.pad.then(|| (kwargs.bos.clone(), kwargs.eos.clone())),
+        })
+    }
+
+    /// Normalizes every token, borrowing the ones left unchanged, drops stop words in
+    /// `tokens` mode, then replaces the remaining tokens with their phonetic codes,
+    /// dropping the tokens without any.
+    fn tokens<'a, S: AsRef<str>>(&self, tokens: &'a [S]) -> Vec<Cow<'a, str>> {
+        let tokens = tokens.iter().map(|token| self.token(token.as_ref()));
+        let tokens: Vec<Cow<str>> =
+            if self.stopword_mode == StopWordMode::Tokens && !self.stopwords.is_empty() {
+                tokens
+                    .filter(|token| !self.stopwords.contains(token.as_ref()))
+                    .collect()
+            } else {
+                tokens.collect()
+            };
+        match self.phonetic {
+            Some(code) => code
+                .encode_tokens(&tokens)
+                .into_iter()
+                .map(Cow::Owned)
+                .collect(),
+            None => tokens,
+        }
+    }
+
+    /// Replaces numbers, URLs and email addresses with their placeholders, or applies
+    /// the Unicode normalization form, then diacritic stripping, then lowercasing.
+    fn token<'a>(&self, token: &'a str) -> Cow<'a, str> {
+        if let Some(placeholder) = self
+            .placeholders
+            .as_ref()
+            .and_then(|placeholders| placeholders.placeholder(token))
+        {
+            return Cow::Owned(placeholder.to_string());
+        }
+        let mut token = Cow::Borrowed(token);
+        if let Some(form) = self.form {
+            token = map_cow(token, |token| ngram_rs::normalize_unicode(token, form));
+        }
+        if self.strip_diacritics {
+            token = map_cow(token, ngram_rs::strip_diacritics);
+        }
+        match self.lowercase {
+            Some(locale) => map_cow(token, |token| ngram_rs::lowercase(token, locale)),
+            None => token,
+        }
+    }
+
+    /// Returns `tokens` surrounded by `n - 1` BOS and EOS tokens each.
+    fn padded<'a, S: AsRef<str>>(&'a self, tokens: &'a [S], n: usize) -> Vec<&'a str> {
+        let (bos, eos) = self.padding.as_ref().expect("only called when padding");
+        let side = n.saturating_sub(1);
+        let mut padded = Vec::with_capacity(tokens.len() + 2 * side);
+        padded.extend(std::iter::repeat_n(bos.as_str(), side));
+        padded.extend(tokens.iter().map(AsRef::as_ref));
+        padded.extend(std::iter::repeat_n(eos.as_str(), side));
+        padded
+    }
+
+    /// Drops the n-grams starting or ending with a stop word in `boundary` mode.
+    ///
+    /// `ngrams` holds one item per window of `tokens`, in generation order.
+    fn retain_ngrams<S: AsRef<str>, T>(
+        &self,
+        tokens: &[S],
+        n_range: &[usize],
+        ngrams: &mut Vec<T>,
+    ) {
+        if self.stopword_mode != StopWordMode::Boundary || self.stopwords.is_empty() {
+            return;
+        }
+        let mut keep = n_range
+            .iter()
+            .filter(|&&n| n != 0 && n <= tokens.len())
+            .flat_map(|&n| tokens.windows(n))
+            .map(|window| {
+                !self.stopwords.contains(window[0].as_ref())
+                    && !self.stopwords.contains(window[window.len() - 1].as_ref())
+            });
+        ngrams.retain(|_| keep.next().unwrap_or(true));
+    }
+}
+
+/// Raises on the first null row of `input`, for `null_policy="error"`.
+fn ensure_no_nulls(input: &Series) -> PolarsResult<()> {
+    match input
+        .is_null()
+        .into_iter()
+        .position(|null| null == Some(true))
+    {
+        Some(row) => {
+            polars_bail!(ComputeError: "null row at index {} with null_policy=\"error\"", row)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Runs `f` on row slices of `inputs` on the Polars thread pool, one slice per thread,
+/// and concatenates the results.
+///
+/// `f` also receives the index of the first row of its slice. Single-value inputs,
+/// such as a broadcast delimiter, are passed to every slice whole.
+fn par_rows<F>(inputs: &[Series], f: F) -> PolarsResult<Series>
+where
+    F: Fn(&[Series], usize) -> PolarsResult<Series> + Sync,
+{
+    let len = inputs[0].len();
+    let slice_len = len.div_ceil(POOL.current_num_threads()).max(1);
+    if slice_len >= len {
+        return f(inputs, 0);
+    }
+
+    let slices: Vec<(usize, Vec<Series>)> = (0..len)
+        .step_by(slice_len)
+        .map(|offset| {
+            let slice = inputs
+                .iter()
+                .map(|input| match input.len() {
+                    1 => input.clone(),
+                    _ => input.slice(offset as i64, slice_len),
+                })
+                .collect();
+            (offset, slice)
+        })
+        .collect();
+    let parts = POOL.install(|| {
+        slices
+            .par_iter()
+            .map(|(offset, slice)| f(slice, *offset))
+            .collect::<PolarsResult<Vec<_>>>()
+    })?;
+
+    let mut parts = parts.into_iter();
+    let mut out = parts.next().expect("at least one slice");
+    for part in parts {
+        out.append(&part)?;
+    }
+    Ok(out)
+}
+
+/// Runs an n-gram expression, split across threads when `parallel` is set.
+///
+/// Null rows are checked up front so that `null_policy="error"` reports the index of
+/// the row in the whole input, and `min_count` is counted over the whole input, as
+/// each thread only sees its own slice.
+fn run_ngrams<F>(inputs: &[Series], mut kwargs: NGramsKwargs, f: F) -> PolarsResult<Series>
+where
+    F: Fn(&[Series], NGramsKwargs, usize) -> PolarsResult<Series> + Sync,
+{
+    if !kwargs.parallel {
+        return f(inputs, kwargs, 0);
+    }
+    if let NullPolicy::Error = kwargs.null_policy {
+        ensure_no_nulls(&inputs[0])?;
+    }
+    if kwargs.min_count > 1 {
+        let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+        kwargs.frequent = frequent_ngrams(inputs, &kwargs, &preprocessor)?;
+    }
+    par_rows(inputs, |slice, offset| f(slice, kwargs.clone(), offset))
+}
+
+/// Applies `policy` to the rows of `out` whose input row is null.
+fn apply_null_policy(input: &Series, out: Series, policy: NullPolicy) -> PolarsResult<Series> {
+    if input.null_count() == 0 {
+        return Ok(out);
+    }
+    match policy {
+        NullPolicy::Propagate => Ok(out),
+        NullPolicy::Error => {
+            ensure_no_nulls(input)?;
+            Ok(out)
+        }
+        NullPolicy::Skip => {
+            let ca = out.list()?;
+            let empty = Series::new_empty(PlSmallStr::EMPTY, &ca.inner_dtype().clone());
+            let filled: ListChunked = ca
+                .into_iter()
+                .map(|row| Some(row.unwrap_or_else(|| empty.clone())))
+                .collect();
+            Ok(filled.with_name(out.name().clone()).into_series())
+        }
+    }
+}
+
+/// Returns the input as a list or array of strings or categoricals, raising on any
+/// other item type.
+///
+/// Lists of nulls, such as a column of empty lists, are accepted. Numeric items are
+/// cast to strings when `cast_numeric` is set. Arrays keep their width.
+fn string_items(series: &Series, cast_numeric: bool) -> PolarsResult<Series> {
+    let (kind, inner, target) = match series.dtype() {
+        DataType::List(inner) => ("List", inner, DataType::List(Box::new(DataType::String))),
+        DataType::Array(inner, width) => (
+            "Array",
+            inner,
+            DataType::Array(Box::new(DataType::String), *width),
+        ),
+        dtype => polars_bail!(
+            SchemaMismatch: "expected strings, or a list or array of strings, got {}",
+            dtype
+        ),
+    };
+    match inner.as_ref() {
+        dtype if *dtype == DataType::String || is_categorical(dtype) => Ok(series.clone()),
+        dtype if *dtype == DataType::Null || (cast_numeric && dtype.is_primitive_numeric()) => {
+            series.cast(&target)
+        }
+        dtype => polars_bail!(
+            SchemaMismatch: "expected a list of strings, got {}({}); pass cast_numeric=True to cast numbers",
+            kind,
+            dtype
+        ),
+    }
+}
+
+/// Calls `f` with the index and the items of every row of a list or array input, or
+/// `None` for a null row.
+///
+/// Array rows are read in place rather than cast to lists first.
+fn for_each_row<F>(series: &Series, cast_numeric: bool, mut f: F) -> PolarsResult<()>
+where
+    F: FnMut(usize, Option<&Series>) -> PolarsResult<()>,
+{
+    let series = string_items(series, cast_numeric)?;
+    if let Ok(ca) = series.array() {
+        for (row, items) in ca.amortized_iter().enumerate() {
+            f(row, items.as_ref().map(|items| items.as_ref()))?;
+        }
+    } else {
+        for (row, items) in series.list()?.amortized_iter().enumerate() {
+            f(row, items.as_ref().map(|items| items.as_ref()))?;
+        }
+    }
+    Ok(())
+}
+
+fn is_categorical(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::Categorical(_, _) | DataType::Enum(_, _))
+}
+
+/// Resolves the categories of one row to the strings of the category mapping,
+/// without copying them.
+fn categorical_items(series: &Series) -> PolarsResult<Vec<Option<&str>>> {
+    Ok(match series.dtype().cat_physical()? {
+        CategoricalPhysical::U8 => series.cat8()?.iter_str().collect(),
+        CategoricalPhysical::U16 => series.cat16()?.iter_str().collect(),
+        CategoricalPhysical::U32 => series.cat32()?.iter_str().collect(),
+    })
+}
+
+/// Sizes the n-gram buffers for `series`: exactly for list inputs, whose token
+/// counts are known up front, and by estimate for strings, which are tokenized later.
+fn ngram_capacity(series: &Series, n_range: &[usize]) -> usize {
+    if let Ok(ca) = series.array() {
+        return ca.len() * ngram_rs::ngram_count(ca.width(), n_range);
+    }
+    match series.list() {
+        Ok(ca) => ca
+            .downcast_iter()
+            .flat_map(|arr| arr.offsets().lengths())
+            .map(|len| ngram_rs::ngram_count(len, n_range))
+            .sum(),
+        Err(_) => series.len() * 8,
+    }
+}
+
+/// Borrows the non-null tokens of one row of a list of strings or categoricals,
+/// straight from the string or category data.
+fn row_words(series: &Series) -> PolarsResult<Vec<&str>> {
+    if is_categorical(series.dtype()) {
+        return Ok(categorical_items(series)?.into_iter().flatten().collect());
+    }
+    Ok(series.str()?.into_iter().flatten().collect())
+}
+
+/// Borrows the tokens of one row like [`row_words`], keeping null items.
+fn row_items(series: &Series) -> PolarsResult<Vec<Option<&str>>> {
+    if is_categorical(series.dtype()) {
+        return categorical_items(series);
+    }
+    Ok(series.str()?.into_iter().collect())
+}
+
+/// The delimiter of every row: the `delimiter` kwarg, or a second `String` input.
+enum Delimiters<'a> {
+    Fixed(&'a str),
+    /// A column with one delimiter per row, or a single one for every row. Null
+    /// delimiters fall back to the kwarg.
+    Column(&'a StringChunked, &'a str),
+}
+
+impl<'a> Delimiters<'a> {
+    fn new(inputs: &'a [Series], delimiter: &'a str) -> PolarsResult<Self> {
+        let Some(column) = inputs.get(1) else {
+            return Ok(Delimiters::Fixed(delimiter));
+        };
+        let ca = column.str()?;
+        polars_ensure!(
+            ca.len() == 1 || ca.len() == inputs[0].len(),
+            ShapeMismatch: "the delimiter column has {} rows, expected 1 or {}",
+            ca.len(),
+            inputs[0].len()
+        );
+        Ok(Delimiters::Column(ca, delimiter))
+    }
+
+    fn get(&self, row: usize) -> &'a str {
+        match *self {
+            Delimiters::Fixed(delimiter) => delimiter,
+            Delimiters::Column(ca, delimiter) => {
+                let row = if ca.len() == 1 { 0 } else { row };
+                ca.get(row).unwrap_or(delimiter)
+            }
+        }
+    }
+}
+
+/// Generates the n-grams of one run of tokens, after preprocessing them, and returns
+/// the preprocessed tokens.
+///
+/// The `short_row` policy is left to the caller, which sees the whole row.
+fn row_ngrams<'a, S: AsRef<str>>(
+    words: &'a [S],
+    kwargs: &NGramsKwargs,
+    delimiter: &str,
+    preprocessor: &Preprocessor,
+    ngrams: &mut Vec<String>,
+) -> Vec<Cow<'a, str>> {
+    let tokens = preprocessor.tokens(words);
+    if preprocessor.padding.is_none() {
+        ngram_rs::write_ngrams_into(&tokens, &kwargs.n_range, Some(delimiter), ngrams);
+        preprocessor.retain_ngrams(&tokens, &kwargs.n_range, ngrams);
+        return tokens;
+    }
+
+    // Each n is padded differently, so the orders are generated one at a time
+    ngrams.clear();
+    let mut order = Vec::new();
+    for &n in &kwargs.n_range {
+        let padded = preprocessor.padded(&tokens, n);
+        ngram_rs::write_ngrams_into(&padded, &[n], Some(delimiter), &mut order);
+        preprocessor.retain_ngrams(&padded, &[n], &mut order);
+        ngrams.append(&mut order);
+    }
+    tokens
+}
+
+/// Calls `f` with the index and the n-grams of every row, or `None` for a null row.
+///
+/// String inputs are tokenized with the `tokenizer` kwarg; list and array inputs are
+/// read in place. The n-gram buffer is reused from row to row.
+fn for_each_row_ngrams<F>(
+    inputs: &[Series],
+    kwargs: &NGramsKwargs,
+    preprocessor: &Preprocessor,
+    mut f: F,
+) -> PolarsResult<()>
+where
+    F: FnMut(usize, Option<&[String]>) -> PolarsResult<()>,
+{
+    let series = &inputs[0];
+    let delimiters = Delimiters::new(inputs, &kwargs.delimiter)?;
+    let mut ngrams = Vec::new();
+    let mut segment = Vec::new();
+    let mut emit = |row: usize, segments: Option<&[&[&str]]>| {
+        let Some(segments) = segments else {
+            return f(row, None);
+        };
+        let delimiter = delimiters.get(row);
+        let tokens = match segments {
+            [words] => row_ngrams(words, kwargs, delimiter, preprocessor, &mut ngrams),
+            _ => {
+                // The runs between null items are windowed one after the other
+                ngrams.clear();
+                let mut tokens = Vec::new();
+                for words in segments {
+                    tokens.extend(row_ngrams(
+                        words,
+                        kwargs,
+                        delimiter,
+                        preprocessor,
+                        &mut segment,
+                    ));
+                    ngrams.append(&mut segment);
+                }
+                tokens
+            }
+        };
+        // A row is short when all its tokens together, without padding, are fewer
+        // than the smallest n; a short run next to longer ones is not
+        let short = preprocessor.padding.is_none()
+            && kwargs
+                .n_range
+                .iter()
+                .min()
+                .is_some_and(|&n| tokens.len() < n);
+        if short && kwargs.short_row == ShortRowPolicy::Partial && !tokens.is_empty() {
+            ngrams.push(tokens.join(delimiter));
+        }
+        match kwargs.short_row {
+            ShortRowPolicy::Null if short => f(row, None),
+            ShortRowPolicy::Error if short => polars_bail!(
+                ComputeError: "a row has fewer tokens than the smallest n with short_row=\"error\""
+            ),
+            _ => f(row, Some(&ngrams)),
+        }
+    };
+
+    if let Ok(ca) = series.str() {
+        let tokenizer = Tokenizer::from_kwarg(&kwargs.tokenizer)?;
+        for (row, text) in ca.iter().enumerate() {
+            match text {
+                Some(text) => emit(row, Some(&[&tokenizer.tokens(text)[..]]))?,
+                None => emit(row, None)?,
+            }
+        }
+        return Ok(());
+    }
+    for_each_row(series, kwargs.cast_numeric, |row, items| {
+        let Some(items) = items else {
+            return emit(row, None);
+        };
+        match kwargs.inner_nulls {
+            InnerNullPolicy::Skip => emit(row, Some(&[&row_words(items)?[..]])),
+            InnerNullPolicy::Placeholder => {
+                let words: Vec<&str> = row_items(items)?
+                    .into_iter()
+                    .map(|item| item.unwrap_or(&kwargs.null_token))
+                    .collect();
+                emit(row, Some(&[&words[..]]))
+            }
+            InnerNullPolicy::Break => {
+                let items = row_items(items)?;
+                let words: Vec<&str> = items.iter().flatten().copied().collect();
+                let mut segments = Vec::new();
+                let mut start = 0;
+                for run in items.split(Option::is_none).filter(|run| !run.is_empty()) {
+                    segments.push(&words[start..start + run.len()]);
+                    start += run.len();
+                }
+                if segments.is_empty() {
+                    segments.push(&[]);
+                }
+                emit(row, Some(&segments))
+            }
+        }
+    })
+}
+
+/// Hashes the n-grams of one row's tokens, after preprocessing them.
+fn row_hashes<S: AsRef<str>>(
+    words: &[S],
+    kwargs: &NGramsKwargs,
+    preprocessor: &Preprocessor,
+) -> Vec<u64> {
+    let tokens = preprocessor.tokens(words);
+    if preprocessor.padding.is_none() {
+        let mut hashes = ngram_rs::hash_ngrams(&tokens, &kwargs.n_range, kwargs.seed);
+        preprocessor.retain_ngrams(&tokens, &kwargs.n_range, &mut hashes);
+        return hashes;
+    }
+
+    let mut hashes = Vec::new();
+    for &n in &kwargs.n_range {
+        let padded = preprocessor.padded(&tokens, n);
+        let mut order = ngram_rs::hash_ngrams(&padded, &[n], kwargs.seed);
+        preprocessor.retain_ngrams(&padded, &[n], &mut order);
+        hashes.append(&mut order);
+    }
+    hashes
+}
+
+/// Names the struct field holding the n-grams of size `n`.
+fn order_name(n: usize) -> String {
+    match n {
+        1 => "unigrams".to_string(),
+        2 => "bigrams".to_string(),
+        3 => "trigrams".to_string(),
+        n => format!("{n}grams"),
+    }
+}
+
+/// Returns one `List(String)` field per size of `n_range`, raising on repeated sizes.
+fn order_fields(n_range: &[usize]) -> PolarsResult<Vec<Field>> {
+    let mut seen = HashSet::new();
+    n_range
+        .iter()
+        .map(|&n| {
+            polars_ensure!(
+                seen.insert(n),
+                InvalidOperation: "n = {} appears twice in `n_range`, which output=\"struct\" doesn't allow",
+                n
+            );
+            Ok(Field::new(
+                order_name(n).into(),
+                DataType::List(Box::new(DataType::String)),
+            ))
+        })
+        .collect()
+}
+
+/// Generates each size of `n_range` into its own struct field.
+fn ngrams_by_order(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    let fields = order_fields(&kwargs.n_range)?;
+    let orders = kwargs
+        .n_range
+        .iter()
+        .zip(&fields)
+        .map(|(&n, field)| {
+            let order = NGramsKwargs {
+                n_range: vec![n],
+                output: NGramsOutput::List,
+                ..kwargs.clone()
+            };
+            Ok(ngrams_impl(inputs, order)?.with_name(field.name().clone()))
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+
+    let series = &inputs[0];
+    Ok(
+        StructChunked::from_series(series.name().clone(), series.len(), orders.iter())?
+            .into_series(),
+    )
+}
+
 fn ngrams_impl(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    if kwargs.output == NGramsOutput::Struct {
+        return ngrams_by_order(inputs, kwargs);
+    }
     let series = &inputs[0];
-    let ca = series.list()?;
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+    let frequent = frequent_ngrams(inputs, &kwargs, &preprocessor)?;
+
+    let capacity = ngram_capacity(series, &kwargs.n_range);
+    let mut builder = ListStringChunkedBuilder::new(series.name().clone(), series.len(), capacity);
+    for_each_row_ngrams(inputs, &kwargs, &preprocessor, |_, ngrams| {
+        match ngrams {
+            Some(ngrams) => builder.append_values_iter(
+                ngrams
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|ngram| is_frequent(&frequent, ngram)),
+            ),
+            None => builder.append_null(),
+        }
+        Ok(())
+    })?;
 
-    let out: ListChunked = ca.try_apply_amortized(|amort_series| {
-        let series = amort_series.as_ref();
+    apply_null_policy(series, builder.finish().into_series(), kwargs.null_policy)
+}
 
-        if series.is_empty() {
-            return Ok(StringChunked::from_iter(std::iter::empty::<String>()).into_series());
+/// Returns the n-grams occurring at least `min_count` times over all rows, or `None`
+/// when every n-gram is kept.
+///
+/// Reuses the counts of the whole column when the rows were split across threads.
+fn frequent_ngrams(
+    inputs: &[Series],
+    kwargs: &NGramsKwargs,
+    preprocessor: &Preprocessor,
+) -> PolarsResult<Option<Arc<HashSet<String>>>> {
+    if kwargs.min_count <= 1 {
+        return Ok(None);
+    }
+    if let Some(frequent) = &kwargs.frequent {
+        return Ok(Some(Arc::clone(frequent)));
+    }
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for_each_row_ngrams(inputs, kwargs, preprocessor, |_, ngrams| {
+        for ngram in ngrams.unwrap_or_default() {
+            match counts.get_mut(ngram.as_str()) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(ngram.clone(), 1);
+                }
+            }
         }
+        Ok(())
+    })?;
+    Ok(Some(Arc::new(
+        counts
+            .into_iter()
+            .filter(|&(_, count)| count >= kwargs.min_count)
+            .map(|(ngram, _)| ngram)
+            .collect(),
+    )))
+}
+
+fn is_frequent(frequent: &Option<Arc<HashSet<String>>>, ngram: &str) -> bool {
+    frequent
+        .as_ref()
+        .is_none_or(|frequent| frequent.contains(ngram))
+}
 
-        let words_ca = match series.str() {
-            Ok(ca) => ca,
-            Err(_) => {
-                // If we can't get as string, return empty series
-                return Ok(StringChunked::from_iter(std::iter::empty::<String>()).into_series());
+/// Generates the n-grams of every row as rows of their own, tagged with the index of
+/// their input row.
+///
+/// Null rows produce no output rows, unless `null_policy` is "error". Row indexes
+/// start at `first_row`.
+fn ngrams_exploded_impl(
+    inputs: &[Series],
+    kwargs: NGramsKwargs,
+    first_row: usize,
+) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    if let NullPolicy::Error = kwargs.null_policy {
+        ensure_no_nulls(series)?;
+    }
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+    let frequent = frequent_ngrams(inputs, &kwargs, &preprocessor)?;
+    let capacity = ngram_capacity(series, &kwargs.n_range);
+    let mut rows: Vec<IdxSize> = Vec::with_capacity(capacity);
+    let mut builder = StringChunkedBuilder::new("ngram".into(), capacity);
+    for_each_row_ngrams(inputs, &kwargs, &preprocessor, |row, ngrams| {
+        for ngram in ngrams.unwrap_or_default() {
+            if is_frequent(&frequent, ngram) {
+                rows.push((first_row + row) as IdxSize);
+                builder.append_value(ngram);
             }
-        };
+        }
+        Ok(())
+    })?;
+
+    let fields = [
+        IdxCa::from_vec("row".into(), rows).into_series(),
+        builder.finish().into_series(),
+    ];
+    Ok(
+        StructChunked::from_series(series.name().clone(), fields[0].len(), fields.iter())?
+            .into_series(),
+    )
+}
+
+fn ngrams_hashed_impl(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    let mut builder = ListPrimitiveChunkedBuilder::<UInt64Type>::new(
+        series.name().clone(),
+        series.len(),
+        ngram_capacity(series, &kwargs.n_range),
+        DataType::UInt64,
+    );
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+
+    if let Ok(ca) = series.str() {
+        let tokenizer = Tokenizer::from_kwarg(&kwargs.tokenizer)?;
+        for text in ca {
+            match text {
+                Some(text) => builder.append_slice(&row_hashes(
+                    &tokenizer.tokens(text),
+                    &kwargs,
+                    &preprocessor,
+                )),
+                None => builder.append_null(),
+            }
+        }
+    } else {
+        for_each_row(series, kwargs.cast_numeric, |_, words| {
+            match words {
+                Some(words) => {
+                    builder.append_slice(&row_hashes(&row_words(words)?, &kwargs, &preprocessor))
+                }
+                None => builder.append_null(),
+            }
+            Ok(())
+        })?;
+    }
+
+    apply_null_policy(series, builder.finish().into_series(), kwargs.null_policy)
+}
+
+fn ngram_count_fields() -> Vec<Field> {
+    vec![
+        Field::new("ngram".into(), DataType::String),
+        Field::new("count".into(), DataType::UInt32),
+    ]
+}
+
+/// Counts the distinct n-grams of one row into a struct series, in order of first
+/// occurrence.
+fn ngram_count_series(ngrams: &[String]) -> PolarsResult<Series> {
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    let mut distinct: Vec<&str> = Vec::new();
+    let mut counts: Vec<u32> = Vec::new();
+    for ngram in ngrams {
+        match positions.get(ngram.as_str()) {
+            Some(&i) => counts[i] += 1,
+            None => {
+                positions.insert(ngram, distinct.len());
+                distinct.push(ngram);
+                counts.push(1);
+            }
+        }
+    }
+
+    let fields = [
+        StringChunked::from_iter_values("ngram".into(), distinct.into_iter()).into_series(),
+        UInt32Chunked::from_vec("count".into(), counts).into_series(),
+    ];
+    Ok(StructChunked::from_series("".into(), fields[0].len(), fields.iter())?.into_series())
+}
+
+fn ngram_counts_impl(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+    let mut builder = get_list_builder(
+        &DataType::Struct(ngram_count_fields()),
+        ngram_capacity(series, &kwargs.n_range),
+        series.len(),
+        series.name().clone(),
+    );
+    for_each_row_ngrams(inputs, &kwargs, &preprocessor, |_, ngrams| match ngrams {
+        Some(ngrams) => builder.append_series(&ngram_count_series(ngrams)?),
+        None => {
+            builder.append_null();
+            Ok(())
+        }
+    })?;
 
-        let words: Vec<String> = words_ca
+    apply_null_policy(series, builder.finish().into_series(), kwargs.null_policy)
+}
+
+/// Counts the n-grams of all rows together and keeps the `k` most frequent, as a
+/// single row holding a list of `{ngram, count}` structs.
+///
+/// Ties keep the order of first occurrence, and null rows are ignored unless
+/// `null_policy` is "error".
+fn top_ngrams_impl(inputs: &[Series], kwargs: TopNGramsKwargs) -> PolarsResult<Series> {
+    let TopNGramsKwargs { k, ngrams: kwargs } = kwargs;
+    let series = &inputs[0];
+    if let NullPolicy::Error = kwargs.null_policy {
+        ensure_no_nulls(series)?;
+    }
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut counts: Vec<(String, u32)> = Vec::new();
+    for_each_row_ngrams(inputs, &kwargs, &preprocessor, |_, ngrams| {
+        for ngram in ngrams.unwrap_or_default() {
+            match positions.get(ngram.as_str()) {
+                Some(&i) => counts[i].1 += 1,
+                None => {
+                    positions.insert(ngram.clone(), counts.len());
+                    counts.push((ngram.clone(), 1));
+                }
+            }
+        }
+        Ok(())
+    })?;
+    // A stable sort, so that ties stay in order of first occurrence
+    counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    counts.truncate(k);
+
+    let fields = [
+        StringChunked::from_iter_values(
+            "ngram".into(),
+            counts.iter().map(|(ngram, _)| ngram.as_str()),
+        )
+        .into_series(),
+        UInt32Chunked::from_iter_values("count".into(), counts.iter().map(|&(_, count)| count))
+            .into_series(),
+    ];
+    let top = StructChunked::from_series("".into(), counts.len(), fields.iter())?;
+    let mut builder = get_list_builder(
+        &DataType::Struct(ngram_count_fields()),
+        counts.len(),
+        1,
+        series.name().clone(),
+    );
+    builder.append_series(&top.into_series())?;
+    Ok(builder.finish().into_series())
+}
+
+/// Returns the number of non-null items of every row of a list or array input.
+///
+/// Rows are counted from the offsets, or from the width of an array, unless some
+/// items are null.
+fn row_lengths(series: &Series, cast_numeric: bool) -> PolarsResult<Vec<Option<usize>>> {
+    let series = string_items(series, cast_numeric)?;
+    if let Ok(ca) = series.array()
+        && ca.get_inner().null_count() == 0
+    {
+        let width = ca.width();
+        let valid = ca.is_not_null();
+        return Ok(valid
+            .into_no_null_iter()
+            .map(|valid| valid.then_some(width))
+            .collect());
+    }
+    if let Ok(ca) = series.list()
+        && ca.get_inner().null_count() == 0
+    {
+        let valid = ca.is_not_null();
+        return Ok(ca
+            .downcast_iter()
+            .flat_map(|arr| arr.offsets().lengths())
+            .zip(valid.into_no_null_iter())
+            .map(|(len, valid)| valid.then_some(len))
+            .collect());
+    }
+
+    let mut lengths = Vec::with_capacity(series.len());
+    for_each_row(&series, false, |_, items| {
+        lengths.push(items.map(|items| items.len() - items.null_count()));
+        Ok(())
+    })?;
+    Ok(lengths)
+}
+
+/// Counts the n-grams of every row without building them, from the number of tokens.
+fn ngram_count_impl(inputs: &[Series], kwargs: NGramCountKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    let count = |len: usize| ngram_rs::ngram_count(len, &kwargs.n_range) as u32;
+    let ca: UInt32Chunked = if let Ok(ca) = series.str() {
+        let tokenizer = Tokenizer::from_kwarg(&kwargs.tokenizer)?;
+        ca.iter()
+            .map(|text| text.map(|text| count(tokenizer.tokens(text).len())))
+            .collect()
+    } else {
+        row_lengths(series, kwargs.cast_numeric)?
             .into_iter()
-            .flatten()
-            .map(|s| s.to_string())
-            .collect();
+            .map(|len| len.map(count))
+            .collect()
+    };
+    Ok(ca.with_name(series.name().clone()).into_series())
+}
+
+fn ngram_tfidf_fields() -> Vec<Field> {
+    vec![
+        Field::new("ngram".into(), DataType::String),
+        Field::new("tfidf".into(), DataType::Float64),
+    ]
+}
 
-        if words.is_empty() {
-            return Ok(StringChunked::from_iter(std::iter::empty::<String>()).into_series());
+/// Weights the distinct n-grams of every row by TF-IDF, in order of first occurrence.
+///
+/// The weight of an n-gram is its count in the row times its smoothed inverse
+/// document frequency `ln((1 + N) / (1 + df)) + 1`, where `N` is the number of
+/// non-null rows and `df` the number of them containing the n-gram. This matches
+/// scikit-learn's `TfidfVectorizer(smooth_idf=True, norm=None)`.
+fn tfidf_impl(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    let preprocessor = Preprocessor::from_kwargs(&kwargs)?;
+
+    // Document frequencies need the whole column before any row can be weighted, so
+    // the first pass keeps every row as (n-gram id, count) pairs
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    let mut vocab: Vec<String> = Vec::new();
+    let mut document_frequency: Vec<u32> = Vec::new();
+    let mut rows: Vec<Option<Vec<(usize, u32)>>> = Vec::with_capacity(series.len());
+    for_each_row_ngrams(inputs, &kwargs, &preprocessor, |_, ngrams| {
+        let Some(ngrams) = ngrams else {
+            rows.push(None);
+            return Ok(());
+        };
+        let mut positions: HashMap<usize, usize> = HashMap::new();
+        let mut counts: Vec<(usize, u32)> = Vec::new();
+        for ngram in ngrams {
+            let id = match ids.get(ngram.as_str()) {
+                Some(&id) => id,
+                None => {
+                    ids.insert(ngram.clone(), vocab.len());
+                    vocab.push(ngram.clone());
+                    document_frequency.push(0);
+                    vocab.len() - 1
+                }
+            };
+            match positions.get(&id) {
+                Some(&i) => counts[i].1 += 1,
+                None => {
+                    positions.insert(id, counts.len());
+                    counts.push((id, 1));
+                    document_frequency[id] += 1;
+                }
+            }
         }
+        rows.push(Some(counts));
+        Ok(())
+    })?;
 
-        let ngrams = ngram_rs::generate_ngrams_owned(&words, &kwargs.n_range, &kwargs.delimiter);
-        Ok(StringChunked::from_iter(ngrams).into_series())
+    let documents = rows.iter().flatten().count() as f64;
+    let idf: Vec<f64> = document_frequency
+        .iter()
+        .map(|&df| ((1.0 + documents) / (1.0 + df as f64)).ln() + 1.0)
+        .collect();
+    let mut builder = get_list_builder(
+        &DataType::Struct(ngram_tfidf_fields()),
+        rows.iter().flatten().map(Vec::len).sum(),
+        rows.len(),
+        series.name().clone(),
+    );
+    for row in &rows {
+        let Some(counts) = row else {
+            builder.append_null();
+            continue;
+        };
+        let fields = [
+            StringChunked::from_iter_values(
+                "ngram".into(),
+                counts.iter().map(|&(id, _)| vocab[id].as_str()),
+            )
+            .into_series(),
+            Float64Chunked::from_iter_values(
+                "tfidf".into(),
+                counts.iter().map(|&(id, count)| count as f64 * idf[id]),
+            )
+            .into_series(),
+        ];
+        let row = StructChunked::from_series("".into(), counts.len(), fields.iter())?;
+        builder.append_series(&row.into_series())?;
+    }
+
+    apply_null_policy(series, builder.finish().into_series(), kwargs.null_policy)
+}
+
+/// The SplitMix64 finalizer, used to fold MinHash values into one key.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Computes MinHash blocking keys of the character n-grams of every string.
+///
+/// Each of the `num_keys * band_size` hash functions is `hash_ngrams` with its own
+/// seed, and keeps the smallest hash over the n-grams of the string. Every band of
+/// `band_size` minimums is folded into one key together with the band index, so two
+/// strings share a given key with probability `jaccard^band_size`. Strings without
+/// any n-gram get no keys.
+fn blocking_keys_impl(inputs: &[Series], kwargs: BlockingKwargs) -> PolarsResult<Series> {
+    polars_ensure!(
+        kwargs.n > 0 && kwargs.num_keys > 0 && kwargs.band_size > 0,
+        InvalidOperation: "`n`, `num_keys` and `band_size` must be positive"
+    );
+    let ca = inputs[0].str()?;
+    let mut builder = ListPrimitiveChunkedBuilder::<UInt64Type>::new(
+        ca.name().clone(),
+        ca.len(),
+        ca.len() * kwargs.num_keys,
+        DataType::UInt64,
+    );
+    let mut keys = Vec::with_capacity(kwargs.num_keys);
+    for text in ca {
+        let Some(text) = text else {
+            builder.append_null();
+            continue;
+        };
+        let ngrams = ngram_rs::generate_char_ngrams(text, &[kwargs.n]);
+        keys.clear();
+        if !ngrams.is_empty() {
+            for band in 0..kwargs.num_keys {
+                let mut key = mix(band as u64);
+                for i in 0..kwargs.band_size {
+                    let seed = kwargs
+                        .seed
+                        .wrapping_add((band * kwargs.band_size + i) as u64);
+                    let min = ngram_rs::hash_ngrams(&ngrams, &[1], seed)
+                        .into_iter()
+                        .min()
+                        .unwrap_or_default();
+                    key = mix(key ^ min);
+                }
+                keys.push(key);
+            }
+        }
+        builder.append_slice(&keys);
+    }
+
+    Ok(builder.finish().into_series())
+}
+
+/// Returns the number of rows of two compared columns, one of which may be a single
+/// row broadcast to the other.
+fn paired_len(a: usize, b: usize) -> PolarsResult<usize> {
+    let len = a.max(b);
+    polars_ensure!(
+        (a == len || a == 1) && (b == len || b == 1),
+        ShapeMismatch: "cannot compare columns of {} and {} rows",
+        a,
+        b
+    );
+    Ok(len)
+}
+
+/// The n-grams compared by `ngram_similarity`, one set per row.
+enum SimilarityRows<'a> {
+    /// Character n-grams, taken from each string as it is compared
+    Chars(&'a StringChunked, usize),
+    /// Word n-grams of each list or array row
+    Words(Vec<Option<Vec<String>>>),
+}
+
+impl<'a> SimilarityRows<'a> {
+    fn new(series: &'a Series, n: usize) -> PolarsResult<Self> {
+        if let Ok(ca) = series.str() {
+            return Ok(SimilarityRows::Chars(ca, n));
+        }
+        let mut rows = Vec::with_capacity(series.len());
+        for_each_row(series, false, |_, words| {
+            rows.push(match words {
+                Some(words) => Some(ngram_rs::generate_ngrams_owned(
+                    &row_words(words)?,
+                    &[n],
+                    " ",
+                )),
+                None => None,
+            });
+            Ok(())
+        })?;
+        Ok(SimilarityRows::Words(rows))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SimilarityRows::Chars(ca, _) => ca.len(),
+            SimilarityRows::Words(rows) => rows.len(),
+        }
+    }
+
+    /// The n-grams of `row`, or of the only row of a single-row input.
+    fn get(&self, row: usize) -> Option<Vec<&str>> {
+        let row = if self.len() == 1 { 0 } else { row };
+        match self {
+            SimilarityRows::Chars(ca, n) => ca
+                .get(row)
+                .map(|text| ngram_rs::generate_char_ngrams(text, &[*n])),
+            SimilarityRows::Words(rows) => rows[row]
+                .as_ref()
+                .map(|ngrams| ngrams.iter().map(String::as_str).collect()),
+        }
+    }
+}
+
+/// Scores the n-grams of paired rows of two columns, broadcasting a single-row input.
+///
+/// Strings are compared by character n-grams and lists or arrays by word n-grams.
+/// A row is null if either side is null.
+fn ngram_similarity_impl(inputs: &[Series], kwargs: SimilarityKwargs) -> PolarsResult<Series> {
+    polars_ensure!(
+        kwargs.n > 0,
+        InvalidOperation: "`n` must be positive"
+    );
+    let a = SimilarityRows::new(&inputs[0], kwargs.n)?;
+    let b = SimilarityRows::new(&inputs[1], kwargs.n)?;
+
+    let ca: Float64Chunked = (0..paired_len(a.len(), b.len())?)
+        .map(|row| {
+            let (a, b) = (a.get(row)?, b.get(row)?);
+            Some(match kwargs.metric {
+                SimilarityMetric::Jaccard => ngram_rs::jaccard_similarity(a, b),
+                SimilarityMetric::Dice => ngram_rs::dice_similarity(a, b),
+            })
+        })
+        .collect();
+    Ok(ca.with_name(inputs[0].name().clone()).into_series())
+}
+
+/// The tokens of every row of a column scored against another: tokenized strings, or
+/// the items of lists and arrays.
+enum TokenRows<'a> {
+    Text(&'a StringChunked, Tokenizer),
+    Items(Vec<Option<Vec<String>>>),
+}
+
+impl<'a> TokenRows<'a> {
+    fn new(series: &'a Series, tokenizer: &str) -> PolarsResult<Self> {
+        if let Ok(ca) = series.str() {
+            return Ok(TokenRows::Text(ca, Tokenizer::from_kwarg(tokenizer)?));
+        }
+        let mut rows = Vec::with_capacity(series.len());
+        for_each_row(series, false, |_, words| {
+            rows.push(match words {
+                Some(words) => Some(row_words(words)?.into_iter().map(str::to_string).collect()),
+                None => None,
+            });
+            Ok(())
+        })?;
+        Ok(TokenRows::Items(rows))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            TokenRows::Text(ca, _) => ca.len(),
+            TokenRows::Items(rows) => rows.len(),
+        }
+    }
+
+    /// The tokens of `row`, or of the only row of a single-row input.
+    fn get(&self, row: usize) -> Option<Vec<&str>> {
+        let row = if self.len() == 1 { 0 } else { row };
+        match self {
+            TokenRows::Text(ca, tokenizer) => ca.get(row).map(|text| tokenizer.tokens(text)),
+            TokenRows::Items(rows) => rows[row]
+                .as_ref()
+                .map(|tokens| tokens.iter().map(String::as_str).collect()),
+        }
+    }
+}
+
+/// Scores every candidate row against its reference row with `score`, broadcasting a
+/// single-row input. A row is null if either side is null.
+fn score_pairs<T, F>(inputs: &[Series], tokenizer: &str, score: F) -> PolarsResult<Vec<Option<T>>>
+where
+    F: Fn(&[&str], &[&str]) -> T,
+{
+    let candidates = TokenRows::new(&inputs[0], tokenizer)?;
+    let references = TokenRows::new(&inputs[1], tokenizer)?;
+    Ok((0..paired_len(candidates.len(), references.len())?)
+        .map(|row| Some(score(&candidates.get(row)?, &references.get(row)?)))
+        .collect())
+}
+
+fn bleu_impl(inputs: &[Series], kwargs: BleuKwargs) -> PolarsResult<Series> {
+    polars_ensure!(
+        kwargs.max_n > 0,
+        InvalidOperation: "`max_n` must be positive"
+    );
+    let scores = score_pairs(inputs, &kwargs.tokenizer, |candidate, reference| {
+        ngram_rs::bleu(candidate, reference, kwargs.max_n)
     })?;
+    Ok(
+        Float64Chunked::from_iter_options(inputs[0].name().clone(), scores.into_iter())
+            .into_series(),
+    )
+}
+
+/// Builds a struct column from `fields`, null as a whole on the rows where `valid` is
+/// false rather than only in its fields.
+fn struct_with_nulls(
+    name: PlSmallStr,
+    fields: &[Series],
+    valid: impl Iterator<Item = bool>,
+) -> PolarsResult<Series> {
+    let len = fields.first().map_or(0, |field| field.len());
+    let ca = StructChunked::from_series(name.clone(), len, fields.iter())?;
+    let valid = BooleanChunked::from_iter_values(name.clone(), valid);
+    let nulls = Series::full_null(name, len, ca.dtype());
+    ca.into_series().zip_with(&valid, &nulls)
+}
+
+fn rouge_fields() -> Vec<Field> {
+    vec![
+        Field::new("precision".into(), DataType::Float64),
+        Field::new("recall".into(), DataType::Float64),
+        Field::new("f1".into(), DataType::Float64),
+    ]
+}
+
+fn rouge_n_impl(inputs: &[Series], kwargs: RougeKwargs) -> PolarsResult<Series> {
+    polars_ensure!(
+        kwargs.n > 0,
+        InvalidOperation: "`n` must be positive"
+    );
+    let scores = score_pairs(inputs, &kwargs.tokenizer, |candidate, reference| {
+        ngram_rs::rouge_n(candidate, reference, kwargs.n)
+    })?;
+    let field = |name: &str, value: fn(&ngram_rs::RougeScore) -> f64| {
+        Float64Chunked::from_iter_options(name.into(), scores.iter().map(|s| s.as_ref().map(value)))
+            .into_series()
+    };
+    let fields = [
+        field("precision", |s| s.precision),
+        field("recall", |s| s.recall),
+        field("f1", |s| s.f1),
+    ];
+    struct_with_nulls(
+        inputs[0].name().clone(),
+        &fields,
+        scores.iter().map(Option::is_some),
+    )
+}
 
-    Ok(out.into_series())
+/// Trains a language detector from the `samples` and `profiles` kwargs.
+fn language_detector(kwargs: &DetectLanguageKwargs) -> PolarsResult<ngram_rs::LanguageDetector> {
+    let mut detector = ngram_rs::LanguageDetector::new(&kwargs.n_range);
+    for (language, samples) in &kwargs.samples {
+        for sample in samples {
+            detector.add_sample(language, sample);
+        }
+    }
+    if let Some(dir) = &kwargs.profiles {
+        let io_error = |e: std::io::Error| polars_err!(ComputeError: "{}: {}", dir, e);
+        for entry in std::fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                let language = path.file_stem().unwrap_or_default().to_string_lossy();
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| polars_err!(ComputeError: "{}: {}", path.display(), e))?;
+                detector.add_sample(&language, &text);
+            }
+        }
+    }
+    polars_ensure!(
+        detector.languages().next().is_some(),
+        InvalidOperation: "no language profiles: pass `samples` or a `profiles` directory of LANG.txt files"
+    );
+    Ok(detector)
 }
 
-fn output_type_list_string(_input_fields: &[Field]) -> PolarsResult<Field> {
+fn detect_language_fields() -> Vec<Field> {
+    vec![
+        Field::new("lang".into(), DataType::String),
+        Field::new("confidence".into(), DataType::Float64),
+    ]
+}
+
+/// Detects the most likely language of every string, with its posterior probability.
+fn detect_language_impl(inputs: &[Series], kwargs: DetectLanguageKwargs) -> PolarsResult<Series> {
+    let ca = inputs[0].str()?;
+    let detector = language_detector(&kwargs)?;
+    let detected: Vec<Option<(&str, f64)>> = ca
+        .iter()
+        .map(|text| text.and_then(|text| detector.detect(text)))
+        .collect();
+
+    let fields = [
+        StringChunked::from_iter_options(
+            "lang".into(),
+            detected.iter().map(|d| d.map(|(lang, _)| lang)),
+        )
+        .into_series(),
+        Float64Chunked::from_iter_options(
+            "confidence".into(),
+            detected.iter().map(|d| d.map(|(_, confidence)| confidence)),
+        )
+        .into_series(),
+    ];
+    struct_with_nulls(
+        ca.name().clone(),
+        &fields,
+        detected.iter().map(Option::is_some),
+    )
+}
+
+fn skipgrams_impl(inputs: &[Series], kwargs: SkipGramsKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    // Skip-grams outnumber the plain n-grams, which makes them a lower bound
+    let capacity = ngram_capacity(series, &[kwargs.n]);
+    let mut builder = ListStringChunkedBuilder::new(series.name().clone(), series.len(), capacity);
+    let mut skipgrams = Vec::new();
+    for_each_row(series, kwargs.cast_numeric, |_, words| {
+        let Some(words) = words else {
+            builder.append_null();
+            return Ok(());
+        };
+        ngram_rs::write_skipgrams_into(
+            &row_words(words)?,
+            kwargs.n,
+            kwargs.k,
+            Some(&kwargs.delimiter),
+            &mut skipgrams,
+        );
+        builder.append_values_iter(skipgrams.iter().map(String::as_str));
+        Ok(())
+    })?;
+
+    Ok(builder.finish().into_series())
+}
+
+/// Generates the byte n-grams of every value of a `Binary` or `String` column.
+fn byte_ngrams_impl(inputs: &[Series], kwargs: ByteNGramsKwargs) -> PolarsResult<Series> {
+    let series = &inputs[0];
+    let ca = match series.dtype() {
+        DataType::String => series.str()?.as_binary(),
+        _ => series.binary()?.clone(),
+    };
+    let capacity = ca
+        .iter()
+        .map(|bytes| ngram_rs::ngram_count(bytes.map_or(0, <[u8]>::len), &kwargs.n_range))
+        .sum();
+    let mut builder = ListBinaryChunkedBuilder::new(ca.name().clone(), ca.len(), capacity);
+    for bytes in &ca {
+        match bytes {
+            Some(bytes) => builder.append_values_iter(
+                ngram_rs::generate_byte_ngrams(bytes, &kwargs.n_range).into_iter(),
+            ),
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish().into_series())
+}
+
+fn char_ngrams_impl(inputs: &[Series], kwargs: CharNGramsKwargs) -> PolarsResult<Series> {
+    let ca = inputs[0].str()?;
+    let pad = match kwargs.pad.as_deref() {
+        Some(pad) => {
+            let mut chars = pad.chars();
+            match (chars.next(), chars.next()) {
+                (Some(pad), None) => Some(pad),
+                _ => polars_bail!(
+                    InvalidOperation: "`pad` must be a single character, got {:?}",
+                    pad
+                ),
+            }
+        }
+        None => None,
+    };
+
+    let policy = ngram_rs::SymbolPolicy {
+        punctuation: symbol_action("punctuation", kwargs.punctuation.as_deref())?,
+        whitespace: symbol_action("whitespace", kwargs.whitespace.as_deref())?,
+        emoji: symbol_action("emoji", kwargs.emoji.as_deref())?,
+    };
+
+    let windows = if kwargs.grapheme {
+        ngram_rs::generate_grapheme_ngrams
+    } else {
+        ngram_rs::generate_char_ngrams
+    };
+
+    let mut builder = ListStringChunkedBuilder::new(ca.name().clone(), ca.len(), ca.len() * 8);
+    let mut padded = String::new();
+    let mut segments = Vec::with_capacity(kwargs.n_range.len());
+    for text in ca {
+        let Some(text) = text else {
+            builder.append_null();
+            continue;
+        };
+        let text = policy.apply(text);
+        let text = text.as_ref();
+        match pad {
+            None => builder.append_values_iter(windows(text, &kwargs.n_range).into_iter()),
+            Some(pad) => {
+                // Each n gets its own padding, so every character starts and ends n
+                // n-grams. The padded texts share one buffer that the n-grams borrow.
+                padded.clear();
+                segments.clear();
+                for &n in &kwargs.n_range {
+                    let start = padded.len();
+                    let side = n.saturating_sub(1);
+                    padded.extend(std::iter::repeat_n(pad, side));
+                    padded.push_str(text);
+                    padded.extend(std::iter::repeat_n(pad, side));
+                    segments.push((n, start..padded.len()));
+                }
+                builder.append_values_iter(
+                    segments
+                        .iter()
+                        .flat_map(|(n, range)| windows(&padded[range.clone()], &[*n])),
+                );
+            }
+        }
+    }
+
+    Ok(builder.finish().into_series())
+}
+
+/// Keeps the input column name, so the expressions never rename a column.
+fn output_type_list_string(input_fields: &[Field]) -> PolarsResult<Field> {
     Ok(Field::new(
-        "ngrams".into(),
+        input_fields[0].name().clone(),
         DataType::List(Box::new(DataType::String)),
     ))
 }
 
-#[polars_expr(output_type_func = output_type_list_string)]
+fn output_type_ngrams(input_fields: &[Field], kwargs: NGramsKwargs) -> PolarsResult<Field> {
+    match kwargs.output {
+        NGramsOutput::List => output_type_list_string(input_fields),
+        NGramsOutput::Struct => Ok(Field::new(
+            input_fields[0].name().clone(),
+            DataType::Struct(order_fields(&kwargs.n_range)?),
+        )),
+    }
+}
+
+#[polars_expr(output_type_func_with_kwargs = output_type_ngrams)]
 fn ngrams(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
-    ngrams_impl(inputs, kwargs)
+    run_ngrams(inputs, kwargs, |inputs, kwargs, _| {
+        ngrams_impl(inputs, kwargs)
+    })
+}
+
+fn output_type_ngram_counts(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Struct(ngram_count_fields()))),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_ngram_counts)]
+fn ngram_counts(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    run_ngrams(inputs, kwargs, |inputs, kwargs, _| {
+        ngram_counts_impl(inputs, kwargs)
+    })
+}
+
+#[polars_expr(output_type_func = output_type_ngram_counts)]
+fn top_ngrams(inputs: &[Series], kwargs: TopNGramsKwargs) -> PolarsResult<Series> {
+    top_ngrams_impl(inputs, kwargs)
+}
+
+fn output_type_tfidf(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Struct(ngram_tfidf_fields()))),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_tfidf)]
+fn tfidf(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    // Never split across threads: the weights of every row depend on the whole column
+    tfidf_impl(inputs, kwargs)
+}
+
+fn output_type_list_u64(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::UInt64)),
+    ))
+}
+
+fn output_type_ngrams_exploded(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("row".into(), IDX_DTYPE),
+            Field::new("ngram".into(), DataType::String),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_ngrams_exploded)]
+fn ngrams_exploded(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    run_ngrams(inputs, kwargs, ngrams_exploded_impl)
+}
+
+#[polars_expr(output_type_func = output_type_list_u64)]
+fn ngrams_hashed(inputs: &[Series], kwargs: NGramsKwargs) -> PolarsResult<Series> {
+    run_ngrams(inputs, kwargs, |inputs, kwargs, _| {
+        ngrams_hashed_impl(inputs, kwargs)
+    })
+}
+
+#[polars_expr(output_type_func = output_type_list_string)]
+fn char_ngrams(inputs: &[Series], kwargs: CharNGramsKwargs) -> PolarsResult<Series> {
+    char_ngrams_impl(inputs, kwargs)
+}
+
+#[polars_expr(output_type_func = output_type_list_string)]
+fn skipgrams(inputs: &[Series], kwargs: SkipGramsKwargs) -> PolarsResult<Series> {
+    skipgrams_impl(inputs, kwargs)
+}
+
+fn output_type_similarity(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Float64,
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_similarity)]
+fn ngram_similarity(inputs: &[Series], kwargs: SimilarityKwargs) -> PolarsResult<Series> {
+    ngram_similarity_impl(inputs, kwargs)
+}
+
+fn output_type_bleu(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Float64,
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_bleu)]
+fn bleu(inputs: &[Series], kwargs: BleuKwargs) -> PolarsResult<Series> {
+    bleu_impl(inputs, kwargs)
+}
+
+fn output_type_rouge_n(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(rouge_fields()),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_rouge_n)]
+fn rouge_n(inputs: &[Series], kwargs: RougeKwargs) -> PolarsResult<Series> {
+    rouge_n_impl(inputs, kwargs)
+}
+
+#[polars_expr(output_type_func = output_type_list_u64)]
+fn blocking_keys(inputs: &[Series], kwargs: BlockingKwargs) -> PolarsResult<Series> {
+    blocking_keys_impl(inputs, kwargs)
+}
+
+fn output_type_detect_language(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(detect_language_fields()),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_detect_language)]
+fn detect_language(inputs: &[Series], kwargs: DetectLanguageKwargs) -> PolarsResult<Series> {
+    detect_language_impl(inputs, kwargs)
+}
+
+fn output_type_ngram_count(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(input_fields[0].name().clone(), DataType::UInt32))
+}
+
+#[polars_expr(output_type_func = output_type_ngram_count)]
+fn ngram_count(inputs: &[Series], kwargs: NGramCountKwargs) -> PolarsResult<Series> {
+    ngram_count_impl(inputs, kwargs)
+}
+
+fn output_type_list_binary(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Binary)),
+    ))
+}
+
+#[polars_expr(output_type_func = output_type_list_binary)]
+fn byte_ngrams(inputs: &[Series], kwargs: ByteNGramsKwargs) -> PolarsResult<Series> {
+    byte_ngrams_impl(inputs, kwargs)
 }
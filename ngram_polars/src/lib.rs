@@ -1,6 +1,7 @@
 use pyo3_polars::PolarsAllocator;
 
 mod expression;
+mod stopwords;
 
 #[global_allocator]
 static ALLOC: PolarsAllocator = PolarsAllocator::new();
@@ -1,6 +1,25 @@
+import math
+
 import polars as pl
+import pytest
 from polars.testing import assert_series_equal, assert_frame_equal
-from ngram_polars import ngrams
+from ngram_polars import (
+    bleu,
+    blocking_keys,
+    byte_ngrams,
+    char_ngrams,
+    detect_language,
+    ngram_count,
+    ngram_counts,
+    ngram_similarity,
+    ngrams,
+    ngrams_exploded,
+    ngrams_hashed,
+    rouge_n,
+    skipgrams,
+    tfidf,
+    top_ngrams,
+)
 
 
 def test_basic_bigrams():
@@ -176,3 +195,673 @@ def test_groupby_aggregation():
         .sort(by=["group", "ngrams"])
     )
     assert_frame_equal(result, expected)
+
+
+def test_ngram_counts():
+    df = pl.DataFrame({"words": [["a", "b", "a", "b", "a"], []]})
+    result = df.select(ngram_counts(pl.col("words"), n_range=[2]).alias("counts"))
+    expected = pl.Series(
+        "counts",
+        [
+            [{"ngram": "a b", "count": 2}, {"ngram": "b a", "count": 2}],
+            [],
+        ],
+        dtype=pl.List(pl.Struct({"ngram": pl.String, "count": pl.UInt32})),
+    )
+    assert_series_equal(result["counts"], expected)
+
+
+def test_ngram_counts_matches_ngrams():
+    df = pl.DataFrame(
+        {
+            "text": ["to be or not to be", "a", None],
+            "words": [["x", None, "y", "x", None, "y"], ["z"], ["x", "y"]],
+            "sep": ["_", "-", "+"],
+        }
+    )
+    result = df.select(
+        ngram_counts(pl.col("text"), n_range=[2], tokenizer="unicode", short_row="null"),
+        ngram_counts(pl.col("words"), n_range=[2], inner_nulls="break", delimiter=pl.col("sep")),
+    )
+    assert result["text"].to_list() == [
+        [
+            {"ngram": "to be", "count": 2},
+            {"ngram": "be or", "count": 1},
+            {"ngram": "or not", "count": 1},
+            {"ngram": "not to", "count": 1},
+        ],
+        None,
+        None,
+    ]
+    assert result["words"].to_list() == [
+        [{"ngram": "y_x", "count": 1}],
+        [],
+        [{"ngram": "x+y", "count": 1}],
+    ]
+
+    placeholder = df.select(
+        ngram_counts(pl.col("words"), n_range=[2], inner_nulls="placeholder", null_token="_")
+    )
+    assert placeholder["words"][0].to_list()[:2] == [
+        {"ngram": "x _", "count": 2},
+        {"ngram": "_ y", "count": 2},
+    ]
+
+
+def test_char_ngrams():
+    df = pl.DataFrame({"name": ["café", "a", None]})
+    result = df.select(
+        plain=char_ngrams(pl.col("name"), n_range=[2]),
+        padded=char_ngrams(pl.col("name"), n_range=[3], pad="#"),
+    )
+    assert result["plain"].to_list() == [["ca", "af", "fé"], [], None]
+    assert result["padded"].to_list() == [
+        ["##c", "#ca", "caf", "afé", "fé#", "é##"],
+        ["##a", "#a#", "a##"],
+        None,
+    ]
+
+
+def test_char_ngrams_grapheme():
+    flag = "\U0001f1eb\U0001f1f7"
+    df = pl.DataFrame({"text": [f"a{flag}n\u0303", None]})
+    result = df.select(
+        chars=char_ngrams(pl.col("text"), n_range=[2]),
+        graphemes=char_ngrams(pl.col("text"), n_range=[2], grapheme=True),
+    )
+    assert len(result["chars"][0]) == 4
+    assert result["graphemes"].to_list() == [[f"a{flag}", f"{flag}n\u0303"], None]
+
+
+def test_char_ngrams_symbols():
+    df = pl.DataFrame({"text": ["O'Neil  rocks! \U0001f918\U0001f918"]})
+    result = df.select(
+        char_ngrams(
+            pl.col("text"), n_range=[1], punctuation="drop", whitespace="_", emoji="*"
+        )
+    )
+    assert result["text"].to_list() == [list("ONeil_rocks_*")]
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="emoji"):
+        df.select(char_ngrams(pl.col("text"), emoji="skip"))
+
+
+def test_skipgrams():
+    df = pl.DataFrame({"words": [["a", "b", "c", "d"], ["solo"]]})
+    result = df.select(skipgrams(pl.col("words"), n=2, k=1, delimiter="_").alias("skip"))
+    expected = pl.Series("skip", [["a_b", "a_c", "b_c", "b_d", "c_d"], []])
+    assert_series_equal(result["skip"], expected)
+
+
+def test_string_column_tokenizers():
+    df = pl.DataFrame({"text": ["Hello, world!  Bye", None]})
+    result = df.select(
+        ws=ngrams(pl.col("text"), n_range=[2]),
+        uni=ngrams(pl.col("text"), n_range=[2], tokenizer="unicode"),
+        rx=ngrams(pl.col("text"), n_range=[1], tokenizer=r"[a-z]+"),
+    )
+    assert result["ws"].to_list() == [["Hello, world!", "world! Bye"], None]
+    assert result["uni"].to_list() == [["Hello world", "world Bye"], None]
+    assert result["rx"].to_list() == [["ello", "world", "ye"], None]
+
+
+def test_cjk_tokenizer():
+    df = pl.DataFrame({"text": ["東京タワーに行く Tokyo", "서울에서 만나요"]})
+    result = df.select(ngrams(pl.col("text"), n_range=[2], tokenizer="cjk"))
+    assert result["text"].to_list() == [
+        ["東 京", "京 タ", "タ ワ", "ワ ー", "ー に", "に 行", "行 く", "く Tokyo"],
+        ["서울에서 만나요"],
+    ]
+
+
+def test_null_policy():
+    df = pl.DataFrame({"words": [["a", "b"], None]})
+    for policy, second in [("propagate", None), ("skip", [])]:
+        result = df.select(ngrams(pl.col("words"), n_range=[2], null_policy=policy))
+        assert result["words"].to_list() == [["a b"], second]
+
+    with pytest.raises(pl.exceptions.ComputeError, match="index 1"):
+        df.select(ngrams(pl.col("words"), n_range=[2], null_policy="error"))
+
+
+def test_output_keeps_input_name():
+    lf = pl.LazyFrame({"tokens": [["a", "b"]], "text": ["a b"]})
+    result = lf.select(
+        ngrams(pl.col("tokens"), n_range=[2]),
+        char_ngrams(pl.col("text"), n_range=[2]),
+    )
+    assert result.collect_schema() == pl.Schema(
+        {"tokens": pl.List(pl.String), "text": pl.List(pl.String)}
+    )
+    assert result.collect().columns == ["tokens", "text"]
+
+
+def test_non_string_items_raise_unless_cast():
+    df = pl.DataFrame({"ids": [[1, 2, 3]]})
+    with pytest.raises(pl.exceptions.SchemaError, match="List\\(i64\\)"):
+        df.select(ngrams(pl.col("ids"), n_range=[2]))
+
+    result = df.select(ngrams(pl.col("ids"), n_range=[2], cast_numeric=True))
+    assert result["ids"].to_list() == [["1 2", "2 3"]]
+
+
+def test_categorical_items():
+    words = [["a", "b", "a"], None, ["c"]]
+    for dtype in (pl.List(pl.Categorical), pl.List(pl.Enum(["a", "b", "c"]))):
+        df = pl.DataFrame({"words": pl.Series(words, dtype=dtype)})
+        result = df.select(
+            ngrams(pl.col("words"), n_range=[2]).alias("ngrams"),
+            ngram_counts(pl.col("words"), n_range=[1]).alias("counts"),
+            skipgrams(pl.col("words"), n=2, k=1).alias("skipgrams"),
+        )
+        assert result["ngrams"].to_list() == [["a b", "b a"], None, []]
+        assert result["counts"].to_list()[0] == [
+            {"ngram": "a", "count": 2},
+            {"ngram": "b", "count": 1},
+        ]
+        assert result["skipgrams"].to_list()[0] == ["a b", "a a", "b a"]
+
+
+
+def test_array_items():
+    words = [["a", "b", "a"], None, ["c", None, "d"]]
+    df = pl.DataFrame({"words": pl.Series(words, dtype=pl.Array(pl.String, 3))})
+    result = df.select(
+        ngrams(pl.col("words"), n_range=[2]).alias("ngrams"),
+        ngrams_hashed(pl.col("words"), n_range=[2]).alias("hashed"),
+        ngram_counts(pl.col("words"), n_range=[1]).alias("counts"),
+        skipgrams(pl.col("words"), n=2, k=1).alias("skipgrams"),
+    )
+    assert result.schema["ngrams"] == pl.List(pl.String)
+    assert result["ngrams"].to_list() == [["a b", "b a"], None, ["c d"]]
+    listed = df.select(ngrams_hashed(pl.col("words").arr.to_list(), n_range=[2]))
+    assert result["hashed"].to_list() == listed["words"].to_list()
+    assert result["counts"].to_list()[0] == [
+        {"ngram": "a", "count": 2},
+        {"ngram": "b", "count": 1},
+    ]
+    assert result["skipgrams"].to_list()[0] == ["a b", "a a", "b a"]
+
+    numbers = pl.DataFrame({"n": pl.Series([[1, 2]], dtype=pl.Array(pl.Int64, 2))})
+    result = numbers.select(ngrams(pl.col("n"), n_range=[2], cast_numeric=True))
+    assert result["n"].to_list() == [["1 2"]]
+
+
+def test_ngrams_hashed():
+    df = pl.DataFrame({"words": [["a", "b", "a", "b"], None], "text": ["a b a b", None]})
+    result = df.select(
+        ngrams_hashed(pl.col("words"), n_range=[2]),
+        ngrams_hashed(pl.col("text"), n_range=[2]),
+        ngrams_hashed(pl.col("words"), n_range=[2], seed=1).alias("seeded"),
+    )
+    assert result.schema["words"] == pl.List(pl.UInt64)
+    hashes = result["words"].to_list()
+    assert hashes[1] is None
+    assert len(hashes[0]) == 3
+    assert hashes[0][0] == hashes[0][2] != hashes[0][1]
+    assert result["text"].to_list() == hashes
+    assert result["seeded"].to_list()[0] != hashes[0]
+
+
+def test_lowercase_and_unicode_normalize():
+    df = pl.DataFrame({"words": [["Caf\u00e9", "CAFE\u0301"]], "text": ["Caf\u00e9 CAFE\u0301"]})
+    result = df.select(
+        ngrams(pl.col("words"), n_range=[1], lowercase=True, unicode_normalize="nfc"),
+        ngrams(pl.col("text"), n_range=[1], lowercase=True, unicode_normalize="nfc"),
+        ngram_counts(pl.col("words"), lowercase=True, unicode_normalize="nfc").alias("counts"),
+    )
+    assert result["words"].to_list() == [["caf\u00e9", "caf\u00e9"]]
+    assert result["text"].to_list() == [["caf\u00e9", "caf\u00e9"]]
+    assert result["counts"].to_list() == [[{"ngram": "caf\u00e9", "count": 2}]]
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="unicode_normalize"):
+        df.select(ngrams(pl.col("words"), unicode_normalize="nfx"))
+
+
+def test_strip_diacritics():
+    df = pl.DataFrame({"text": ["Crème BRÛLÉE cafe\u0301"]})
+    result = df.select(
+        ngrams(pl.col("text"), n_range=[1], lowercase=True, strip_diacritics=True)
+    )
+    assert result["text"].to_list() == [["creme", "brulee", "cafe"]]
+
+
+def test_placeholders():
+    df = pl.DataFrame({"text": ["Pay 12.50 at https://shop.example or ask bob@example.com"]})
+    result = df.select(
+        default=ngrams(pl.col("text"), n_range=[1], placeholders=True, lowercase=True),
+        custom=ngrams(
+            pl.col("text"), n_range=[1], placeholders={"number": "#", "email": None}
+        ),
+    )
+    assert result["default"].to_list() == [
+        ["pay", "<NUM>", "at", "<URL>", "or", "ask", "<EMAIL>"]
+    ]
+    assert result["custom"].to_list() == [
+        ["Pay", "#", "at", "<URL>", "or", "ask", "bob@example.com"]
+    ]
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="placeholder kind"):
+        df.select(ngrams(pl.col("text"), placeholders={"phone": "<TEL>"}))
+
+
+def test_lowercase_locale():
+    df = pl.DataFrame({"text": ["İSTANBUL IRMAK"]})
+    result = df.select(
+        tr=ngrams(pl.col("text"), n_range=[1], lowercase=True, locale="tr-TR"),
+        root=ngrams(pl.col("text"), n_range=[1], lowercase=True),
+    )
+    assert result["tr"].to_list() == [["istanbul", "ırmak"]]
+    assert result["root"].to_list() == [["i\u0307stanbul", "irmak"]]
+
+
+def test_stopwords():
+    df = pl.DataFrame({"text": ["The state of the art"]})
+    result = df.select(
+        ngrams(pl.col("text"), n_range=[2], lowercase=True, stopwords="english").alias("tokens"),
+        ngrams(
+            pl.col("text"),
+            n_range=[2, 3, 4],
+            lowercase=True,
+            stopwords=["the", "of"],
+            stopword_mode="boundary",
+        ).alias("boundary"),
+        ngrams_hashed(pl.col("text"), n_range=[1], stopwords=["of"]).alias("hashed"),
+    )
+    assert result["tokens"].to_list() == [["state art"]]
+    assert result["boundary"].to_list() == [["state of the art"]]
+    assert len(result["hashed"].to_list()[0]) == 4
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="klingon"):
+        df.select(ngrams(pl.col("text"), stopwords="klingon"))
+
+
+def test_phonetic():
+    df = pl.DataFrame({"text": ["Catherine Smith 42", "Kathryn Smyth"]})
+    result = df.select(
+        ngrams(pl.col("text"), n_range=[1, 2], phonetic="metaphone"),
+        ngrams(pl.col("text"), n_range=[1], phonetic="soundex").alias("soundex"),
+    )
+    assert result["text"].to_list() == [["K0RN", "SM0", "K0RN SM0"]] * 2
+    assert result["soundex"].to_list() == [["C365", "S530"], ["K365", "S530"]]
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="phonetic"):
+        df.select(ngrams(pl.col("text"), phonetic="nysiis"))
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="boundary"):
+        df.select(ngrams(pl.col("text"), phonetic="soundex", stopwords=["a"], stopword_mode="boundary"))
+
+
+def test_bos_eos_padding():
+    df = pl.DataFrame({"words": [["a", "b"], []]})
+    result = df.select(
+        ngrams(pl.col("words"), n_range=[1, 3], pad=True),
+        ngrams_hashed(pl.col("words"), n_range=[1, 3], pad=True).alias("hashed"),
+        ngrams(pl.col("words"), n_range=[2], pad=True, bos="^", eos="$", delimiter="_").alias("custom"),
+    )
+    assert result["words"].to_list() == [
+        ["a", "b", "<s> <s> a", "<s> a b", "a b </s>", "b </s> </s>"],
+        ["<s> <s> </s>", "<s> </s> </s>"],
+    ]
+    assert [len(row) for row in result["hashed"].to_list()] == [6, 2]
+    assert result["custom"].to_list() == [["^_a", "a_b", "b_$"], ["^_$"]]
+
+
+def test_struct_output():
+    df = pl.DataFrame({"words": [["a", "b", "c", "d"], None]})
+    result = df.select(ngrams(pl.col("words"), n_range=[1, 2, 4], output="struct"))
+    assert result.schema["words"] == pl.Struct(
+        {"unigrams": pl.List(pl.String), "bigrams": pl.List(pl.String), "4grams": pl.List(pl.String)}
+    )
+    assert result.unnest("words").row(0) == (
+        ["a", "b", "c", "d"],
+        ["a b", "b c", "c d"],
+        ["a b c d"],
+    )
+    assert result.unnest("words").row(1) == (None, None, None)
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="appears twice"):
+        df.select(ngrams(pl.col("words"), n_range=[2, 2], output="struct"))
+
+
+def test_ngrams_exploded():
+    df = pl.DataFrame({"text": ["a b c", None, "d e"]})
+    result = df.select(ngrams_exploded(pl.col("text"), n_range=[2])).unnest("text")
+    assert result.to_dict(as_series=False) == {"row": [0, 0, 2], "ngram": ["a b", "b c", "d e"]}
+
+    words = pl.DataFrame({"words": [["x", "y"], []]})
+    result = words.select(ngrams_exploded(pl.col("words"), n_range=[1])).unnest("words")
+    assert result.to_dict(as_series=False) == {"row": [0, 0], "ngram": ["x", "y"]}
+
+    with pytest.raises(pl.exceptions.ComputeError, match="index 1"):
+        df.select(ngrams_exploded(pl.col("text"), null_policy="error"))
+
+
+def test_flexible_n_range():
+    df = pl.DataFrame({"words": [["a", "b", "c"]]})
+    expected = [["a b", "b c", "a b c"]]
+    assert df.select(ngrams(pl.col("words"), n_range=(2, 3)))["words"].to_list() == expected
+    assert df.select(ngrams(pl.col("words"), n_range={"min": 2, "max": 3}))["words"].to_list() == expected
+    assert df.select(ngrams(pl.col("words"), n_range=3))["words"].to_list() == [["a b c"]]
+    text = pl.DataFrame({"text": ["abc"]})
+    assert text.select(char_ngrams(pl.col("text"), n_range=(1, 2)))["text"].to_list() == [
+        ["a", "b", "c", "ab", "bc"]
+    ]
+
+    with pytest.raises(pl.exceptions.PolarsError, match="larger than max"):
+        df.select(ngrams(pl.col("words"), n_range=(3, 2)))
+
+
+def test_per_row_delimiter():
+    df = pl.DataFrame({"words": [["a", "b"], ["c", "d"], ["e", "f"]], "sep": ["_", "", None]})
+    result = df.select(ngrams(pl.col("words"), n_range=[2], delimiter=pl.col("sep")))
+    assert result["words"].to_list() == [["a_b"], ["cd"], ["e f"]]
+
+    text = pl.DataFrame({"text": ["a b", "c d"], "sep": ["-", "+"]})
+    result = text.select(
+        ngrams_exploded(pl.col("text"), n_range=[2], delimiter=pl.col("sep"))
+    ).unnest("text")
+    assert result["ngram"].to_list() == ["a-b", "c+d"]
+
+
+def test_parallel_matches_serial():
+    rows = [[f"w{i}", f"w{i + 1}", f"w{i + 2}"] if i % 7 else None for i in range(1000)]
+    df = pl.DataFrame({"words": rows})
+    for expression in (ngrams, ngram_counts, ngrams_hashed, ngrams_exploded):
+        serial = df.select(expression(pl.col("words"), n_range=[1, 2]))
+        parallel = df.select(expression(pl.col("words"), n_range=[1, 2], parallel=True))
+        assert parallel.equals(serial)
+
+    text = pl.DataFrame({"text": [" ".join(words) if words else None for words in rows]})
+    serial = text.select(ngram_counts(pl.col("text"), n_range=[1, 2]))
+    parallel = text.select(ngram_counts(pl.col("text"), n_range=[1, 2], parallel=True))
+    assert parallel.equals(serial)
+
+    # `min_count` is counted over the whole column, not per thread
+    for expression in (ngrams, ngrams_exploded):
+        serial = df.select(expression(pl.col("words"), n_range=[1], min_count=3))
+        parallel = df.select(expression(pl.col("words"), n_range=[1], min_count=3, parallel=True))
+        assert parallel.equals(serial)
+
+    with pytest.raises(pl.exceptions.ComputeError, match="index 0"):
+        df.select(ngrams(pl.col("words"), null_policy="error", parallel=True))
+
+
+def test_unknown_options_raise():
+    with pytest.raises(TypeError, match=r"ngrams_hashed\(\) got an unexpected .* 'short_row'"):
+        ngrams_hashed(pl.col("words"), short_row="null")
+    with pytest.raises(TypeError, match="'lowercas'"):
+        tfidf(pl.col("words"), lowercas=True)
+
+
+def test_streaming_and_over():
+    lf = pl.LazyFrame({"group": ["a", "a", "b"], "words": [["x", "y"], ["y", "z"], ["z", "w"]]})
+    expected = [["x y"], ["y z"], ["z w"]]
+    streamed = lf.select(ngrams(pl.col("words"), n_range=[2])).collect(engine="streaming")
+    assert streamed["words"].to_list() == expected
+
+    result = lf.select(ngrams(pl.col("words"), n_range=[2]).over("group")).collect()
+    assert result["words"].to_list() == expected
+
+
+def test_tfidf():
+    df = pl.DataFrame({"text": ["a b a", "a c", None]})
+    result = df.select(tfidf(pl.col("text")))
+    assert result.schema["text"] == pl.List(
+        pl.Struct({"ngram": pl.String, "tfidf": pl.Float64})
+    )
+    rows = result["text"].to_list()
+    idf_a = math.log(3 / 3) + 1
+    idf_b = math.log(3 / 2) + 1
+    assert [entry["ngram"] for entry in rows[0]] == ["a", "b"]
+    assert rows[0][0]["tfidf"] == pytest.approx(2 * idf_a)
+    assert rows[0][1]["tfidf"] == pytest.approx(idf_b)
+    assert rows[1][1] == {"ngram": "c", "tfidf": pytest.approx(idf_b)}
+    assert rows[2] is None
+
+    grouped = (
+        pl.DataFrame({"g": [1, 1, 2], "text": ["a b", "a", "a"]})
+        .group_by("g", maintain_order=True)
+        .agg(tfidf(pl.col("text")))
+    )
+    assert grouped["text"].to_list()[1] == [[{"ngram": "a", "tfidf": 1.0}]]
+
+
+def test_top_ngrams():
+    df = pl.DataFrame(
+        {
+            "g": [1, 1, 2, 2],
+            "text": ["a b a", "b c b", "x y", None],
+        }
+    )
+    result = df.select(top_ngrams(pl.col("text"), k=2))
+    assert result.height == 1
+    assert result["text"].to_list()[0] == [
+        {"ngram": "b", "count": 3},
+        {"ngram": "a", "count": 2},
+    ]
+
+    grouped = (
+        df.group_by("g", maintain_order=True)
+        .agg(top_ngrams(pl.col("text"), k=1, n_range=[2]).alias("top"))
+    )
+    assert grouped.schema["top"] == pl.List(
+        pl.Struct({"ngram": pl.String, "count": pl.UInt32})
+    )
+    assert grouped["top"].to_list() == [
+        [{"ngram": "a b", "count": 1}],
+        [{"ngram": "x y", "count": 1}],
+    ]
+
+
+def test_ngram_similarity():
+    df = pl.DataFrame(
+        {
+            "a": ["night", "abc", None],
+            "b": ["nacht", "abc", "x"],
+            "wa": [["a", "b", "c"], ["a"], None],
+            "wb": [["a", "b", "d"], [], ["x"]],
+        }
+    )
+    result = df.select(
+        ngram_similarity(pl.col("a"), pl.col("b"), n=2).alias("jaccard"),
+        ngram_similarity(pl.col("a"), pl.col("b"), n=2, metric="dice").alias("dice"),
+        ngram_similarity(pl.col("wa"), pl.col("wb"), n=2).alias("words"),
+        ngram_similarity(pl.col("b"), pl.lit("abc"), n=2).alias("broadcast"),
+    )
+    assert result.schema["jaccard"] == pl.Float64
+    assert result["jaccard"].to_list() == [pytest.approx(1 / 7), 1.0, None]
+    assert result["dice"].to_list() == [0.25, 1.0, None]
+    assert result["words"].to_list() == [pytest.approx(1 / 3), 1.0, None]
+    assert result["broadcast"].to_list() == [0.0, 1.0, 0.0]
+
+
+def test_bleu_and_rouge_n():
+    df = pl.DataFrame(
+        {
+            "candidate": ["the cat sat on a mat", "the cat", None],
+            "reference": ["the cat sat on the mat", "the cat ran away", "x"],
+        }
+    )
+    result = df.select(
+        bleu(pl.col("candidate"), pl.col("reference"), max_n=2).alias("bleu"),
+        rouge_n(pl.col("candidate"), pl.col("reference"), n=2).alias("rouge"),
+        bleu(
+            pl.col("candidate").str.split(" "), pl.col("reference").str.split(" "), max_n=2
+        ).alias("lists"),
+    )
+    assert result.schema["bleu"] == pl.Float64
+    scores = result["bleu"].to_list()
+    assert scores[0] == pytest.approx(math.sqrt(5 / 6 * 3 / 5))
+    assert scores[1] == pytest.approx(math.exp(1 - 4 / 2))
+    assert scores[2] is None
+    assert result["lists"].to_list() == scores
+    assert result["rouge"].to_list() == [
+        {"precision": 0.6, "recall": 0.6, "f1": pytest.approx(0.6)},
+        {"precision": 1.0, "recall": pytest.approx(1 / 3), "f1": pytest.approx(0.5)},
+        None,
+    ]
+
+
+def test_blocking_keys():
+    df = pl.DataFrame({"name": ["jonathan smith", "jonathan smith", "zz", None]})
+    result = df.select(
+        blocking_keys(pl.col("name")),
+        blocking_keys(pl.col("name"), num_keys=8, band_size=2, seed=1).alias("banded"),
+    )
+    assert result.schema["name"] == pl.List(pl.UInt64)
+    keys = result["name"].to_list()
+    assert len(keys[0]) == 4
+    assert keys[0] == keys[1]
+    assert keys[2] == []
+    assert keys[3] is None
+    assert len(result["banded"].to_list()[0]) == 8
+
+    left = pl.DataFrame({"id": [1, 2], "name": ["jonathan smith", "alice brown"]})
+    right = pl.DataFrame({"id_b": [10], "name_b": ["jonathon smith"]})
+    pairs = (
+        left.with_columns(key=blocking_keys("name", num_keys=16))
+        .explode("key")
+        .join(
+            right.with_columns(key=blocking_keys("name_b", num_keys=16)).explode("key"),
+            on="key",
+        )
+        .unique(["id", "id_b"])
+    )
+    assert pairs["id"].to_list() == [1]
+
+
+def test_detect_language(tmp_path):
+    samples = {
+        "en": "the quick brown fox jumps over the lazy dog",
+        "fr": ["le renard brun rapide saute par-dessus le chien paresseux"],
+    }
+    df = pl.DataFrame({"text": ["the dog is lazy", "le chien est paresseux", None]})
+    result = df.select(detect_language(pl.col("text"), samples=samples))
+    assert result.schema["text"] == pl.Struct({"lang": pl.String, "confidence": pl.Float64})
+    rows = result["text"].to_list()
+    assert [row["lang"] for row in rows[:2]] == ["en", "fr"]
+    assert all(0.5 < row["confidence"] <= 1.0 for row in rows[:2])
+    assert rows[2] is None
+
+    (tmp_path / "en.txt").write_text(samples["en"])
+    (tmp_path / "fr.txt").write_text(samples["fr"][0])
+    from_dir = df.select(detect_language(pl.col("text"), profiles=tmp_path))
+    assert from_dir["text"].to_list() == rows
+
+    with pytest.raises(pl.exceptions.InvalidOperationError, match="no language profiles"):
+        df.select(detect_language(pl.col("text")))
+
+
+def test_ngram_count():
+    df = pl.DataFrame(
+        {
+            "words": [["a", "b", "c"], [], None, ["a", None, "b"]],
+            "text": ["a b c", "", None, "a  b"],
+        }
+    )
+    result = df.select(
+        ngram_count(pl.col("words"), n_range=(1, 2)),
+        ngram_count(pl.col("text"), n_range=(1, 2)),
+        ngrams(pl.col("words"), n_range=(1, 2)).list.len().alias("built"),
+    )
+    assert result.schema["words"] == pl.UInt32
+    assert result["words"].to_list() == [5, 0, None, 3]
+    assert result["text"].to_list() == [5, 0, None, 3]
+    assert result["words"].to_list() == result["built"].to_list()
+
+    arrays = pl.DataFrame({"a": pl.Series([["a", "b", "c"], None], dtype=pl.Array(pl.String, 3))})
+    assert arrays.select(ngram_count(pl.col("a"), n_range=[2]))["a"].to_list() == [2, None]
+
+
+def test_min_count():
+    df = pl.DataFrame({"text": ["a b a", "b c", None, "a b"]})
+    result = df.select(
+        ngrams(pl.col("text"), n_range=(1, 2), min_count=2),
+        ngrams(pl.col("text"), n_range=[2], min_count=2, output="struct").alias("orders"),
+    )
+    assert result["text"].to_list() == [["a", "b", "a", "a b"], ["b"], None, ["a", "b", "a b"]]
+    assert result["orders"].struct.field("bigrams").to_list() == [["a b"], [], None, ["a b"]]
+
+    exploded = df.select(ngrams_exploded(pl.col("text"), n_range=[1], min_count=3)).unnest("text")
+    assert exploded.rows() == [(0, "a"), (0, "b"), (0, "a"), (1, "b"), (3, "a"), (3, "b")]
+
+
+def test_byte_ngrams():
+    df = pl.DataFrame({"seq": [b"GATC", b"\xff\x00", None], "text": ["añ", "", None]})
+    result = df.select(
+        byte_ngrams(pl.col("seq")),
+        byte_ngrams(pl.col("seq"), n_range=2).alias("pairs"),
+        byte_ngrams(pl.col("text"), n_range=[1]).alias("text"),
+    )
+    assert result.schema["seq"] == pl.List(pl.Binary)
+    assert result["seq"].to_list() == [[b"GAT", b"ATC"], [], None]
+    assert result["pairs"].to_list()[1] == [b"\xff\x00"]
+    assert result["text"].to_list() == [[b"a", b"\xc3", b"\xb1"], [], None]
+
+
+def test_short_row_policy():
+    df = pl.DataFrame({"text": ["a b c", "a b", "", None]})
+    result = df.select(
+        ngrams(pl.col("text"), n_range=[3]).alias("empty"),
+        ngrams(pl.col("text"), n_range=[3], short_row="null").alias("null"),
+        ngrams(pl.col("text"), n_range=[3], short_row="partial").alias("partial"),
+    )
+    assert result["empty"].to_list() == [["a b c"], [], [], None]
+    assert result["null"].to_list() == [["a b c"], None, None, None]
+    assert result["partial"].to_list() == [["a b c"], ["a b"], [], None]
+
+    exploded = df.select(
+        ngrams_exploded(pl.col("text"), n_range=[3], short_row="partial")
+    ).unnest("text")
+    assert exploded.rows() == [(0, "a b c"), (1, "a b")]
+
+    with pytest.raises(pl.exceptions.ComputeError, match="short_row"):
+        df.select(ngrams(pl.col("text"), n_range=[3], short_row="error"))
+
+    # Padded rows are never short, so only the default policy is accepted
+    padded = df.select(ngrams(pl.col("text"), n_range=[3], pad=True, short_row="empty"))
+    assert padded["text"].to_list()[1] == ["<s> <s> a", "<s> a b", "a b </s>", "b </s> </s>"]
+    for policy in ["null", "partial", "error"]:
+        with pytest.raises(pl.exceptions.InvalidOperationError, match="pad"):
+            df.select(ngrams(pl.col("text"), n_range=[3], pad=True, short_row=policy))
+
+
+def test_inner_nulls():
+    df = pl.DataFrame({"words": [["a", None, "b", "c"], [None, None], None]})
+    result = df.select(
+        ngrams(pl.col("words"), n_range=[2]).alias("skip"),
+        ngrams(pl.col("words"), n_range=[2], inner_nulls="break").alias("break"),
+        ngrams(pl.col("words"), n_range=[2], inner_nulls="placeholder").alias(
+            "placeholder"
+        ),
+    )
+    assert result["skip"].to_list() == [["a b", "b c"], [], None]
+    assert result["break"].to_list() == [["b c"], [], None]
+    assert result["placeholder"].to_list() == [
+        ["a <null>", "<null> b", "b c"],
+        ["<null> <null>"],
+        None,
+    ]
+
+    partial = df.select(
+        ngrams(pl.col("words"), n_range=[2], inner_nulls="break", short_row="partial")
+    )
+    assert partial["words"].to_list() == [["b c"], [], None]
+
+
+def test_inner_nulls_short_run_is_not_short_row():
+    # Only a row whose tokens together are fewer than the smallest n is short, so a
+    # short run between nulls gets no partial n-gram of its own
+    df = pl.DataFrame(
+        {"words": [["a", "b", "c", None, "d"], ["a", None, "b"], [None, "a"]]}
+    )
+    result = df.select(
+        ngrams(
+            pl.col("words"), n_range=[2], inner_nulls="break", short_row="partial"
+        ).alias("partial"),
+        ngrams(
+            pl.col("words"), n_range=[2], inner_nulls="break", short_row="null"
+        ).alias("null"),
+    )
+    assert result["partial"].to_list() == [["a b", "b c"], [], ["a"]]
+    assert result["null"].to_list() == [["a b", "b c"], [], None]
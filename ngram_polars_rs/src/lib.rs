@@ -0,0 +1,226 @@
+//! Polars expression methods for `ngram_rs`.
+//!
+//! The [`NGramExprExt`] trait adds `.ngrams(...)` and `.char_ngrams(...)` to Polars
+//! `Expr`s, so Rust Polars users get n-gram columns in lazy queries without going
+//! through the Python plugin:
+//!
+//! ```no_run
+//! use ngram_polars_rs::NGramExprExt;
+//! use polars::prelude::*;
+//!
+//! # fn example(lf: LazyFrame) -> PolarsResult<DataFrame> {
+//! lf.select([
+//!     col("body").ngrams(&[1, 2], " ").alias("grams"),
+//!     col("title").char_ngrams(&[3]).alias("trigrams"),
+//! ])
+//! .collect()
+//! # }
+//! ```
+
+use polars::prelude::*;
+
+/// N-gram methods on Polars expressions.
+pub trait NGramExprExt {
+    /// Generates the word n-grams of every row.
+    ///
+    /// A `List(String)` column is read as tokens and null tokens are skipped; a
+    /// `String` column is split on whitespace first. Null rows stay null.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_range` - The n-gram sizes to generate, all n-grams of the first size first
+    /// * `delimiter` - The string placed between the tokens of an n-gram
+    ///
+    /// # Returns
+    ///
+    /// A `List(String)` expression keeping the input name.
+    fn ngrams(self, n_range: &[usize], delimiter: &str) -> Expr;
+
+    /// Generates the character n-grams of every row of a `String` column.
+    ///
+    /// Windows are taken over Unicode scalar values, so multi-byte characters are
+    /// never split. Null rows stay null.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_range` - The n-gram sizes to generate, in characters
+    ///
+    /// # Returns
+    ///
+    /// A `List(String)` expression keeping the input name.
+    fn char_ngrams(self, n_range: &[usize]) -> Expr;
+}
+
+impl NGramExprExt for Expr {
+    fn ngrams(self, n_range: &[usize], delimiter: &str) -> Expr {
+        let n_range = n_range.to_vec();
+        let delimiter = delimiter.to_string();
+        self.map_with_fmt_str(
+            move |column| ngrams_column(&column, &n_range, &delimiter),
+            |_, field| list_output(field, true),
+            "ngrams",
+        )
+    }
+
+    fn char_ngrams(self, n_range: &[usize]) -> Expr {
+        let n_range = n_range.to_vec();
+        self.map_with_fmt_str(
+            move |column| char_ngrams_column(&column, &n_range),
+            |_, field| list_output(field, false),
+            "char_ngrams",
+        )
+    }
+}
+
+fn is_string_list(dtype: &DataType) -> bool {
+    matches!(dtype, DataType::List(inner) if **inner == DataType::String)
+}
+
+/// Checks the input type at planning time, so a wrong column fails before any data
+/// is read.
+fn list_output(field: &Field, allow_lists: bool) -> PolarsResult<Field> {
+    let dtype = field.dtype();
+    if !(*dtype == DataType::String || allow_lists && is_string_list(dtype)) {
+        polars_bail!(
+            SchemaMismatch: "expected {}, got {}",
+            if allow_lists { "String or List(String)" } else { "String" },
+            dtype
+        );
+    }
+    Ok(Field::new(
+        field.name().clone(),
+        DataType::List(Box::new(DataType::String)),
+    ))
+}
+
+fn ngrams_column(column: &Column, n_range: &[usize], delimiter: &str) -> PolarsResult<Column> {
+    let series = column.as_materialized_series();
+    let mut builder =
+        ListStringChunkedBuilder::new(series.name().clone(), series.len(), series.len() * 4);
+    let mut ngrams = Vec::new();
+    let mut append = |tokens: Option<&[&str]>| match tokens {
+        Some(tokens) => {
+            ngram_rs::write_ngrams_into(tokens, n_range, Some(delimiter), &mut ngrams);
+            builder.append_values_iter(ngrams.iter().map(String::as_str));
+        }
+        None => builder.append_null(),
+    };
+
+    if let Ok(ca) = series.str() {
+        for text in ca {
+            let tokens = text.map(|text| ngram_rs::tokenize_whitespace(text).collect::<Vec<_>>());
+            append(tokens.as_deref());
+        }
+    } else {
+        for row in series.list()?.amortized_iter() {
+            let tokens = match &row {
+                Some(row) => Some(
+                    row.as_ref()
+                        .str()?
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>(),
+                ),
+                None => None,
+            };
+            append(tokens.as_deref());
+        }
+    }
+    Ok(builder.finish().into_column())
+}
+
+fn char_ngrams_column(column: &Column, n_range: &[usize]) -> PolarsResult<Column> {
+    let ca = column.str()?;
+    let mut builder = ListStringChunkedBuilder::new(ca.name().clone(), ca.len(), ca.len() * 8);
+    for text in ca {
+        match text {
+            Some(text) => builder
+                .append_values_iter(ngram_rs::generate_char_ngrams(text, n_range).into_iter()),
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish().into_column())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(series: &Series) -> Vec<Option<Vec<String>>> {
+        series
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|row| {
+                row.map(|row| {
+                    row.str()
+                        .unwrap()
+                        .into_no_null_iter()
+                        .map(str::to_string)
+                        .collect()
+                })
+            })
+            .collect()
+    }
+
+    /// Tests word n-grams on token lists and on raw text in a lazy query
+    #[test]
+    fn test_ngrams_expr() -> PolarsResult<()> {
+        let words = Series::new(
+            "words".into(),
+            [
+                Series::new("".into(), [Some("a"), None, Some("b"), Some("c")]),
+                Series::new("".into(), ["d"]),
+            ],
+        );
+        let text = Series::new("text".into(), [Some("the quick  fox"), None]);
+        let df = DataFrame::new(2, vec![words.into_column(), text.into_column()])?;
+
+        let result = df
+            .lazy()
+            .select([
+                col("words").ngrams(&[2], "_"),
+                col("text").ngrams(&[1, 2], " ").alias("grams"),
+            ])
+            .collect()?;
+        assert_eq!(
+            strings(result.column("words")?.as_materialized_series()),
+            [
+                Some(vec!["a_b".to_string(), "b_c".to_string()]),
+                Some(vec![])
+            ]
+        );
+        let grams = strings(result.column("grams")?.as_materialized_series());
+        assert_eq!(
+            grams[0].as_deref().unwrap(),
+            ["the", "quick", "fox", "the quick", "quick fox"]
+        );
+        assert_eq!(grams[1], None);
+        Ok(())
+    }
+
+    /// Tests character n-grams and the planning-time type check
+    #[test]
+    fn test_char_ngrams_expr() -> PolarsResult<()> {
+        let df = df!("name" => [Some("café"), None])?;
+        let result = df
+            .clone()
+            .lazy()
+            .select([col("name").char_ngrams(&[3])])
+            .collect()?;
+        assert_eq!(
+            strings(result.column("name")?.as_materialized_series()),
+            [Some(vec!["caf".to_string(), "afé".to_string()]), None]
+        );
+
+        let numbers = df!("n" => [1, 2])?;
+        assert!(
+            numbers
+                .lazy()
+                .select([col("n").char_ngrams(&[2])])
+                .collect()
+                .is_err()
+        );
+        Ok(())
+    }
+}
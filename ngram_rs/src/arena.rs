@@ -0,0 +1,81 @@
+use alloc::string::String;
+use bumpalo::Bump;
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+
+use crate::ngram_count;
+
+/// Generates n-grams inside a caller-provided bump arena.
+///
+/// Every n-gram, unigrams included, is copied into `bump` and the returned vector is
+/// allocated there too, so the whole batch is released at once by `Bump::reset`.
+/// This suits servers that process one document per request in a loop.
+///
+/// # Arguments
+///
+/// * `bump` - Arena receiving the n-gram strings and the output vector
+/// * `words` - A slice of String objects representing the input text as individual words
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::Bump;
+/// use ngram_rs::generate_ngrams_in;
+///
+/// let mut bump = Bump::new();
+/// let words = vec!["a".to_string(), "b".to_string()];
+/// {
+///     let ngrams = generate_ngrams_in(&bump, &words, &[1, 2], None);
+///     assert_eq!(ngrams, ["a", "b", "a b"]);
+/// }
+/// bump.reset();
+/// ```
+pub fn generate_ngrams_in<'bump>(
+    bump: &'bump Bump,
+    words: &[String],
+    n_range: &[usize],
+    delimiter: Option<&str>,
+) -> BumpVec<'bump, &'bump str> {
+    let delimiter = delimiter.unwrap_or(" ");
+    let mut result = BumpVec::with_capacity_in(ngram_count(words.len(), n_range), bump);
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            let len = window.iter().map(String::len).sum::<usize>() + (n - 1) * delimiter.len();
+            let mut ngram = BumpString::with_capacity_in(len, bump);
+            for (i, word) in window.iter().enumerate() {
+                if i > 0 {
+                    ngram.push_str(delimiter);
+                }
+                ngram.push_str(word);
+            }
+            result.push(ngram.into_bump_str());
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that arena output matches the owned output and lives in the arena
+    #[test]
+    fn test_arena_generation() {
+        let bump = Bump::new();
+        let words = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+
+        let ngrams = generate_ngrams_in(&bump, &words, &[1, 3], Some("_"));
+        assert_eq!(
+            ngrams.as_slice(),
+            crate::generate_ngrams_owned(&words, &[1, 3], "_")
+        );
+        assert!(bump.allocated_bytes() > 0);
+    }
+}
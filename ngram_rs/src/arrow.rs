@@ -0,0 +1,214 @@
+use std::fmt::Write;
+
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{Array, ListArray, StringArray};
+use arrow_schema::ArrowError;
+
+use crate::{ngram_bytes, ngram_count, tokenize_whitespace};
+
+/// Generates n-grams for every row of an Arrow `List<Utf8>` array.
+///
+/// Each row is treated as a sequence of tokens. Null rows stay null in the output and
+/// null tokens inside a row are skipped, matching the Polars plugin. The output buffers
+/// are sized exactly in a first pass and n-grams are then written straight into them,
+/// without an intermediate `String` per n-gram.
+///
+/// # Arguments
+///
+/// * `input` - A list array whose values are a `StringArray`
+/// * `n_range` - The n-gram sizes to generate for each row
+/// * `delimiter` - The separator placed between tokens
+///
+/// # Returns
+///
+/// A `List<Utf8>` array with one list of n-grams per input row, or an
+/// `InvalidArgumentError` if the list values are not `Utf8`.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::builder::{ListBuilder, StringBuilder};
+/// use arrow_array::{Array, StringArray};
+/// use ngram_rs::ngrams_list_array;
+///
+/// let mut builder = ListBuilder::new(StringBuilder::new());
+/// builder.append_value([Some("the"), Some("quick"), Some("fox")]);
+/// let input = builder.finish();
+///
+/// let output = ngrams_list_array(&input, &[2], " ").unwrap();
+/// let row = output.value(0);
+/// let row = row.as_any().downcast_ref::<StringArray>().unwrap();
+/// assert_eq!(row.value(0), "the quick");
+/// assert_eq!(row.value(1), "quick fox");
+/// ```
+pub fn ngrams_list_array(
+    input: &ListArray,
+    n_range: &[usize],
+    delimiter: &str,
+) -> Result<ListArray, ArrowError> {
+    let values = input
+        .values()
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!(
+                "expected List<Utf8> input, got List<{}>",
+                input.value_type()
+            ))
+        })?;
+
+    let offsets = input.value_offsets();
+    let row_tokens = |row: usize| -> Option<Vec<&str>> {
+        if input.is_null(row) {
+            return None;
+        }
+        let (start, end) = (offsets[row] as usize, offsets[row + 1] as usize);
+        Some(
+            (start..end)
+                .filter(|&i| values.is_valid(i))
+                .map(|i| values.value(i))
+                .collect(),
+        )
+    };
+
+    build_ngram_lists(input.len(), row_tokens, n_range, delimiter)
+}
+
+/// Tokenizes every row of an Arrow `Utf8` array on whitespace and generates its n-grams.
+///
+/// Tokens are split with [`tokenize_whitespace`]; null rows stay null in the output.
+///
+/// # Arguments
+///
+/// * `input` - A string array of raw text, one document per row
+/// * `n_range` - The n-gram sizes to generate for each row
+/// * `delimiter` - The separator placed between tokens
+///
+/// # Returns
+///
+/// A `List<Utf8>` array with one list of n-grams per input row.
+///
+/// # Examples
+///
+/// ```
+/// use arrow_array::{Array, StringArray};
+/// use ngram_rs::ngrams_string_array;
+///
+/// let input = StringArray::from(vec![Some("the quick fox"), None]);
+/// let output = ngrams_string_array(&input, &[2], "_").unwrap();
+///
+/// assert!(output.is_null(1));
+/// let row = output.value(0);
+/// let row = row.as_any().downcast_ref::<StringArray>().unwrap();
+/// assert_eq!(row.value(1), "quick_fox");
+/// ```
+pub fn ngrams_string_array(
+    input: &StringArray,
+    n_range: &[usize],
+    delimiter: &str,
+) -> Result<ListArray, ArrowError> {
+    let row_tokens = |row: usize| -> Option<Vec<&str>> {
+        input
+            .is_valid(row)
+            .then(|| tokenize_whitespace(input.value(row)).collect())
+    };
+
+    build_ngram_lists(input.len(), row_tokens, n_range, delimiter)
+}
+
+/// Builds the output list array from the tokens of each row.
+fn build_ngram_lists<'a>(
+    num_rows: usize,
+    row_tokens: impl Fn(usize) -> Option<Vec<&'a str>>,
+    n_range: &[usize],
+    delimiter: &str,
+) -> Result<ListArray, ArrowError> {
+    let rows: Vec<Option<Vec<&str>>> = (0..num_rows).map(row_tokens).collect();
+
+    let (mut items, mut bytes) = (0, 0);
+    for words in rows.iter().flatten() {
+        items += ngram_count(words.len(), n_range);
+        bytes += ngram_bytes(words, n_range, delimiter);
+    }
+
+    let values = StringBuilder::with_capacity(items, bytes);
+    let mut builder = ListBuilder::with_capacity(values, num_rows);
+
+    for row in &rows {
+        let Some(words) = row else {
+            builder.append_null();
+            continue;
+        };
+        let out = builder.values();
+        for &n in n_range {
+            if n == 0 || n > words.len() {
+                continue;
+            }
+            for window in words.windows(n) {
+                for (i, word) in window.iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(delimiter)
+                            .expect("writing to a StringBuilder");
+                    }
+                    out.write_str(word).expect("writing to a StringBuilder");
+                }
+                // Finalizes the bytes written above as one value
+                out.append_value("");
+            }
+        }
+        builder.append(true);
+    }
+
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::types::Int32Type;
+
+    fn row(array: &ListArray, i: usize) -> Vec<String> {
+        let values = array.value(i);
+        let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+        values.iter().map(|v| v.unwrap().to_string()).collect()
+    }
+
+    /// Tests that the list kernel matches `generate_ngrams_owned` and handles nulls
+    #[test]
+    fn test_ngrams_list_array() {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        builder.append_value([Some("a"), Some("b"), None, Some("c")]);
+        builder.append_null();
+        builder.append_value(Vec::<Option<&str>>::new());
+        let input = builder.finish();
+
+        let output = ngrams_list_array(&input, &[1, 2], "-").unwrap();
+        assert_eq!(output.len(), 3);
+        let words = ["a", "b", "c"].map(String::from);
+        assert_eq!(
+            row(&output, 0),
+            crate::generate_ngrams_owned(&words, &[1, 2], "-")
+        );
+        assert!(output.is_null(1));
+        assert!(row(&output, 2).is_empty());
+    }
+
+    /// Tests that the string kernel tokenizes each row before generating n-grams
+    #[test]
+    fn test_ngrams_string_array() {
+        let input = StringArray::from(vec![Some("  the quick\tfox "), Some("one"), None]);
+        let output = ngrams_string_array(&input, &[2], " ").unwrap();
+
+        assert_eq!(row(&output, 0), ["the quick", "quick fox"]);
+        assert!(row(&output, 1).is_empty());
+        assert!(output.is_null(2));
+    }
+
+    /// Tests that a non-string list is rejected
+    #[test]
+    fn test_ngrams_list_array_wrong_type() {
+        let input = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![Some(vec![Some(1)])]);
+        let err = ngrams_list_array(&input, &[1], " ").unwrap_err();
+        assert!(matches!(err, ArrowError::InvalidArgumentError(_)));
+    }
+}
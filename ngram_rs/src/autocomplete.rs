@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// One suggestion returned by [`Autocomplete::complete`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion<'a> {
+    /// The suggested token
+    pub token: &'a str,
+    /// How often the token followed the matched context
+    pub count: usize,
+    /// The share of the continuations of the matched context that are this token
+    pub probability: f64,
+}
+
+/// A node of the token trie: the count of the n-gram spelled by the path to it and
+/// its children by next token.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Node {
+    count: usize,
+    /// Sum of the counts of the children
+    continuations: usize,
+    children: BTreeMap<String, usize>,
+}
+
+/// Next-token suggestions from a trie of word n-grams.
+///
+/// Every n-gram up to `max_order` tokens is stored along a path of the trie, so the
+/// tokens that followed a context are the children of its node, kept in token order
+/// so that those starting with a partly typed token form one contiguous range. A
+/// query walks at most `max_order - 1` context tokens and scans only the matching
+/// children, which keeps lookups fast enough for interactive use.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::Autocomplete;
+///
+/// let mut complete = Autocomplete::new(3);
+/// complete.add_document(&["new", "york", "city"]);
+/// complete.add_document(&["new", "york", "times"]);
+/// complete.add_document(&["new", "jersey"]);
+///
+/// let next = complete.complete(&["new"], "", 5);
+/// assert_eq!(next.iter().map(|c| (c.token, c.count)).collect::<Vec<_>>(), [("york", 2), ("jersey", 1)]);
+///
+/// let typed = complete.complete(&["new", "york"], "ti", 5);
+/// assert_eq!(typed[0].token, "times");
+/// assert_eq!(typed[0].probability, 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Autocomplete {
+    max_order: usize,
+    /// The nodes of the trie, the root first
+    nodes: Vec<Node>,
+}
+
+impl Autocomplete {
+    /// Creates an empty trie storing n-grams of up to `max_order` tokens, so
+    /// suggestions are conditioned on up to `max_order - 1` context tokens.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_order` is 0.
+    pub fn new(max_order: usize) -> Self {
+        assert!(max_order > 0, "autocomplete needs an order of at least 1");
+        Autocomplete {
+            max_order,
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Returns the longest n-gram size stored.
+    pub fn max_order(&self) -> usize {
+        self.max_order
+    }
+
+    /// Returns the number of distinct n-grams stored.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// Returns `true` if nothing has been added.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the n-grams of one tokenized document.
+    pub fn add_document<S: AsRef<str>>(&mut self, words: &[S]) {
+        for start in 0..words.len() {
+            let end = (start + self.max_order).min(words.len());
+            let mut node = 0;
+            for word in &words[start..end] {
+                let word = word.as_ref();
+                let child = match self.nodes[node].children.get(word) {
+                    Some(&child) => child,
+                    None => {
+                        self.nodes.push(Node::default());
+                        let child = self.nodes.len() - 1;
+                        self.nodes[node].children.insert(word.to_string(), child);
+                        child
+                    }
+                };
+                self.nodes[node].continuations += 1;
+                self.nodes[child].count += 1;
+                node = child;
+            }
+        }
+    }
+
+    /// Suggests the tokens most likely to follow `context` that start with `partial`.
+    ///
+    /// Only the last `max_order - 1` context tokens are used. When none of the tokens
+    /// seen after them starts with `partial`, the context is shortened from the left
+    /// until some do, down to suggesting the most frequent matching tokens overall.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The tokens typed so far, before the one being completed
+    /// * `partial` - The start of the token being typed; empty to predict the next one
+    /// * `k` - The largest number of suggestions returned
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` completions by decreasing count, ties broken by token.
+    pub fn complete<S: AsRef<str>>(
+        &self,
+        context: &[S],
+        partial: &str,
+        k: usize,
+    ) -> Vec<Completion<'_>> {
+        let longest = context.len().min(self.max_order - 1);
+        for used in (0..=longest).rev() {
+            let Some(node) = self.find(&context[context.len() - used..]) else {
+                continue;
+            };
+            let node = &self.nodes[node];
+            let mut completions: Vec<Completion> = node
+                .children
+                .range::<str, _>((Bound::Included(partial), Bound::Unbounded))
+                .take_while(|(token, _)| token.starts_with(partial))
+                .map(|(token, &child)| Completion {
+                    token,
+                    count: self.nodes[child].count,
+                    probability: self.nodes[child].count as f64 / node.continuations as f64,
+                })
+                .collect();
+            if completions.is_empty() {
+                continue;
+            }
+            completions.sort_by(|a, b| b.count.cmp(&a.count).then(a.token.cmp(b.token)));
+            completions.truncate(k);
+            return completions;
+        }
+        Vec::new()
+    }
+
+    /// Returns the node reached by following `path` from the root.
+    fn find<S: AsRef<str>>(&self, path: &[S]) -> Option<usize> {
+        path.iter().try_fold(0, |node, word| {
+            self.nodes[node].children.get(word.as_ref()).copied()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests counts, probabilities, partial tokens and backing off to shorter contexts
+    #[test]
+    fn test_autocomplete() {
+        let mut complete = Autocomplete::new(3);
+        assert!(complete.is_empty());
+        complete.add_document(&["i", "want", "to", "go"]);
+        complete.add_document(&["i", "want", "tea"]);
+        complete.add_document(&["we", "want", "to", "eat"]);
+        // i, want, to, go, tea, we, eat; i want, want to, to go, want tea, we want, to
+        // eat; i want to, want to go, i want tea, we want to, want to eat
+        assert_eq!(complete.len(), 7 + 6 + 5);
+
+        let found: Vec<(&str, usize, f64)> = complete
+            .complete(&["i", "want"], "t", 5)
+            .iter()
+            .map(|c| (c.token, c.count, c.probability))
+            .collect();
+        assert_eq!(found, [("tea", 1, 0.5), ("to", 1, 0.5)]);
+
+        // Only the last two tokens count, and "you want" backs off to "want"
+        let next = complete.complete(&["so", "we", "want"], "", 1);
+        assert_eq!((next[0].token, next[0].count), ("to", 1));
+        let next = complete.complete(&["you", "want"], "", 5);
+        assert_eq!((next[0].token, next[0].count), ("to", 2));
+        assert!((next[0].probability - 2.0 / 3.0).abs() < 1e-12);
+
+        // No continuation of "to" starts with "t", so all tokens are searched
+        let next = complete.complete(&["to"], "t", 5);
+        assert_eq!(
+            next.iter().map(|c| c.token).collect::<Vec<_>>(),
+            ["to", "tea"]
+        );
+
+        assert!(complete.complete(&["i"], "x", 5).is_empty());
+        assert!(complete.complete(&["i"], "", 0).is_empty());
+        assert_eq!(Autocomplete::new(1).complete(&["a"], "", 3), []);
+    }
+}
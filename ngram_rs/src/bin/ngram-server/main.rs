@@ -0,0 +1,249 @@
+//! `ngram-server` HTTP service, built with the `server` feature.
+//!
+//! ```text
+//! ngram-server --addr 0.0.0.0:8080 --profiles profiles/
+//! curl localhost:8080/ngrams -H 'content-type: application/json' \
+//!     -d '{"text": "the quick brown fox", "n": [1, 2]}'
+//! ```
+//!
+//! Every endpoint takes a JSON body by `POST`:
+//!
+//! * `/ngrams` - `{text, n, delimiter?, char?}` returns `{ngrams}`
+//! * `/similarity` - `{a, b, n, char?}` returns `{jaccard, dice}`
+//! * `/detect-language` - `{text}` returns `{language, confidence}`, using profiles
+//!   trained from the `LANG.txt` files of `--profiles`
+
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use axum::Router;
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use clap::{Arg, Command};
+use ngram_rs::{
+    LanguageDetector, dice_similarity, generate_char_ngrams, jaccard_similarity,
+    tokenize_whitespace, write_ngrams_into,
+};
+use serde::{Deserialize, Serialize};
+
+struct AppState {
+    detector: Option<LanguageDetector>,
+}
+
+type ApiError = (StatusCode, String);
+
+fn default_n() -> Vec<usize> {
+    vec![1]
+}
+
+fn default_shingle() -> usize {
+    3
+}
+
+#[derive(Deserialize)]
+struct NGramsRequest {
+    text: String,
+    #[serde(default = "default_n")]
+    n: Vec<usize>,
+    delimiter: Option<String>,
+    /// Character n-grams instead of word n-grams
+    #[serde(default)]
+    char: bool,
+}
+
+#[derive(Serialize)]
+struct NGramsResponse {
+    ngrams: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SimilarityRequest {
+    a: String,
+    b: String,
+    #[serde(default = "default_shingle")]
+    n: usize,
+    #[serde(default)]
+    char: bool,
+}
+
+#[derive(Serialize)]
+struct SimilarityResponse {
+    jaccard: f64,
+    dice: f64,
+}
+
+#[derive(Deserialize)]
+struct DetectRequest {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct DetectResponse {
+    language: String,
+    confidence: f64,
+}
+
+/// Returns the word or character n-grams of `text` for every size in `n_range`.
+fn ngrams_of(text: &str, n_range: &[usize], delimiter: Option<&str>, chars: bool) -> Vec<String> {
+    if chars {
+        return generate_char_ngrams(text, n_range)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+    }
+    let tokens: Vec<&str> = tokenize_whitespace(text).collect();
+    let mut ngrams = Vec::new();
+    write_ngrams_into(&tokens, n_range, delimiter, &mut ngrams);
+    ngrams
+}
+
+async fn ngrams(Json(request): Json<NGramsRequest>) -> Json<NGramsResponse> {
+    Json(NGramsResponse {
+        ngrams: ngrams_of(
+            &request.text,
+            &request.n,
+            request.delimiter.as_deref(),
+            request.char,
+        ),
+    })
+}
+
+async fn similarity(
+    Json(request): Json<SimilarityRequest>,
+) -> Result<Json<SimilarityResponse>, ApiError> {
+    if request.n == 0 {
+        return Err((StatusCode::BAD_REQUEST, "`n` must be positive".to_string()));
+    }
+    let a = ngrams_of(&request.a, &[request.n], None, request.char);
+    let b = ngrams_of(&request.b, &[request.n], None, request.char);
+    Ok(Json(SimilarityResponse {
+        jaccard: jaccard_similarity(&a, &b),
+        dice: dice_similarity(&a, &b),
+    }))
+}
+
+async fn detect_language(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DetectRequest>,
+) -> Result<Json<DetectResponse>, ApiError> {
+    let unavailable = || {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "no language profiles loaded; start the server with --profiles DIR".to_string(),
+        )
+    };
+    let detector = state.detector.as_ref().ok_or_else(unavailable)?;
+    let (language, confidence) = detector.detect(&request.text).ok_or_else(unavailable)?;
+    Ok(Json(DetectResponse {
+        language: language.to_string(),
+        confidence,
+    }))
+}
+
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/ngrams", post(ngrams))
+        .route("/similarity", post(similarity))
+        .route("/detect-language", post(detect_language))
+        .with_state(Arc::new(state))
+}
+
+/// Trains a detector on character 1- to 3-grams, one language per `LANG.txt` file.
+fn load_profiles(dir: &Path) -> io::Result<LanguageDetector> {
+    let mut detector = LanguageDetector::new(&[1, 2, 3]);
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            let language = path.file_stem().unwrap_or_default().to_string_lossy();
+            detector.add_sample(&language, &std::fs::read_to_string(&path)?);
+        }
+    }
+    Ok(detector)
+}
+
+fn cli() -> Command {
+    Command::new("ngram-server")
+        .about("Serve n-gram generation, similarity and language detection over HTTP")
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(
+            Arg::new("addr")
+                .long("addr")
+                .value_name("HOST:PORT")
+                .help("Address to listen on")
+                .default_value("127.0.0.1:8080"),
+        )
+        .arg(
+            Arg::new("profiles")
+                .long("profiles")
+                .value_name("DIR")
+                .help("Directory of LANG.txt sample files for /detect-language"),
+        )
+}
+
+async fn serve() -> io::Result<()> {
+    let matches = cli().get_matches();
+    let addr: &String = matches.get_one("addr").expect("has a default");
+    let detector = matches
+        .get_one::<String>("profiles")
+        .map(|dir| {
+            load_profiles(Path::new(dir))
+                .map_err(|e| io::Error::new(e.kind(), format!("{dir}: {e}")))
+        })
+        .transpose()?;
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("ngram-server: listening on {}", listener.local_addr()?);
+    axum::serve(listener, router(AppState { detector })).await
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match serve().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ngram-server: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the handlers directly, including detection without profiles
+    #[tokio::test]
+    async fn test_handlers() {
+        let request = NGramsRequest {
+            text: "a b c".to_string(),
+            n: vec![2],
+            delimiter: Some("_".to_string()),
+            char: false,
+        };
+        assert_eq!(ngrams(Json(request)).await.ngrams, ["a_b", "b_c"]);
+
+        let request = SimilarityRequest {
+            a: "night".to_string(),
+            b: "nacht".to_string(),
+            n: 2,
+            char: true,
+        };
+        let Json(scores) = similarity(Json(request)).await.ok().unwrap();
+        assert_eq!(scores.jaccard, 1.0 / 7.0);
+        assert_eq!(scores.dice, 0.25);
+
+        let state = Arc::new(AppState { detector: None });
+        let request = DetectRequest {
+            text: "hello".to_string(),
+        };
+        let error = detect_language(State(state), Json(request))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.0, StatusCode::SERVICE_UNAVAILABLE);
+        cli().debug_assert();
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::{self, BufRead, Write};
+
+use clap::{Arg, ArgMatches, Command, value_parser};
+use ngram_rs::{EntryOrder, NGramCounter, Placeholders, tokenize_whitespace};
+use rayon::prelude::*;
+
+pub fn command() -> Command {
+    Command::new("count")
+        .about("Count n-grams across files in parallel and print the most frequent")
+        .arg(crate::n_arg("1"))
+        .arg(crate::delimiter_arg())
+        .arg(
+            Arg::new("top-k")
+                .long("top-k")
+                .short('k')
+                .value_name("K")
+                .help("Only print the K most frequent n-grams")
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("min-count")
+                .long("min-count")
+                .short('m')
+                .value_name("COUNT")
+                .help("Drop n-grams seen fewer than COUNT times")
+                .default_value("1")
+                .value_parser(value_parser!(usize)),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("KEY")
+                .help("Order rows by decreasing count or by order and n-gram")
+                .value_parser(["count", "ngram"])
+                .default_value("count"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .value_name("FORMAT")
+                .help("Output format; parquet requires --out")
+                .value_parser(["tsv", "json", "parquet"])
+                .default_value("tsv"),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .short('o')
+                .value_name("PATH")
+                .help("Write to PATH instead of standard output"),
+        )
+        .arg(crate::placeholders_arg())
+        .arg(crate::files_arg())
+}
+
+/// Counts the n-grams of every line of one input.
+fn count_input(
+    path: &str,
+    n_range: &[usize],
+    delimiter: &str,
+    placeholders: Option<&Placeholders>,
+) -> io::Result<NGramCounter> {
+    let mut counter = NGramCounter::new();
+    for line in crate::open_input(path)?.lines() {
+        let line = line?;
+        let tokens: Vec<&str> = tokenize_whitespace(&line)
+            .map(|token| placeholders.map_or(token, |placeholders| placeholders.replace(token)))
+            .collect();
+        counter.add_document(&tokens, n_range, Some(delimiter));
+    }
+    Ok(counter)
+}
+
+/// Counts every input on the rayon thread pool and prints the filtered table.
+///
+/// Rows are sorted by decreasing count, then by order and n-gram, or with
+/// `--sort ngram` by order and n-gram only, so the output is the same for every run
+/// and thread count. `--top-k` always keeps the most frequent rows.
+pub fn run(matches: &ArgMatches, stdout: &mut impl Write) -> io::Result<()> {
+    let n_range: &Vec<usize> = matches.get_one("n").expect("has a default");
+    let delimiter: &String = matches.get_one("delimiter").expect("has a default");
+    let min_count = *matches
+        .get_one::<usize>("min-count")
+        .expect("has a default");
+    let top_k = matches
+        .get_one::<usize>("top-k")
+        .copied()
+        .unwrap_or(usize::MAX);
+    let sort: &String = matches.get_one("sort").expect("has a default");
+    let format: &String = matches.get_one("output").expect("has a default");
+    let out_path = matches.get_one::<String>("out");
+    let placeholders = crate::placeholders(matches);
+
+    let counter = crate::input_paths(matches)
+        .into_par_iter()
+        .map(|path| count_input(path, n_range, delimiter, placeholders.as_ref()))
+        .try_reduce(NGramCounter::new, |mut a, b| {
+            a.merge(&b);
+            Ok(a)
+        })?;
+
+    let mut rows: Vec<(&str, usize, usize)> = counter
+        .entries(EntryOrder::Frequency)
+        .into_iter()
+        .filter(|&(_, _, count)| count >= min_count)
+        .take(top_k)
+        .collect();
+    if sort == "ngram" {
+        rows.sort_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)));
+    }
+
+    if format == "parquet" {
+        return write_parquet(&rows, out_path);
+    }
+
+    let mut file_out;
+    let out: &mut dyn Write = match out_path {
+        Some(path) => {
+            file_out = io::BufWriter::new(std::fs::File::create(path)?);
+            &mut file_out
+        }
+        None => stdout,
+    };
+    match format.as_str() {
+        "json" => write_json(&rows, out)?,
+        _ => {
+            for (ngram, n, count) in &rows {
+                writeln!(out, "{ngram}\t{n}\t{count}")?;
+            }
+        }
+    }
+    out.flush()
+}
+
+/// Writes rows as a JSON array of `{"ngram", "n", "count"}` objects.
+fn write_json(rows: &[(&str, usize, usize)], out: &mut dyn Write) -> io::Result<()> {
+    let rows: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|&(ngram, n, count)| serde_json::json!({ "ngram": ngram, "n": n, "count": count }))
+        .collect();
+    serde_json::to_writer(&mut *out, &rows)?;
+    writeln!(out)
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(rows: &[(&str, usize, usize)], path: Option<&String>) -> io::Result<()> {
+    let path = path.ok_or_else(|| io::Error::other("--output parquet requires --out PATH"))?;
+    let mut table = NGramCounter::new();
+    for &(ngram, n, count) in rows {
+        table.add(ngram, n, count);
+    }
+    table.write_parquet(path).map_err(io::Error::other)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(_rows: &[(&str, usize, usize)], _path: Option<&String>) -> io::Result<()> {
+    Err(io::Error::other(
+        "parquet output needs ngram to be built with the `parquet` feature",
+    ))
+}
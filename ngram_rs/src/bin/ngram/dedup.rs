@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, ArgMatches, Command, value_parser};
+use ngram_rs::{MinHash, jaccard_similarity};
+use rayon::prelude::*;
+
+use crate::sim::{char_arg, shingle_n_arg, shingles};
+
+/// Hashes per MinHash signature.
+const NUM_HASHES: usize = 128;
+
+pub fn command() -> Command {
+    Command::new("dedup")
+        .about("List clusters of near-duplicate files under a directory")
+        .arg(shingle_n_arg())
+        .arg(char_arg())
+        .arg(
+            Arg::new("threshold")
+                .long("threshold")
+                .short('t')
+                .value_name("SCORE")
+                .help("Minimum Jaccard similarity for two files to be duplicates")
+                .default_value("0.8")
+                .value_parser(value_parser!(f64)),
+        )
+        .arg(Arg::new("dir").value_name("DIR").required(true))
+}
+
+/// Collects every regular file under `dir`, recursively, in path order.
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns every pair `(i, j)`, `i < j`, of files sharing at least one band key.
+fn candidate_pairs(keys: &[Vec<u64>]) -> HashSet<(usize, usize)> {
+    let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (i, file_keys) in keys.iter().enumerate() {
+        for &key in file_keys {
+            buckets.entry(key).or_default().push(i);
+        }
+    }
+    let mut pairs = HashSet::new();
+    for bucket in buckets.values() {
+        for (a, &i) in bucket.iter().enumerate() {
+            pairs.extend(bucket[a + 1..].iter().map(|&j| (i, j)));
+        }
+    }
+    pairs
+}
+
+/// Returns the root of `i`, halving paths as it goes.
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Finds near-duplicate files and prints each cluster on one line, tab separated.
+///
+/// Files are blocked by the LSH band keys of their [`MinHash`] signatures, and only
+/// files sharing a key are compared, exactly, on the rayon thread pool. Bands are
+/// tuned so that a pair at the threshold shares a key with probability at least 0.99,
+/// so duplicates are rarely missed. Dissimilar files are rarely compared, and those
+/// that share a key by chance are dropped by the exact check.
+///
+/// Clusters are the connected components of the "at least `threshold` similar"
+/// relation, so two members of a cluster may be linked only through a third file.
+pub fn run(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    let n = *matches.get_one::<usize>("n").expect("has a default");
+    let chars = matches.get_flag("char");
+    let threshold = *matches.get_one::<f64>("threshold").expect("has a default");
+    let dir = Path::new(matches.get_one::<String>("dir").expect("required"));
+
+    let mut files = Vec::new();
+    walk(dir, &mut files)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", dir.display())))?;
+    files.sort();
+    let sets: Vec<HashSet<String>> = files
+        .par_iter()
+        .map(|path| shingles(path, n, chars))
+        .collect::<io::Result<_>>()?;
+
+    let minhash = MinHash::new(NUM_HASHES).threshold(threshold);
+    let keys: Vec<Vec<u64>> = sets
+        .par_iter()
+        .map(|set| minhash.band_keys(&minhash.signature(set)))
+        .collect();
+    let candidates: Vec<(usize, usize)> = candidate_pairs(&keys).into_iter().collect();
+    let pairs: Vec<(usize, usize)> = candidates
+        .into_par_iter()
+        .filter(|&(i, j)| jaccard_similarity(&sets[i], &sets[j]) >= threshold)
+        .collect();
+
+    let mut parents: Vec<usize> = (0..files.len()).collect();
+    for (i, j) in pairs {
+        let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+        parents[a.max(b)] = a.min(b);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = vec![Vec::new(); files.len()];
+    for i in 0..files.len() {
+        let root = find(&mut parents, i);
+        clusters[root].push(i);
+    }
+    for cluster in clusters.into_iter().filter(|c| c.len() > 1) {
+        let paths: Vec<String> = cluster
+            .iter()
+            .map(|&i| files[i].display().to_string())
+            .collect();
+        writeln!(out, "{}", paths.join("\t"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that only files sharing a band key become candidates, once per pair
+    #[test]
+    fn test_candidate_pairs() {
+        let keys = vec![vec![1, 2], vec![3, 2], vec![4, 5], vec![1, 2]];
+        let pairs = candidate_pairs(&keys);
+        assert_eq!(pairs, HashSet::from([(0, 1), (0, 3), (1, 3)]));
+        assert!(candidate_pairs(&[vec![1], vec![2]]).is_empty());
+    }
+
+    /// Tests that union-find merges transitively linked files under the smallest index
+    #[test]
+    fn test_find_merges_clusters() {
+        let mut parents = vec![0, 0, 1, 3];
+        assert_eq!(find(&mut parents, 2), 0);
+        assert_eq!(find(&mut parents, 3), 3);
+    }
+}
@@ -0,0 +1,33 @@
+use std::io::{self, BufRead, Write};
+
+use clap::{ArgMatches, Command};
+use ngram_rs::{tokenize_whitespace, write_ngrams_into};
+
+pub fn command() -> Command {
+    Command::new("generate")
+        .about("Print the n-grams of each input line, one per line")
+        .arg(crate::n_arg("1"))
+        .arg(crate::delimiter_arg())
+        .arg(crate::files_arg())
+}
+
+/// Tokenizes every input line on whitespace and writes its n-grams to `out`.
+///
+/// Lines are independent documents: no n-gram spans a line break.
+pub fn run(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    let n_range: &Vec<usize> = matches.get_one("n").expect("has a default");
+    let delimiter: &String = matches.get_one("delimiter").expect("has a default");
+
+    let mut ngrams = Vec::new();
+    for path in crate::input_paths(matches) {
+        for line in crate::open_input(path)?.lines() {
+            let line = line?;
+            let tokens: Vec<&str> = tokenize_whitespace(&line).collect();
+            write_ngrams_into(&tokens, n_range, Some(delimiter), &mut ngrams);
+            for ngram in &ngrams {
+                writeln!(out, "{ngram}")?;
+            }
+        }
+    }
+    Ok(())
+}
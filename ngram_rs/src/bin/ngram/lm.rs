@@ -0,0 +1,121 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use clap::{Arg, ArgMatches, Command};
+use ngram_rs::{LanguageModel, Smoothing, tokenize_whitespace};
+
+pub fn command() -> Command {
+    Command::new("lm")
+        .about("Train and evaluate word n-gram language models")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("train")
+                .about("Train a model on one sentence per line and save it")
+                .arg(
+                    Arg::new("order")
+                        .long("order")
+                        .value_name("N")
+                        .help("Largest n-gram size of the model")
+                        .default_value("3")
+                        .value_parser(crate::parse_size),
+                )
+                .arg(
+                    Arg::new("smoothing")
+                        .long("smoothing")
+                        .value_name("METHOD")
+                        .help("Smoothing method: Kneser-Ney or add-one")
+                        .value_parser(["kn", "laplace"])
+                        .default_value("kn"),
+                )
+                .arg(
+                    Arg::new("out")
+                        .long("out")
+                        .short('o')
+                        .value_name("PATH")
+                        .help("Where to write the model")
+                        .required(true),
+                )
+                .arg(crate::placeholders_arg())
+                .arg(crate::files_arg()),
+        )
+        .subcommand(
+            Command::new("score")
+                .about("Report the perplexity of a model on one sentence per line")
+                .arg(Arg::new("model").value_name("MODEL").required(true))
+                .arg(crate::placeholders_arg())
+                .arg(crate::files_arg()),
+        )
+}
+
+/// Reads every non-blank input line as one whitespace-tokenized sentence, replacing
+/// numbers, URLs and email addresses with `--placeholders`.
+fn read_sentences(matches: &ArgMatches) -> io::Result<Vec<Vec<String>>> {
+    let placeholders = crate::placeholders(matches);
+    let mut sentences = Vec::new();
+    for path in crate::input_paths(matches) {
+        for line in crate::open_input(path)?.lines() {
+            let line = line?;
+            let tokens: Vec<String> = tokenize_whitespace(&line)
+                .map(|token| match &placeholders {
+                    Some(placeholders) => placeholders.replace(token).to_string(),
+                    None => token.to_string(),
+                })
+                .collect();
+            if !tokens.is_empty() {
+                sentences.push(tokens);
+            }
+        }
+    }
+    Ok(sentences)
+}
+
+pub fn run(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    match matches.subcommand() {
+        Some(("train", sub)) => train(sub, out),
+        Some(("score", sub)) => score(sub, out),
+        _ => unreachable!("subcommand_required is set"),
+    }
+}
+
+fn train(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    let order = *matches.get_one::<usize>("order").expect("has a default");
+    let smoothing = match matches
+        .get_one::<String>("smoothing")
+        .expect("has a default")
+        .as_str()
+    {
+        "laplace" => Smoothing::Laplace,
+        _ => Smoothing::KneserNey,
+    };
+    let path: &String = matches.get_one("out").expect("required");
+
+    let sentences = read_sentences(matches)?;
+    let model = LanguageModel::train(order, smoothing, &sentences);
+    let mut writer = BufWriter::new(File::create(path)?);
+    model.write_to(&mut writer)?;
+    writer.flush()?;
+
+    writeln!(out, "sentences\t{}", sentences.len())?;
+    writeln!(out, "vocabulary\t{}", model.vocab_size())
+}
+
+fn score(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    let path: &String = matches.get_one("model").expect("required");
+    let model = File::open(path)
+        .and_then(|f| LanguageModel::read_from(&mut BufReader::new(f)))
+        .map_err(|e| io::Error::new(e.kind(), format!("{path}: {e}")))?;
+
+    let sentences = read_sentences(matches)?;
+    let perplexity = model.perplexity_batch(&sentences).corpus;
+    let tokens: usize = sentences.iter().map(|s| s.len() + 1).sum();
+    let log_prob = if tokens == 0 {
+        0.0
+    } else {
+        -perplexity.ln() * tokens as f64
+    };
+
+    writeln!(out, "sentences\t{}", sentences.len())?;
+    writeln!(out, "tokens\t{tokens}")?;
+    writeln!(out, "log_prob\t{log_prob:.4}")?;
+    writeln!(out, "perplexity\t{perplexity:.4}")
+}
@@ -0,0 +1,150 @@
+//! `ngram` command-line tool, built with the `cli` feature.
+//!
+//! ```text
+//! echo "the quick brown fox" | ngram generate --n 1,2 --delimiter _
+//! ngram count --n 2 --top-k 100 --min-count 5 --sort ngram --placeholders corpus/*.txt
+//! ngram sim a.txt b.txt --n 3 --char
+//! ngram dedup corpus/ --threshold 0.8
+//! ngram lm train --order 3 --smoothing kn --placeholders -o model.bin corpus.txt
+//! ngram lm score model.bin test.txt
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::ExitCode;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+mod count;
+mod dedup;
+mod generate;
+mod lm;
+mod sim;
+
+fn cli() -> Command {
+    Command::new("ngram")
+        .about("Generate and analyse n-grams from the command line")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(generate::command())
+        .subcommand(count::command())
+        .subcommand(sim::command())
+        .subcommand(dedup::command())
+        .subcommand(lm::command())
+}
+
+/// `--n` argument shared by subcommands: a comma-separated list of n-gram sizes.
+fn n_arg(default: &'static str) -> Arg {
+    Arg::new("n")
+        .long("n")
+        .short('n')
+        .value_name("SIZES")
+        .help("Comma-separated n-gram sizes, e.g. 1,2,3")
+        .default_value(default)
+        .value_parser(parse_n_range)
+}
+
+/// `--delimiter` argument placed between the tokens of an n-gram.
+fn delimiter_arg() -> Arg {
+    Arg::new("delimiter")
+        .long("delimiter")
+        .short('d')
+        .help("Separator placed between tokens")
+        .default_value(" ")
+}
+
+/// `--placeholders` flag replacing numbers, URLs and email addresses before counting.
+fn placeholders_arg() -> Arg {
+    Arg::new("placeholders")
+        .long("placeholders")
+        .help("Replace numbers, URLs and email addresses with <NUM>, <URL> and <EMAIL>")
+        .action(ArgAction::SetTrue)
+}
+
+/// Returns the placeholders to apply to tokens, if `--placeholders` is set.
+fn placeholders(matches: &ArgMatches) -> Option<ngram_rs::Placeholders> {
+    matches
+        .get_flag("placeholders")
+        .then(ngram_rs::Placeholders::new)
+}
+
+/// Positional input files; no file or `-` reads standard input.
+fn files_arg() -> Arg {
+    Arg::new("files")
+        .value_name("FILE")
+        .help("Input files; reads standard input when omitted or `-`")
+        .action(ArgAction::Append)
+}
+
+fn parse_n_range(value: &str) -> Result<Vec<usize>, String> {
+    value.split(',').map(parse_size).collect()
+}
+
+/// Parses a single n-gram size, rejecting 0.
+fn parse_size(value: &str) -> Result<usize, String> {
+    match value.trim().parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!("`{value}` is not a positive integer")),
+        Ok(n) => Ok(n),
+    }
+}
+
+/// Opens one input path; `-` is standard input.
+fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    if path == "-" {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    File::open(path)
+        .map(|f| Box::new(BufReader::new(f)) as Box<dyn BufRead>)
+        .map_err(|e| io::Error::new(e.kind(), format!("{path}: {e}")))
+}
+
+/// Returns the input paths named on the command line, or `-` for standard input.
+fn input_paths(matches: &ArgMatches) -> Vec<&str> {
+    let files: Vec<&str> = matches
+        .get_many::<String>("files")
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    if files.is_empty() { vec!["-"] } else { files }
+}
+
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+
+    let result = match matches.subcommand() {
+        Some(("generate", sub)) => generate::run(sub, &mut stdout),
+        Some(("count", sub)) => count::run(sub, &mut stdout),
+        Some(("sim", sub)) => sim::run(sub, &mut stdout),
+        Some(("dedup", sub)) => dedup::run(sub, &mut stdout),
+        Some(("lm", sub)) => lm::run(sub, &mut stdout),
+        _ => unreachable!("subcommand_required is set"),
+    }
+    .and_then(|()| stdout.flush());
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // A closed pipe (e.g. `| head`) is a normal way to stop reading
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("ngram: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of the `--n` list
+    #[test]
+    fn test_parse_n_range() {
+        assert_eq!(parse_n_range("1, 2,3"), Ok(vec![1, 2, 3]));
+        assert!(parse_n_range("1,0").is_err());
+        assert!(parse_n_range("two").is_err());
+        cli().debug_assert();
+    }
+}
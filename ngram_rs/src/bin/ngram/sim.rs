@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use ngram_rs::{dice_similarity, generate_char_ngrams, jaccard_similarity, tokenize_whitespace};
+
+pub fn command() -> Command {
+    Command::new("sim")
+        .about("Print the Jaccard and Dice similarity of the n-grams of two files")
+        .arg(shingle_n_arg())
+        .arg(char_arg())
+        .arg(Arg::new("a").value_name("A").required(true))
+        .arg(Arg::new("b").value_name("B").required(true))
+}
+
+/// `--n` argument for set comparisons, which use a single n-gram size.
+pub fn shingle_n_arg() -> Arg {
+    Arg::new("n")
+        .long("n")
+        .short('n')
+        .value_name("SIZE")
+        .help("N-gram size")
+        .default_value("3")
+        .value_parser(crate::parse_size)
+}
+
+/// `--char` flag switching from word to character n-grams.
+pub fn char_arg() -> Arg {
+    Arg::new("char")
+        .long("char")
+        .help("Compare character n-grams instead of word n-grams")
+        .action(ArgAction::SetTrue)
+}
+
+/// Reads a file and returns its set of distinct n-grams of size `n`.
+///
+/// Word n-grams are built over the whitespace tokens of the whole file, so they may
+/// span line breaks, which keeps reflowed copies of a text similar.
+pub fn shingles(path: &Path, n: usize, chars: bool) -> io::Result<HashSet<String>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    if chars {
+        return Ok(generate_char_ngrams(&text, &[n])
+            .into_iter()
+            .map(str::to_string)
+            .collect());
+    }
+    let tokens: Vec<&str> = tokenize_whitespace(&text).collect();
+    Ok(tokens.windows(n).map(|window| window.join(" ")).collect())
+}
+
+pub fn run(matches: &ArgMatches, out: &mut impl Write) -> io::Result<()> {
+    let n = *matches.get_one::<usize>("n").expect("has a default");
+    let chars = matches.get_flag("char");
+    let a = shingles(
+        Path::new(matches.get_one::<String>("a").expect("required")),
+        n,
+        chars,
+    )?;
+    let b = shingles(
+        Path::new(matches.get_one::<String>("b").expect("required")),
+        n,
+        chars,
+    )?;
+
+    writeln!(out, "jaccard\t{:.6}", jaccard_similarity(&a, &b))?;
+    writeln!(out, "dice\t{:.6}", dice_similarity(&a, &b))
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag shared between a long-running operation and whoever may abort it.
+///
+/// Clones share the same flag, so one clone can be handed to the operation and
+/// another kept by a request handler, a timeout or a signal handler. Operations check
+/// it between units of work (a line, a sentence, a document) and stop with
+/// [`Cancelled`] once it is set.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{CancelToken, Cancelled};
+///
+/// let token = CancelToken::new();
+/// let worker = token.clone();
+/// assert_eq!(worker.check(), Ok(()));
+///
+/// token.cancel();
+/// assert_eq!(worker.check(), Err(Cancelled));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every operation holding a clone of the token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once [`CancelToken::cancel`] has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns `Err(Cancelled)` once the token is cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Wraps a flag already shared with other code.
+impl From<Arc<AtomicBool>> for CancelToken {
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancelToken(flag)
+    }
+}
+
+/// The error returned by an operation stopped through its [`CancelToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Lets I/O-based operations report cancellation as an `Interrupted` error.
+impl From<Cancelled> for io::Error {
+    fn from(cancelled: Cancelled) -> Self {
+        io::Error::new(io::ErrorKind::Interrupted, cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that clones and wrapped flags share one state
+    #[test]
+    fn test_cancel_token() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancelToken::from(Arc::clone(&flag));
+        let clone = token.clone();
+        assert_eq!(clone.check(), Ok(()));
+
+        token.cancel();
+        assert!(clone.is_cancelled() && flag.load(Ordering::Relaxed));
+        assert_eq!(clone.check(), Err(Cancelled));
+        assert_eq!(
+            io::Error::from(Cancelled).kind(),
+            io::ErrorKind::Interrupted
+        );
+    }
+}
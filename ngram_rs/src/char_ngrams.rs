@@ -0,0 +1,370 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Generates character n-grams from a string for all specified n-values.
+///
+/// Windows are taken over Unicode scalar values (`char`s), not bytes, so multi-byte
+/// characters are never split. Every n-gram is a slice of `text`, which means no
+/// allocation happens beyond the result vector.
+///
+/// # Arguments
+///
+/// * `text` - The input string
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+///
+/// # Returns
+///
+/// A vector of string slices, all n-grams for the first n-value followed by those for
+/// the next. Zero and n-values longer than the text are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_char_ngrams;
+///
+/// assert_eq!(generate_char_ngrams("café", &[3]), vec!["caf", "afé"]);
+/// ```
+pub fn generate_char_ngrams<'a>(text: &'a str, n_range: &[usize]) -> Vec<&'a str> {
+    // boundaries[i] is the byte offset of the i-th char; the last entry is text.len()
+    let mut boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    boundaries.push(text.len());
+    windows_between(text, &boundaries, n_range)
+}
+
+/// Generates character n-grams from a string, counting grapheme clusters as characters.
+///
+/// Like [`generate_char_ngrams`], but windows are taken over extended grapheme clusters
+/// (UAX #29), so a letter and its combining accents, or an emoji sequence such as a
+/// flag or a family, always stay within one n-gram.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{generate_char_ngrams, generate_grapheme_ngrams};
+///
+/// // "é" written as "e" followed by a combining acute accent
+/// let text = "ce\u{301}";
+/// assert_eq!(generate_grapheme_ngrams(text, &[1]), ["c", "e\u{301}"]);
+/// assert_eq!(generate_char_ngrams(text, &[1]), ["c", "e", "\u{301}"]);
+/// ```
+#[cfg(feature = "unicode")]
+pub fn generate_grapheme_ngrams<'a>(text: &'a str, n_range: &[usize]) -> Vec<&'a str> {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let mut boundaries: Vec<usize> = text.grapheme_indices(true).map(|(i, _)| i).collect();
+    boundaries.push(text.len());
+    windows_between(text, &boundaries, n_range)
+}
+
+/// Slices `text` into windows of `n` units for every n, where `boundaries` holds the
+/// byte offset of each unit followed by `text.len()`.
+fn windows_between<'a>(text: &'a str, boundaries: &[usize], n_range: &[usize]) -> Vec<&'a str> {
+    let num_units = boundaries.len() - 1;
+    let mut result = Vec::with_capacity(crate::ngram_count(num_units, n_range));
+
+    for &n in n_range {
+        if n == 0 || n > num_units {
+            continue;
+        }
+
+        for start in 0..=num_units - n {
+            result.push(&text[boundaries[start]..boundaries[start + n]]);
+        }
+    }
+
+    result
+}
+
+/// Generates byte n-grams from a byte string for all specified n-values.
+///
+/// The byte counterpart of [`generate_char_ngrams`], for data that is not text or
+/// whose encoding must not be interpreted, such as hashes or DNA sequences. Every
+/// n-gram is a slice of `bytes`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_byte_ngrams;
+///
+/// assert_eq!(generate_byte_ngrams(b"GATC", &[3]), [b"GAT", b"ATC"]);
+/// ```
+pub fn generate_byte_ngrams<'a>(bytes: &'a [u8], n_range: &[usize]) -> Vec<&'a [u8]> {
+    let mut result = Vec::with_capacity(crate::ngram_count(bytes.len(), n_range));
+    for &n in n_range {
+        if n > 0 {
+            result.extend(bytes.windows(n));
+        }
+    }
+    result
+}
+
+/// What [`SymbolPolicy`] does with one class of characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SymbolAction {
+    /// Leave the characters as they are
+    #[default]
+    Keep,
+    /// Remove the characters, joining their neighbours
+    Drop,
+    /// Replace every run of the characters with a single placeholder character
+    Placeholder(char),
+}
+
+/// The kinds of characters a [`SymbolPolicy`] handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SymbolClass {
+    Punctuation,
+    Whitespace,
+    Emoji,
+}
+
+impl SymbolClass {
+    fn of(c: char) -> Option<Self> {
+        if c.is_whitespace() {
+            return Some(SymbolClass::Whitespace);
+        }
+        match c {
+            '\u{200d}'
+            | '\u{20e3}'
+            | '\u{fe0f}'
+            | '\u{2600}'..='\u{27bf}'
+            | '\u{2b00}'..='\u{2bff}'
+            | '\u{1f000}'..='\u{1faff}'
+            | '\u{e0020}'..='\u{e007f}' => Some(SymbolClass::Emoji),
+            '\u{a1}'..='\u{a9}'
+            | '\u{ab}'..='\u{b1}'
+            | '\u{b4}'
+            | '\u{b6}'..='\u{b8}'
+            | '\u{bb}'
+            | '\u{bf}'
+            | '\u{d7}'
+            | '\u{f7}'
+            | '\u{2010}'..='\u{2027}'
+            | '\u{2030}'..='\u{205e}'
+            | '\u{20a0}'..='\u{20cf}'
+            | '\u{3001}'..='\u{3003}'
+            | '\u{3008}'..='\u{3011}'
+            | '\u{3014}'..='\u{301f}'
+            | '\u{30fb}'
+            | '\u{ff01}'..='\u{ff0f}'
+            | '\u{ff1a}'..='\u{ff20}'
+            | '\u{ff3b}'..='\u{ff40}'
+            | '\u{ff5b}'..='\u{ff65}' => Some(SymbolClass::Punctuation),
+            c if c.is_ascii_punctuation() => Some(SymbolClass::Punctuation),
+            _ => None,
+        }
+    }
+}
+
+/// How [`generate_char_ngrams_with`] treats punctuation, whitespace and emoji before
+/// windowing.
+///
+/// Similarity tasks disagree on these characters: names match better without
+/// punctuation, while short-text classifiers may want to know that an emoji was there
+/// without telling them apart. The default keeps everything, as
+/// [`generate_char_ngrams`] does.
+///
+/// Punctuation covers ASCII punctuation and symbols and the common punctuation, currency
+/// and fullwidth blocks; emoji covers the pictographic blocks and the joiners and
+/// modifiers of emoji sequences; whitespace is any Unicode white space.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{SymbolAction, SymbolPolicy};
+///
+/// let policy = SymbolPolicy {
+///     punctuation: SymbolAction::Drop,
+///     whitespace: SymbolAction::Placeholder('_'),
+///     emoji: SymbolAction::Placeholder('*'),
+/// };
+/// assert_eq!(policy.apply("Hi,  there! 🎉🎉"), "Hi_there_*");
+/// assert_eq!(SymbolPolicy::default().apply("a, b"), "a, b");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SymbolPolicy {
+    /// Punctuation marks and symbols
+    pub punctuation: SymbolAction,
+    /// White space
+    pub whitespace: SymbolAction,
+    /// Emoji, including the parts of emoji sequences
+    pub emoji: SymbolAction,
+}
+
+impl SymbolPolicy {
+    fn action(&self, class: SymbolClass) -> SymbolAction {
+        match class {
+            SymbolClass::Punctuation => self.punctuation,
+            SymbolClass::Whitespace => self.whitespace,
+            SymbolClass::Emoji => self.emoji,
+        }
+    }
+
+    /// Applies the policy to `text`, borrowing it when nothing changes.
+    ///
+    /// Dropped characters do not end a run, so a placeholder also stands for runs
+    /// broken only by dropped characters.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let changes =
+            |c| SymbolClass::of(c).is_some_and(|class| self.action(class) != SymbolAction::Keep);
+        if !text.chars().any(changes) {
+            return Cow::Borrowed(text);
+        }
+
+        let mut cleaned = String::with_capacity(text.len());
+        let mut run = None;
+        for c in text.chars() {
+            let class = SymbolClass::of(c);
+            match class.map_or(SymbolAction::Keep, |class| self.action(class)) {
+                SymbolAction::Keep => {
+                    cleaned.push(c);
+                    run = None;
+                }
+                SymbolAction::Drop => {}
+                SymbolAction::Placeholder(placeholder) => {
+                    if run != class {
+                        cleaned.push(placeholder);
+                        run = class;
+                    }
+                }
+            }
+        }
+        Cow::Owned(cleaned)
+    }
+}
+
+/// Generates character n-grams after applying a [`SymbolPolicy`] to the text.
+///
+/// The n-grams borrow from `text` when the policy leaves it unchanged, and are owned
+/// otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{SymbolAction, SymbolPolicy, generate_char_ngrams_with};
+///
+/// let policy = SymbolPolicy { punctuation: SymbolAction::Drop, ..SymbolPolicy::default() };
+/// assert_eq!(generate_char_ngrams_with("O'Neil", &[3], policy), ["ONe", "Nei", "eil"]);
+/// ```
+pub fn generate_char_ngrams_with<'a>(
+    text: &'a str,
+    n_range: &[usize],
+    policy: SymbolPolicy,
+) -> Vec<Cow<'a, str>> {
+    match policy.apply(text) {
+        Cow::Borrowed(text) => generate_char_ngrams(text, n_range)
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect(),
+        Cow::Owned(cleaned) => generate_char_ngrams(&cleaned, n_range)
+            .into_iter()
+            .map(|ngram| Cow::Owned(ngram.into()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests character windows on multi-byte input
+    #[test]
+    fn test_char_ngrams_multibyte() {
+        let result = generate_char_ngrams("añb", &[1, 2, 4]);
+        assert_eq!(result, vec!["a", "ñ", "b", "añ", "ñb"]);
+    }
+
+    /// Tests empty input and invalid n-values
+    #[test]
+    fn test_char_ngrams_empty() {
+        assert!(generate_char_ngrams("", &[1, 2]).is_empty());
+        assert!(generate_char_ngrams("abc", &[0]).is_empty());
+    }
+
+    /// Tests that grapheme n-grams keep emoji sequences and combining marks whole
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_grapheme_ngrams() {
+        let flag = "\u{1f1eb}\u{1f1f7}";
+        let text = format!("a{flag}n\u{303}");
+        assert_eq!(
+            generate_grapheme_ngrams(&text, &[2]),
+            [format!("a{flag}"), format!("{flag}n\u{303}")]
+        );
+        assert_eq!(generate_char_ngrams(&text, &[2]).len(), 4);
+        assert!(generate_grapheme_ngrams("", &[1]).is_empty());
+    }
+
+    /// Tests that byte n-grams split multi-byte characters, unlike char n-grams
+    #[test]
+    fn test_byte_ngrams() {
+        let result = generate_byte_ngrams("añ".as_bytes(), &[1, 2, 4]);
+        assert_eq!(
+            result,
+            [&b"a"[..], &[0xc3], &[0xb1], &[b'a', 0xc3], &[0xc3, 0xb1]]
+        );
+        assert!(generate_byte_ngrams(b"", &[1]).is_empty());
+        assert!(generate_byte_ngrams(b"ab", &[0]).is_empty());
+    }
+
+    /// Tests every action on each class, runs across dropped characters and borrowing
+    #[test]
+    fn test_symbol_policy() {
+        let drop_all = SymbolPolicy {
+            punctuation: SymbolAction::Drop,
+            whitespace: SymbolAction::Drop,
+            emoji: SymbolAction::Drop,
+        };
+        assert_eq!(drop_all.apply("¡Hola, 世界! 👍🏽 «ok»"), "Hola世界ok");
+
+        let placeholders = SymbolPolicy {
+            punctuation: SymbolAction::Placeholder('.'),
+            whitespace: SymbolAction::Placeholder(' '),
+            emoji: SymbolAction::Placeholder('E'),
+        };
+        assert_eq!(
+            placeholders.apply("no!!!\t\n  way 👨\u{200d}👩\u{200d}👧"),
+            "no. way E"
+        );
+
+        // The whitespace between the emoji is dropped, so they form a single run
+        let emoji_only = SymbolPolicy {
+            whitespace: SymbolAction::Drop,
+            emoji: SymbolAction::Placeholder('E'),
+            ..SymbolPolicy::default()
+        };
+        assert_eq!(emoji_only.apply("hi 😀 😀 there"), "hiEthere");
+
+        assert!(matches!(
+            placeholders.apply("plain"),
+            Cow::Borrowed("plain")
+        ));
+        assert!(matches!(
+            SymbolPolicy::default().apply("a, b 😀"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    /// Tests that n-grams borrow when the policy changes nothing
+    #[test]
+    fn test_char_ngrams_with() {
+        let policy = SymbolPolicy {
+            whitespace: SymbolAction::Placeholder('_'),
+            ..SymbolPolicy::default()
+        };
+        let ngrams = generate_char_ngrams_with("a  b", &[2], policy);
+        assert_eq!(ngrams, ["a_", "_b"]);
+        assert!(matches!(ngrams[0], Cow::Owned(_)));
+
+        let ngrams = generate_char_ngrams_with("ab", &[1], policy);
+        assert!(matches!(
+            ngrams[..],
+            [Cow::Borrowed("a"), Cow::Borrowed("b")]
+        ));
+        assert_eq!(
+            generate_char_ngrams_with("a, b", &[2], SymbolPolicy::default()),
+            generate_char_ngrams("a, b", &[2])
+        );
+    }
+}
@@ -0,0 +1,549 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::PathBuf;
+
+use rayon::prelude::*;
+
+use crate::{CancelToken, NGramCounter, tokenize_whitespace};
+
+/// Counts n-grams over corpora larger than memory.
+///
+/// Documents are read in chunks of `chunk_size`. Each chunk is counted in parallel on
+/// the rayon thread pool and its counts are accumulated in memory. Once they outgrow
+/// the [`memory_budget`](ChunkedProcessor::memory_budget), they are sorted and spilled
+/// to an anonymous temporary file, so at most one chunk of documents and the budget's
+/// worth of counts are held in memory. When the input is exhausted the spill files are
+/// merged with k-way merges of at most 64 files at a time, which also bounds the open
+/// file descriptors, and every n-gram is passed to a sink once, with its total count.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::ChunkedProcessor;
+///
+/// let docs = ["a b a", "b a", "a b"].map(|d| Ok(d.to_string()));
+/// let mut table = Vec::new();
+/// ChunkedProcessor::new(&[2])
+///     .chunk_size(2)
+///     .process(docs, |ngram, _n, count| {
+///         table.push((ngram.to_string(), count));
+///         Ok(())
+///     })
+///     .unwrap();
+///
+/// assert_eq!(table, [("a b".to_string(), 2), ("b a".to_string(), 2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChunkedProcessor {
+    n_range: Vec<usize>,
+    delimiter: String,
+    chunk_size: usize,
+    memory_budget: usize,
+    spill_dir: Option<PathBuf>,
+    cancel: Option<CancelToken>,
+    /// The most spill files merged at once
+    fan_in: usize,
+}
+
+/// The default memory budget, 256 MiB.
+const DEFAULT_MEMORY_BUDGET: usize = 256 << 20;
+
+/// The most spill files open in one k-way merge.
+const MAX_FAN_IN: usize = 64;
+
+impl ChunkedProcessor {
+    /// Creates a processor counting the n-gram sizes in `n_range`.
+    ///
+    /// Defaults to chunks of 100 000 documents, a single space delimiter, a memory
+    /// budget of 256 MiB and spill files in the system temporary directory.
+    pub fn new(n_range: &[usize]) -> Self {
+        ChunkedProcessor {
+            n_range: n_range.to_vec(),
+            delimiter: " ".to_string(),
+            chunk_size: 100_000,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            spill_dir: None,
+            cancel: None,
+            fan_in: MAX_FAN_IN,
+        }
+    }
+
+    /// Sets the number of documents read and counted per chunk (at least 1).
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets the delimiter placed between tokens.
+    pub fn delimiter(mut self, delimiter: &str) -> Self {
+        self.delimiter = delimiter.to_string();
+        self
+    }
+
+    /// Sets how many bytes the accumulated counts may take before they are spilled.
+    ///
+    /// Counts are merged across chunks and spilled only when their estimated size
+    /// exceeds `bytes`, so there are fewer, larger spill files to merge, and a corpus
+    /// whose counts fit in the budget never touches the disk. The estimate covers the
+    /// n-gram bytes and the per-entry overhead of the hash tables, not the documents
+    /// of the current chunk. Defaults to 256 MiB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::ChunkedProcessor;
+    ///
+    /// let docs = (0..1_000).map(|i| Ok(format!("doc {} of many", i % 10)));
+    /// let counter = ChunkedProcessor::new(&[1, 2])
+    ///     .chunk_size(100)
+    ///     .memory_budget(256 << 20)
+    ///     .count(docs)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(counter.get("of many"), 1_000);
+    /// ```
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Sets the directory where spill files are created.
+    pub fn spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets a token checked before every chunk and every merged n-gram, aborting the
+    /// count with an [`io::ErrorKind::Interrupted`] error once cancelled.
+    pub fn cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Counts a corpus and streams the merged counts to `sink`.
+    ///
+    /// Documents are tokenized on whitespace. `sink` receives `(ngram, n, count)` once
+    /// per distinct n-gram, sorted by `n` and then by the bytes of the n-gram, so the
+    /// sequence is the same for every run, thread count, chunk size and memory budget.
+    /// An error returned by the sink aborts the merge. Spill files are removed even on
+    /// error.
+    ///
+    /// # Returns
+    ///
+    /// The number of documents processed.
+    pub fn process<I, F>(&self, documents: I, mut sink: F) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = io::Result<String>>,
+        F: FnMut(&str, usize, usize) -> io::Result<()>,
+    {
+        let check = || match &self.cancel {
+            Some(cancel) => cancel.check().map_err(io::Error::from),
+            None => Ok(()),
+        };
+        let mut sink = |ngram: &str, n: usize, count: usize| {
+            check()?;
+            sink(ngram, n, count)
+        };
+        // Spill files by merge level, each level holding fewer than `fan_in` files
+        let mut levels: Vec<Vec<File>> = Vec::new();
+        let mut pending = NGramCounter::new();
+        // The estimated size of `pending`, grown by each chunk's new entries
+        let mut pending_size = 0;
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        let mut total = 0;
+
+        let mut documents = documents.into_iter();
+        loop {
+            check()?;
+            chunk.clear();
+            for document in documents.by_ref().take(self.chunk_size) {
+                chunk.push(document?);
+            }
+            if chunk.is_empty() {
+                break;
+            }
+            total += chunk.len();
+            let counts = self.count_chunk(&chunk);
+            pending_size += added_size(&pending, &counts);
+            pending.merge(&counts);
+            if pending_size > self.memory_budget {
+                let spill = self.spill(std::mem::take(&mut pending))?;
+                self.add_spill(&mut levels, spill)?;
+                pending_size = 0;
+            }
+        }
+
+        let mut spills: Vec<File> = levels.into_iter().flatten().collect();
+        if spills.is_empty() {
+            // Everything fit in the budget, so there is nothing to merge
+            for n in pending.orders() {
+                for (ngram, count) in pending.sorted_order(n) {
+                    sink(ngram, n, count)?;
+                }
+            }
+        } else {
+            if !pending.is_empty() {
+                spills.push(self.spill(pending)?);
+            }
+            // Merge in passes until a single merge stays within the fan-in
+            while spills.len() > self.fan_in {
+                check()?;
+                let mut groups = spills.into_iter();
+                spills = Vec::new();
+                loop {
+                    let group: Vec<File> = groups.by_ref().take(self.fan_in).collect();
+                    if group.is_empty() {
+                        break;
+                    }
+                    spills.push(self.merge_to_file(group)?);
+                }
+            }
+            merge_spills(spills, &mut sink)?;
+        }
+        Ok(total)
+    }
+
+    /// Counts a corpus into an in-memory counter, using the same chunked pipeline.
+    ///
+    /// Useful when the final table fits in memory but the documents do not.
+    pub fn count<I>(&self, documents: I) -> io::Result<NGramCounter>
+    where
+        I: IntoIterator<Item = io::Result<String>>,
+    {
+        let mut counter = NGramCounter::new();
+        self.process(documents, |ngram, n, count| {
+            counter.add(ngram, n, count);
+            Ok(())
+        })?;
+        Ok(counter)
+    }
+
+    fn count_chunk(&self, chunk: &[String]) -> NGramCounter {
+        let per_thread = chunk.len().div_ceil(rayon::current_num_threads()).max(1);
+        chunk
+            .par_chunks(per_thread)
+            .map(|documents| {
+                let mut counter = NGramCounter::new();
+                for document in documents {
+                    let tokens: Vec<&str> = tokenize_whitespace(document).collect();
+                    counter.add_document(&tokens, &self.n_range, Some(&self.delimiter));
+                }
+                counter
+            })
+            .reduce(NGramCounter::new, |mut a, b| {
+                a.merge(&b);
+                a
+            })
+    }
+
+    /// Writes the counts sorted by `(n, ngram)` to an anonymous temporary file.
+    fn spill(&self, counter: NGramCounter) -> io::Result<File> {
+        self.write_spill(|writer| {
+            for n in counter.orders() {
+                for (ngram, count) in counter.sorted_order(n) {
+                    write_record(writer, n, ngram, count)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Merges sorted spill files into a single sorted spill file.
+    fn merge_to_file(&self, spills: Vec<File>) -> io::Result<File> {
+        self.write_spill(|writer| {
+            merge_spills(spills, &mut |ngram, n, count| {
+                write_record(writer, n, ngram, count)
+            })
+        })
+    }
+
+    /// Creates an anonymous temporary file, fills it with `write` and rewinds it.
+    fn write_spill<F>(&self, write: F) -> io::Result<File>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    {
+        let file = match &self.spill_dir {
+            Some(dir) => tempfile::tempfile_in(dir)?,
+            None => tempfile::tempfile()?,
+        };
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        let mut file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.rewind()?;
+        Ok(file)
+    }
+
+    /// Adds a spill file to the first level, merging every level that fills up into
+    /// one file of the next, so at most `fan_in` files per level stay open.
+    fn add_spill(&self, levels: &mut Vec<Vec<File>>, mut spill: File) -> io::Result<()> {
+        for level in 0.. {
+            if levels.len() == level {
+                levels.push(Vec::new());
+            }
+            levels[level].push(spill);
+            if levels[level].len() < self.fan_in {
+                break;
+            }
+            spill = self.merge_to_file(std::mem::take(&mut levels[level]))?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimates the memory `counts` adds to `pending` when merged into it: the bytes of
+/// every n-gram `pending` lacks plus its `String`, its count and the bookkeeping of its
+/// hash table slot.
+///
+/// Only the chunk's counts are walked, so tracking the size of `pending` this way costs
+/// time proportional to the chunk, not to everything accumulated so far.
+fn added_size(pending: &NGramCounter, counts: &NGramCounter) -> usize {
+    const ENTRY_OVERHEAD: usize = size_of::<String>() + size_of::<usize>() + 16;
+    counts
+        .iter()
+        .filter(|&(ngram, n, _)| pending.get_order(ngram, n) == 0)
+        .map(|(ngram, _, _)| ngram.len() + ENTRY_OVERHEAD)
+        .sum()
+}
+
+// Spill record layout, little endian: n (u64), ngram length (u64), ngram bytes, count (u64)
+
+fn write_record<W: Write>(writer: &mut W, n: usize, ngram: &str, count: usize) -> io::Result<()> {
+    writer.write_all(&(n as u64).to_le_bytes())?;
+    writer.write_all(&(ngram.len() as u64).to_le_bytes())?;
+    writer.write_all(ngram.as_bytes())?;
+    writer.write_all(&(count as u64).to_le_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads the next record, or `None` at a clean end of file.
+fn read_record<R: Read>(reader: &mut R) -> io::Result<Option<(usize, String, usize)>> {
+    let n = match read_u64(reader) {
+        Ok(n) => n as usize,
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut ngram = vec![0; read_u64(reader)? as usize];
+    reader.read_exact(&mut ngram)?;
+    let ngram =
+        String::from_utf8(ngram).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let count = read_u64(reader)? as usize;
+    Ok(Some((n, ngram, count)))
+}
+
+/// K-way merges sorted spill files, summing the counts of equal n-grams.
+fn merge_spills<F>(spills: Vec<File>, sink: &mut F) -> io::Result<()>
+where
+    F: FnMut(&str, usize, usize) -> io::Result<()>,
+{
+    let mut readers: Vec<BufReader<File>> = spills.into_iter().map(BufReader::new).collect();
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some((n, ngram, count)) = read_record(reader)? {
+            heap.push(Reverse((n, ngram, count, i)));
+        }
+    }
+
+    let mut current: Option<(usize, String, usize)> = None;
+    while let Some(Reverse((n, ngram, count, i))) = heap.pop() {
+        if let Some(next) = read_record(&mut readers[i])? {
+            heap.push(Reverse((next.0, next.1, next.2, i)));
+        }
+        match &mut current {
+            Some((cn, cngram, ccount)) if *cn == n && *cngram == ngram => *ccount += count,
+            _ => {
+                if let Some((cn, cngram, ccount)) = current.replace((n, ngram, count)) {
+                    sink(&cngram, cn, ccount)?;
+                }
+            }
+        }
+    }
+    if let Some((n, ngram, count)) = current {
+        sink(&ngram, n, count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that chunked counting matches counting everything in memory
+    #[test]
+    fn test_chunked_matches_in_memory() {
+        let texts: Vec<String> = (0..50)
+            .map(|i| format!("w{} w{} common w{} tail", i % 7, i % 3, i % 5))
+            .collect();
+
+        let mut expected = NGramCounter::new();
+        for text in &texts {
+            let tokens: Vec<&str> = tokenize_whitespace(text).collect();
+            expected.add_document(&tokens, &[1, 2, 3], Some("_"));
+        }
+
+        let processor = ChunkedProcessor::new(&[1, 2, 3])
+            .chunk_size(6)
+            .delimiter("_");
+        let counted = processor.count(texts.iter().cloned().map(Ok)).unwrap();
+        assert_eq!(counted, expected);
+
+        let mut previous: Option<(usize, String)> = None;
+        processor
+            .process(texts.into_iter().map(Ok), |ngram, n, _| {
+                let key = (n, ngram.to_string());
+                assert!(previous.as_ref().is_none_or(|p| *p < key));
+                previous = Some(key);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    /// Tests that a memory budget only spills once it is exceeded
+    #[test]
+    fn test_chunked_memory_budget() {
+        let texts: Vec<String> = (0..200).map(|i| format!("t{} u{} v", i, i % 9)).collect();
+        let expected = ChunkedProcessor::new(&[1, 2])
+            .chunk_size(7)
+            .count(texts.iter().cloned().map(Ok))
+            .unwrap();
+
+        // The spill directory does not exist, so any spill fails
+        let missing = std::env::temp_dir().join("ngram_rs_no_such_spill_dir");
+        let in_memory = ChunkedProcessor::new(&[1, 2])
+            .chunk_size(7)
+            .memory_budget(usize::MAX)
+            .spill_dir(&missing)
+            .count(texts.iter().cloned().map(Ok))
+            .unwrap();
+        assert_eq!(in_memory, expected);
+        let spilling = ChunkedProcessor::new(&[1, 2])
+            .chunk_size(7)
+            .memory_budget(1)
+            .spill_dir(&missing);
+        assert!(spilling.count(texts.iter().cloned().map(Ok)).is_err());
+
+        for budget in [1, 2_000, 20_000] {
+            let counted = ChunkedProcessor::new(&[1, 2])
+                .chunk_size(7)
+                .memory_budget(budget)
+                .count(texts.iter().cloned().map(Ok))
+                .unwrap();
+            assert_eq!(counted, expected, "budget {budget}");
+        }
+    }
+
+    /// Tests that the tracked size grows only by the entries new to the pending counts
+    #[test]
+    fn test_added_size() {
+        let mut chunk = NGramCounter::new();
+        chunk.add("ab", 1, 2);
+        chunk.add("a b", 2, 1);
+        let mut pending = NGramCounter::new();
+        let first = added_size(&pending, &chunk);
+        assert!(first > "ab".len() + "a b".len());
+        pending.merge(&chunk);
+        assert_eq!(added_size(&pending, &chunk), 0);
+
+        // The same string at another order is a separate entry
+        let mut other = NGramCounter::new();
+        other.add("ab", 2, 1);
+        other.add("a b", 2, 3);
+        let mut new_entry = NGramCounter::new();
+        new_entry.add("ab", 2, 1);
+        assert_eq!(
+            added_size(&pending, &other),
+            added_size(&NGramCounter::new(), &new_entry)
+        );
+    }
+
+    /// Tests that the sink sequence does not depend on threads, chunks or budget
+    #[test]
+    fn test_chunked_deterministic() {
+        let texts: Vec<String> = (0..120)
+            .map(|i| format!("p{} q{} r", i % 11, i % 4))
+            .collect();
+        let run = |threads: usize, chunk_size: usize, budget: Option<usize>| {
+            let mut processor = ChunkedProcessor::new(&[2, 1]).chunk_size(chunk_size);
+            if let Some(budget) = budget {
+                processor = processor.memory_budget(budget);
+            }
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let mut table = Vec::new();
+            pool.install(|| {
+                processor.process(texts.iter().cloned().map(Ok), |ngram, n, count| {
+                    table.push((ngram.to_string(), n, count));
+                    Ok(())
+                })
+            })
+            .unwrap();
+            table
+        };
+
+        let expected = run(1, 120, None);
+        assert_eq!(run(2, 3, Some(1)), expected);
+        for (threads, chunk_size, budget) in [(4, 1, None), (3, 17, Some(500)), (8, 5, None)] {
+            assert_eq!(run(threads, chunk_size, budget), expected);
+        }
+    }
+
+    /// Tests that many spill files are merged in levels and passes within the fan-in
+    #[test]
+    fn test_chunked_fan_in() {
+        let texts: Vec<String> = (0..90)
+            .map(|i| format!("x{} y{} z", i % 13, i % 6))
+            .collect();
+        let expected = ChunkedProcessor::new(&[1, 2])
+            .count(texts.iter().cloned().map(Ok))
+            .unwrap();
+        for fan_in in [2, 3, 64] {
+            let mut processor = ChunkedProcessor::new(&[1, 2])
+                .chunk_size(1)
+                .memory_budget(1);
+            processor.fan_in = fan_in;
+            let counted = processor.count(texts.iter().cloned().map(Ok)).unwrap();
+            assert_eq!(counted, expected, "fan-in {fan_in}");
+        }
+    }
+
+    /// Tests that a cancelled token stops counting and merging
+    #[test]
+    fn test_chunked_cancelled() {
+        let token = CancelToken::new();
+        let processor = ChunkedProcessor::new(&[1])
+            .chunk_size(1)
+            .cancel_token(token.clone());
+        let docs = ["a b", "c d", "e"].map(|d| Ok(d.to_string()));
+        let mut seen = 0;
+        let result = processor.process(docs, |_, _, _| {
+            seen += 1;
+            token.cancel();
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(seen, 1);
+        assert!(processor.count([Ok("a".to_string())]).is_err());
+    }
+
+    /// Tests that input and sink errors are propagated
+    #[test]
+    fn test_chunked_errors() {
+        let processor = ChunkedProcessor::new(&[1]).chunk_size(1);
+        let docs = vec![Ok("a".to_string()), Err(io::Error::other("boom"))];
+        assert!(processor.count(docs).is_err());
+
+        let docs = vec![Ok("a b".to_string())];
+        let result = processor.process(docs, |_, _, _| Err(io::Error::other("full")));
+        assert_eq!(result.unwrap_err().to_string(), "full");
+    }
+}
@@ -0,0 +1,79 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use compact_str::CompactString;
+
+use crate::ngram_count;
+
+/// Generates n-grams as `CompactString`s, storing short n-grams inline.
+///
+/// This is the small-string counterpart of [`crate::generate_ngrams_owned`]: n-grams up
+/// to 24 bytes (12 on 32-bit targets) live inside the `CompactString` itself, so most
+/// items in a typical corpus need no heap allocation at all.
+///
+/// # Arguments
+///
+/// * `words` - A slice of String objects representing the input text as individual words
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Delimiter string to use between words in n-grams
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_ngrams_compact;
+///
+/// let words = vec!["hello".to_string(), "world".to_string()];
+/// let ngrams = generate_ngrams_compact(&words, &[2], "-");
+///
+/// assert_eq!(ngrams, vec!["hello-world"]);
+/// assert!(!ngrams[0].is_heap_allocated());
+/// ```
+pub fn generate_ngrams_compact(
+    words: &[String],
+    n_range: &[usize],
+    delimiter: &str,
+) -> Vec<CompactString> {
+    let mut result = Vec::with_capacity(ngram_count(words.len(), n_range));
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            let len = window.iter().map(String::len).sum::<usize>() + (n - 1) * delimiter.len();
+            let mut ngram = CompactString::with_capacity(len);
+            for (i, word) in window.iter().enumerate() {
+                if i > 0 {
+                    ngram.push_str(delimiter);
+                }
+                ngram.push_str(word);
+            }
+            result.push(ngram);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that compact output matches the owned output
+    #[test]
+    fn test_compact_matches_owned() {
+        let words = vec![
+            "a".to_string(),
+            "considerably-longer-token".to_string(),
+            "c".to_string(),
+        ];
+
+        let compact = generate_ngrams_compact(&words, &[1, 2, 3], " ");
+        assert_eq!(
+            compact,
+            crate::generate_ngrams_owned(&words, &[1, 2, 3], " ")
+        );
+        assert!(!compact[0].is_heap_allocated());
+        assert!(compact[5].is_heap_allocated());
+    }
+}
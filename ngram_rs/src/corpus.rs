@@ -0,0 +1,213 @@
+use std::io::{self, BufRead, Read};
+
+use crate::{NGramCounter, tokenize_whitespace};
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Iterator over the text field of each line of a JSON Lines stream.
+///
+/// Created by [`jsonl_documents`].
+#[derive(Debug)]
+pub struct JsonlDocuments<R> {
+    lines: io::Lines<R>,
+    field: String,
+    line: usize,
+}
+
+impl<R: BufRead> Iterator for JsonlDocuments<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.line += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut value: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(value) => value,
+                Err(e) => return Some(Err(invalid_data(format!("line {}: {e}", self.line)))),
+            };
+            return Some(
+                match value.get_mut(&self.field).map(serde_json::Value::take) {
+                    Some(serde_json::Value::String(text)) => Ok(text),
+                    _ => Err(invalid_data(format!(
+                        "line {}: missing string field `{}`",
+                        self.line, self.field
+                    ))),
+                },
+            );
+        }
+    }
+}
+
+/// Streams documents from JSON Lines, one object per line, reading the text in `field`.
+///
+/// Lines are parsed one at a time, so arbitrarily large dumps are read in constant
+/// memory. Blank lines are skipped; invalid JSON or a missing or non-string field
+/// yields an `InvalidData` error naming the line.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::jsonl_documents;
+///
+/// let data = r#"{"id": 1, "text": "hello world"}
+/// {"id": 2, "text": "hello again"}"#;
+/// let docs: Vec<String> = jsonl_documents(data.as_bytes(), "text")
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(docs, ["hello world", "hello again"]);
+/// ```
+pub fn jsonl_documents<R: BufRead>(reader: R, field: &str) -> JsonlDocuments<R> {
+    JsonlDocuments {
+        lines: reader.lines(),
+        field: field.to_string(),
+        line: 0,
+    }
+}
+
+/// Iterator over one column of a CSV stream.
+///
+/// Created by [`csv_documents`].
+pub struct CsvDocuments<R> {
+    records: csv::StringRecordsIntoIter<R>,
+    column: usize,
+}
+
+impl<R: Read> Iterator for CsvDocuments<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e.into())),
+        };
+        Some(
+            record.get(self.column).map(str::to_string).ok_or_else(|| {
+                invalid_data(format!("record {:?} is too short", record.position()))
+            }),
+        )
+    }
+}
+
+/// Streams documents from a CSV file with a header row, reading the text in `column`.
+///
+/// Quoted fields with embedded newlines are handled by the `csv` crate. An error is
+/// returned up front if the header has no such column.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::csv_documents;
+///
+/// let data = "id,body\n1,\"first, document\"\n2,second document\n";
+/// let docs: Vec<String> = csv_documents(data.as_bytes(), "body")
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(docs, ["first, document", "second document"]);
+/// ```
+pub fn csv_documents<R: Read>(reader: R, column: &str) -> io::Result<CsvDocuments<R>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let index = reader
+        .headers()?
+        .iter()
+        .position(|name| name == column)
+        .ok_or_else(|| invalid_data(format!("no column named `{column}`")))?;
+
+    Ok(CsvDocuments {
+        records: reader.into_records(),
+        column: index,
+    })
+}
+
+impl NGramCounter {
+    /// Tokenizes every document of a corpus on whitespace and counts its n-grams.
+    ///
+    /// Works with any iterator of fallible documents, such as [`jsonl_documents`] or
+    /// [`csv_documents`]; counting stops at the first error.
+    ///
+    /// # Returns
+    ///
+    /// The number of documents counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{NGramCounter, jsonl_documents};
+    ///
+    /// let data = "{\"text\": \"to be or not to be\"}\n{\"text\": \"to be\"}\n";
+    /// let mut counter = NGramCounter::new();
+    /// let docs = counter
+    ///     .add_corpus(jsonl_documents(data.as_bytes(), "text"), &[2], None)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(docs, 2);
+    /// assert_eq!(counter.get("to be"), 3);
+    /// ```
+    pub fn add_corpus<I>(
+        &mut self,
+        documents: I,
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = io::Result<String>>,
+    {
+        let mut count = 0;
+        for document in documents {
+            let document = document?;
+            let tokens: Vec<&str> = tokenize_whitespace(&document).collect();
+            self.add_document(&tokens, n_range, delimiter);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that malformed JSON lines and missing fields report their line number
+    #[test]
+    fn test_jsonl_documents_errors() {
+        let data = "{\"text\": \"ok\"}\n\n{\"body\": \"x\"}\nnot json\n";
+        let results: Vec<io::Result<String>> = jsonl_documents(data.as_bytes(), "text").collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), "ok");
+        let error = |i: usize| results[i].as_ref().unwrap_err().to_string();
+        assert!(error(1).contains("line 3"));
+        assert!(error(2).contains("line 4"));
+    }
+
+    /// Tests CSV column lookup and counting a CSV corpus
+    #[test]
+    fn test_csv_corpus_counting() {
+        assert!(csv_documents("a,b\n1,2\n".as_bytes(), "text").is_err());
+
+        let data = "text,label\nthe cat sat,1\n\"the cat\nran\",0\n";
+        let mut counter = NGramCounter::new();
+        let docs = counter
+            .add_corpus(
+                csv_documents(data.as_bytes(), "text").unwrap(),
+                &[2],
+                Some("_"),
+            )
+            .unwrap();
+
+        assert_eq!(docs, 2);
+        assert_eq!(counter.get("the_cat"), 2);
+        assert_eq!(counter.get("cat_ran"), 1);
+    }
+}
@@ -0,0 +1,639 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::{count_into, count_ngrams_into};
+
+/// The rank-frequency distribution of one order of an [`NGramCounter`], with its fitted
+/// Zipf exponent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankFrequency {
+    /// `(rank, frequency)` pairs, rank 1 being the most frequent n-gram
+    pub points: Vec<(usize, usize)>,
+    /// The exponent `s` of the Zipf law `frequency ∝ rank^-s`, fitted by least squares
+    /// on the log-log points; `None` with fewer than two points
+    pub zipf_exponent: Option<f64>,
+}
+
+/// How well a counted vocabulary covers the n-grams of a new text, computed by
+/// [`NGramCounter::coverage`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    /// N-gram occurrences in the text
+    pub total: usize,
+    /// Occurrences of n-grams present in the vocabulary
+    pub covered: usize,
+    /// Share of the occurrences that are covered, from 0 to 1; 0 for a text without
+    /// n-grams
+    pub coverage: f64,
+    /// Share of the occurrences that are out of vocabulary, `1 - coverage` unless the
+    /// text has no n-grams
+    pub oov_rate: f64,
+    /// The most frequent uncovered n-grams with their count in the text
+    pub uncovered: Vec<(String, usize)>,
+}
+
+/// Summary statistics of a counted corpus, computed by [`NGramCounter::stats`].
+///
+/// Its `Display` form is a Markdown table with one row per order, ready to paste into
+/// a dataset card.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusStats {
+    /// Number of tokens, the unigram occurrences; 0 if unigrams were not counted
+    pub tokens: usize,
+    /// Statistics of every counted order, smallest first
+    pub orders: Vec<OrderStats>,
+}
+
+/// Statistics of the n-grams of one order, part of [`CorpusStats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderStats {
+    /// The order
+    pub n: usize,
+    /// Occurrences of n-grams of this order
+    pub occurrences: usize,
+    /// Distinct n-grams (types) of this order
+    pub types: usize,
+    /// Types over occurrences
+    pub type_token_ratio: f64,
+    /// Percentage of the types occurring exactly once (hapax legomena)
+    pub hapax_percent: f64,
+    /// Mean length of the n-gram occurrences, in characters including delimiters
+    pub mean_length: f64,
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Tokens: {}", self.tokens)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "| n | occurrences | types | type-token ratio | hapax % | mean length |"
+        )?;
+        writeln!(f, "|---|---|---|---|---|---|")?;
+        for order in &self.orders {
+            writeln!(
+                f,
+                "| {} | {} | {} | {:.4} | {:.2} | {:.2} |",
+                order.n,
+                order.occurrences,
+                order.types,
+                order.type_token_ratio,
+                order.hapax_percent,
+                order.mean_length
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The order in which [`NGramCounter::entries`] lists the counted n-grams.
+///
+/// Counts themselves never depend on how a corpus was split across threads, but the
+/// hash tables holding them are seeded per process, so their raw iteration order
+/// changes from run to run. Every ordering except `Unspecified` is a total order over
+/// `(ngram, n, count)` entries and gives byte-identical output for equal counts,
+/// whatever the run, thread count or merge order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EntryOrder {
+    /// By increasing order, then by the bytes of the n-gram
+    #[default]
+    Lexicographic,
+    /// By decreasing count, then by increasing order, then by the bytes of the n-gram
+    Frequency,
+    /// By increasing order, then in hash table order, skipping the sort; differs
+    /// between runs
+    Unspecified,
+}
+
+/// Occurrence counts of n-grams, kept separately for each order `n`.
+///
+/// Counts accumulate across documents, so one counter can hold the n-gram table of a
+/// whole corpus. Orders are iterated from smallest to largest.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::NGramCounter;
+///
+/// let mut counter = NGramCounter::new();
+/// counter.add_document(&["to", "be", "or", "not", "to", "be"], &[1, 2], None);
+///
+/// assert_eq!(counter.get("to be"), 2);
+/// assert_eq!(counter.total(1), 6);
+/// assert_eq!(counter.most_common(1), vec![("be", 1, 2)]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NGramCounter {
+    orders: BTreeMap<usize, HashMap<String, usize>>,
+}
+
+impl NGramCounter {
+    /// Creates an empty counter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the n-grams of one document.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The tokens of the document
+    /// * `n_range` - The n-gram sizes to count
+    /// * `delimiter` - Optional delimiter placed between tokens (defaults to space)
+    pub fn add_document<S: AsRef<str>>(
+        &mut self,
+        words: &[S],
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) {
+        for &n in n_range {
+            if n == 0 || n > words.len() {
+                continue;
+            }
+            count_ngrams_into(words, &[n], delimiter, self.orders.entry(n).or_default());
+        }
+    }
+
+    /// Counts the n-grams of one document with their tokens joined last to first, as
+    /// [`crate::generate_ngrams_reversed`] forms them.
+    ///
+    /// Reversed and regular n-grams share the same tables, so a counter is usually
+    /// filled with one or the other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document_reversed(&["to", "be", "or", "not", "to", "be"], &[2], None);
+    ///
+    /// assert_eq!(counter.get("be to"), 2);
+    /// assert_eq!(counter.get("to be"), 0);
+    /// ```
+    pub fn add_document_reversed<S: AsRef<str>>(
+        &mut self,
+        words: &[S],
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) {
+        for &n in n_range {
+            if n == 0 || n > words.len() {
+                continue;
+            }
+            let counts = self.orders.entry(n).or_default();
+            count_into(words, &[n], delimiter.unwrap_or(" "), true, counts);
+        }
+    }
+
+    /// Adds `count` occurrences of an already joined n-gram of order `n`.
+    pub fn add(&mut self, ngram: &str, n: usize, count: usize) {
+        let counts = self.orders.entry(n).or_default();
+        match counts.get_mut(ngram) {
+            Some(c) => *c += count,
+            None => {
+                counts.insert(ngram.to_string(), count);
+            }
+        }
+    }
+
+    /// Adds every count of `other` to this counter.
+    pub fn merge(&mut self, other: &NGramCounter) {
+        for (ngram, n, count) in other.iter() {
+            self.add(ngram, n, count);
+        }
+    }
+
+    /// Returns the count of `ngram`, summed over all orders.
+    pub fn get(&self, ngram: &str) -> usize {
+        self.orders.values().filter_map(|c| c.get(ngram)).sum()
+    }
+
+    /// Returns the count of `ngram` as an n-gram of order `n`.
+    pub fn get_order(&self, ngram: &str, n: usize) -> usize {
+        self.orders
+            .get(&n)
+            .and_then(|c| c.get(ngram))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of distinct n-grams across all orders.
+    pub fn len(&self) -> usize {
+        self.orders.values().map(HashMap::len).sum()
+    }
+
+    /// Returns `true` if nothing has been counted.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the orders present in the counter, smallest first.
+    pub fn orders(&self) -> impl Iterator<Item = usize> + '_ {
+        self.orders.keys().copied()
+    }
+
+    /// Returns the counts of order `n`, if any n-gram of that order was counted.
+    pub fn order(&self, n: usize) -> Option<&HashMap<String, usize>> {
+        self.orders.get(&n)
+    }
+
+    /// Returns the total number of occurrences counted for order `n`.
+    pub fn total(&self, n: usize) -> usize {
+        self.orders.get(&n).map_or(0, |c| c.values().sum())
+    }
+
+    /// Iterates over `(ngram, n, count)` entries, by increasing order.
+    ///
+    /// Entries within one order come in unspecified order, which changes between runs;
+    /// use [`NGramCounter::entries`] for a reproducible one.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize, usize)> + '_ {
+        self.orders.iter().flat_map(|(&n, counts)| {
+            counts
+                .iter()
+                .map(move |(ngram, &count)| (ngram.as_str(), n, count))
+        })
+    }
+
+    /// Profiles the counted corpus: token count, and for each order the number of
+    /// types, the type-token ratio, the share of hapax legomena and the mean n-gram
+    /// length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["to", "be", "or", "not", "to", "be"], &[1, 2], None);
+    ///
+    /// let stats = counter.stats();
+    /// assert_eq!(stats.tokens, 6);
+    /// let unigrams = &stats.orders[0];
+    /// assert_eq!((unigrams.types, unigrams.type_token_ratio), (4, 4.0 / 6.0));
+    /// assert_eq!(unigrams.hapax_percent, 50.0);
+    /// assert!((stats.orders[1].mean_length - 5.4).abs() < 1e-12);
+    /// assert!(stats.to_string().contains("| 2 | 5 | 4 |"));
+    /// ```
+    pub fn stats(&self) -> CorpusStats {
+        let orders = self
+            .orders
+            .iter()
+            .map(|(&n, counts)| {
+                let occurrences: usize = counts.values().sum();
+                let types = counts.len();
+                let hapaxes = counts.values().filter(|&&count| count == 1).count();
+                let length: usize = counts
+                    .iter()
+                    .map(|(ngram, &count)| ngram.chars().count() * count)
+                    .sum();
+                let ratio = |part: usize, whole: usize| {
+                    if whole == 0 {
+                        0.0
+                    } else {
+                        part as f64 / whole as f64
+                    }
+                };
+                OrderStats {
+                    n,
+                    occurrences,
+                    types,
+                    type_token_ratio: ratio(types, occurrences),
+                    hapax_percent: 100.0 * ratio(hapaxes, types),
+                    mean_length: ratio(length, occurrences),
+                }
+            })
+            .collect();
+        CorpusStats {
+            tokens: self.total(1),
+            orders,
+        }
+    }
+
+    /// Measures how well the n-grams of order `n` counted so far cover a new text.
+    ///
+    /// Every occurrence of an n-gram of the text counts towards coverage if the
+    /// counter holds that n-gram, and towards the OOV rate otherwise. A low coverage
+    /// suggests that a model trained on the counted corpus won't fit the new domain;
+    /// the most frequent uncovered n-grams show what is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - The tokens of the new text
+    /// * `n` - The order of the n-grams compared
+    /// * `delimiter` - The delimiter the counter was filled with (defaults to space)
+    /// * `top_k` - How many uncovered n-grams to report, most frequent first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["the", "cat", "sat", "on", "the", "mat"], &[1], None);
+    ///
+    /// let text = ["the", "dog", "sat", "on", "the", "dog"];
+    /// let coverage = counter.coverage(&text, 1, None, 5);
+    /// assert_eq!((coverage.covered, coverage.total), (4, 6));
+    /// assert!((coverage.oov_rate - 1.0 / 3.0).abs() < 1e-12);
+    /// assert_eq!(coverage.uncovered, [("dog".to_string(), 2)]);
+    /// ```
+    pub fn coverage<S: AsRef<str>>(
+        &self,
+        words: &[S],
+        n: usize,
+        delimiter: Option<&str>,
+        top_k: usize,
+    ) -> Coverage {
+        let mut counts = HashMap::new();
+        count_ngrams_into(words, &[n], delimiter, &mut counts);
+
+        let total: usize = counts.values().sum();
+        let mut uncovered: Vec<(String, usize)> = counts
+            .into_iter()
+            .filter(|(ngram, _)| self.get_order(ngram, n) == 0)
+            .collect();
+        let missing: usize = uncovered.iter().map(|(_, count)| count).sum();
+        uncovered.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        uncovered.truncate(top_k);
+
+        let (coverage, oov_rate) = if total == 0 {
+            (0.0, 0.0)
+        } else {
+            let oov_rate = missing as f64 / total as f64;
+            (1.0 - oov_rate, oov_rate)
+        };
+        Coverage {
+            total,
+            covered: total - missing,
+            coverage,
+            oov_rate,
+            uncovered,
+        }
+    }
+
+    /// Returns the rank-frequency distribution of order `n` and its Zipf exponent.
+    ///
+    /// Frequencies are sorted in decreasing order and ranked from 1, ties getting
+    /// consecutive ranks, ready for a log-log rank-frequency plot. Natural language
+    /// usually fits an exponent close to 1 for unigrams.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// for (word, count) in [("the", 60), ("of", 30), ("and", 20), ("to", 15)] {
+    ///     counter.add(word, 1, count);
+    /// }
+    ///
+    /// let dist = counter.rank_frequency(1);
+    /// assert_eq!(dist.points, [(1, 60), (2, 30), (3, 20), (4, 15)]);
+    /// assert!((dist.zipf_exponent.unwrap() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn rank_frequency(&self, n: usize) -> RankFrequency {
+        let mut frequencies: Vec<usize> = self
+            .order(n)
+            .into_iter()
+            .flat_map(HashMap::values)
+            .copied()
+            .collect();
+        frequencies.sort_unstable_by(|a, b| b.cmp(a));
+        let points: Vec<(usize, usize)> = frequencies
+            .into_iter()
+            .enumerate()
+            .map(|(i, frequency)| (i + 1, frequency))
+            .collect();
+
+        let zipf_exponent = (points.len() >= 2).then(|| {
+            let logs: Vec<(f64, f64)> = points
+                .iter()
+                .map(|&(rank, frequency)| ((rank as f64).ln(), (frequency as f64).ln()))
+                .collect();
+            let len = logs.len() as f64;
+            let mean_x = logs.iter().map(|&(x, _)| x).sum::<f64>() / len;
+            let mean_y = logs.iter().map(|&(_, y)| y).sum::<f64>() / len;
+            let covariance: f64 = logs.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+            let variance: f64 = logs.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+            -covariance / variance
+        });
+        RankFrequency {
+            points,
+            zipf_exponent,
+        }
+    }
+
+    /// Serializes the `top_k` most frequent n-grams as a JSON array of
+    /// `{"text": ..., "value": ...}` objects.
+    ///
+    /// This is the shape d3-cloud and most word-cloud and chart libraries take as
+    /// input. Entries follow [`NGramCounter::most_common`], so the output is
+    /// deterministic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["to", "be", "or", "not", "to", "be"], &[1], None);
+    ///
+    /// assert_eq!(
+    ///     counter.to_json(2),
+    ///     r#"[{"text":"be","value":2},{"text":"to","value":2}]"#
+    /// );
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, top_k: usize) -> String {
+        let entries: Vec<serde_json::Value> = self
+            .most_common(top_k)
+            .into_iter()
+            .map(|(ngram, _, count)| serde_json::json!({ "text": ngram, "value": count }))
+            .collect();
+        serde_json::Value::Array(entries).to_string()
+    }
+
+    /// Returns the `(ngram, count)` entries of order `n`, sorted by n-gram.
+    pub(crate) fn sorted_order(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut entries: Vec<(&str, usize)> = self
+            .order(n)
+            .into_iter()
+            .flatten()
+            .map(|(ngram, &count)| (ngram.as_str(), count))
+            .collect();
+        entries.sort_unstable_by_key(|&(ngram, _)| ngram);
+        entries
+    }
+
+    /// Returns every `(ngram, n, count)` entry, listed in the given [`EntryOrder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{NGramCounter, EntryOrder};
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["b", "a", "b"], &[1, 2], None);
+    ///
+    /// assert_eq!(
+    ///     counter.entries(EntryOrder::Lexicographic),
+    ///     [("a", 1, 1), ("b", 1, 2), ("a b", 2, 1), ("b a", 2, 1)]
+    /// );
+    /// assert_eq!(counter.entries(EntryOrder::Frequency)[..2], [("b", 1, 2), ("a", 1, 1)]);
+    /// ```
+    pub fn entries(&self, ordering: EntryOrder) -> Vec<(&str, usize, usize)> {
+        let mut entries: Vec<_> = self.iter().collect();
+        match ordering {
+            EntryOrder::Lexicographic => {
+                entries.sort_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)));
+            }
+            EntryOrder::Frequency => entries
+                .sort_unstable_by(|a, b| b.2.cmp(&a.2).then(a.1.cmp(&b.1)).then(a.0.cmp(b.0))),
+            EntryOrder::Unspecified => {}
+        }
+        entries
+    }
+
+    /// Returns the `k` most frequent `(ngram, n, count)` entries.
+    ///
+    /// Ties are broken by order, then lexicographically, so the result is deterministic.
+    pub fn most_common(&self, k: usize) -> Vec<(&str, usize, usize)> {
+        let mut entries = self.entries(EntryOrder::Frequency);
+        entries.truncate(k);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that entries come out identically however the counts were accumulated
+    #[test]
+    fn test_entries_ordering() {
+        let documents = [["x", "y", "x"], ["y", "z", "y"], ["z", "x", "w"]];
+        let mut forward = NGramCounter::new();
+        for document in &documents {
+            forward.add_document(document, &[1, 2], None);
+        }
+        let mut merged = NGramCounter::new();
+        for document in documents.iter().rev() {
+            let mut part = NGramCounter::new();
+            part.add_document(document, &[2, 1], None);
+            merged.merge(&part);
+        }
+
+        for ordering in [EntryOrder::Lexicographic, EntryOrder::Frequency] {
+            assert_eq!(forward.entries(ordering), merged.entries(ordering));
+        }
+        let lexicographic = forward.entries(EntryOrder::Lexicographic);
+        assert!(
+            lexicographic
+                .windows(2)
+                .all(|w| (w[0].1, w[0].0) < (w[1].1, w[1].0))
+        );
+        assert_eq!(forward.entries(EntryOrder::Frequency)[0], ("x", 1, 3));
+
+        let mut unspecified = merged.entries(EntryOrder::Unspecified);
+        assert!(unspecified.windows(2).all(|w| w[0].1 <= w[1].1));
+        unspecified.sort_unstable();
+        let mut sorted = lexicographic.clone();
+        sorted.sort_unstable();
+        assert_eq!(unspecified, sorted);
+    }
+
+    /// Tests counting, per-order lookups and merging
+    #[test]
+    fn test_counter_add_and_merge() {
+        let mut a = NGramCounter::new();
+        a.add_document(&["a", "b", "a", "b"], &[1, 2, 5], Some("_"));
+        assert_eq!(a.get_order("a_b", 2), 2);
+        assert_eq!(a.get_order("a_b", 1), 0);
+        assert_eq!(a.orders().collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(a.len(), 4);
+
+        let mut b = NGramCounter::new();
+        b.add("a_b", 2, 3);
+        b.add("c", 1, 1);
+        a.merge(&b);
+        assert_eq!(a.get("a_b"), 5);
+        assert_eq!(a.total(1), 5);
+        assert_eq!(a.most_common(2), vec![("a_b", 2, 5), ("a", 1, 2)]);
+    }
+
+    /// Tests corpus statistics of a multi-byte corpus and of an empty counter
+    #[test]
+    fn test_stats() {
+        let mut counter = NGramCounter::new();
+        counter.add_document(&["été", "été", "nuit"], &[1, 3], Some("_"));
+
+        let stats = counter.stats();
+        assert_eq!(stats.tokens, 3);
+        let unigrams = &stats.orders[0];
+        assert_eq!(
+            (unigrams.n, unigrams.occurrences, unigrams.types),
+            (1, 3, 2)
+        );
+        assert_eq!(unigrams.hapax_percent, 50.0);
+        assert!((unigrams.mean_length - 10.0 / 3.0).abs() < 1e-12);
+        assert_eq!(stats.orders[1].mean_length, 12.0);
+
+        let empty = NGramCounter::new().stats();
+        assert_eq!((empty.tokens, empty.orders.len()), (0, 0));
+        assert_eq!(empty.to_string().lines().count(), 4);
+    }
+
+    /// Tests that the JSON export escapes n-grams and honors `top_k`
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_to_json() {
+        let mut counter = NGramCounter::new();
+        counter.add("say \"hi\"", 2, 3);
+        counter.add("x", 1, 1);
+
+        let json: serde_json::Value = serde_json::from_str(&counter.to_json(5)).unwrap();
+        assert_eq!(json[0]["text"], "say \"hi\"");
+        assert_eq!(json[1]["value"], 1);
+        assert_eq!(counter.to_json(0), "[]");
+    }
+
+    /// Tests coverage per order, the ranking of uncovered n-grams and empty texts
+    #[test]
+    fn test_coverage() {
+        let mut counter = NGramCounter::new();
+        counter.add_document(&["a", "b", "c"], &[2], Some("_"));
+
+        let coverage = counter.coverage(&["a", "b", "d", "a", "b", "e"], 2, Some("_"), 2);
+        assert_eq!((coverage.covered, coverage.total), (2, 5));
+        assert_eq!(coverage.coverage, 0.4);
+        let uncovered: Vec<(&str, usize)> = coverage
+            .uncovered
+            .iter()
+            .map(|(ngram, count)| (ngram.as_str(), *count))
+            .collect();
+        assert_eq!(uncovered, [("b_d", 1), ("b_e", 1)]);
+
+        // Unigrams were never counted, so nothing of order 1 is covered
+        assert_eq!(counter.coverage(&["a"], 1, None, 0).oov_rate, 1.0);
+        let empty = counter.coverage(&["a"], 2, None, 3);
+        assert_eq!((empty.total, empty.coverage, empty.oov_rate), (0, 0.0, 0.0));
+    }
+
+    /// Tests ranks over ties and the degenerate distributions
+    #[test]
+    fn test_rank_frequency() {
+        let mut counter = NGramCounter::new();
+        counter.add_document(&["a", "b", "a", "c", "c"], &[1], None);
+
+        let dist = counter.rank_frequency(1);
+        assert_eq!(dist.points, [(1, 2), (2, 2), (3, 1)]);
+        assert!(dist.zipf_exponent.unwrap() > 0.0);
+
+        counter.add("x y", 2, 4);
+        assert_eq!(counter.rank_frequency(2).points, [(1, 4)]);
+        assert_eq!(counter.rank_frequency(2).zipf_exponent, None);
+        assert!(counter.rank_frequency(3).points.is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{generate_char_ngrams, tokenize_whitespace};
+
+/// Character n-gram counts of one language.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Profile {
+    counts: HashMap<String, usize>,
+    total: usize,
+}
+
+/// Identifies the language of a text from its character n-grams.
+///
+/// Each language is described by a profile trained on sample text. A text is scored
+/// with a multinomial naive Bayes model over its character n-grams, with add-one
+/// smoothing and equal priors. Text is lowercased and its whitespace collapsed
+/// before n-grams are taken, with a space on each side so that word boundaries are
+/// part of the features.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::LanguageDetector;
+///
+/// let mut detector = LanguageDetector::new(&[1, 2, 3]);
+/// detector.add_sample("en", "the quick brown fox jumps over the lazy dog");
+/// detector.add_sample("fr", "le renard brun rapide saute par-dessus le chien paresseux");
+///
+/// let (language, confidence) = detector.detect("the dog is lazy").unwrap();
+/// assert_eq!(language, "en");
+/// assert!(confidence > 0.5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LanguageDetector {
+    n_range: Vec<usize>,
+    profiles: BTreeMap<String, Profile>,
+    /// Every n-gram seen in any profile, used as the smoothing vocabulary
+    vocab: HashSet<String>,
+}
+
+impl LanguageDetector {
+    /// Creates a detector without languages, using character n-grams of the sizes in
+    /// `n_range`.
+    pub fn new(n_range: &[usize]) -> Self {
+        LanguageDetector {
+            n_range: n_range.to_vec(),
+            profiles: BTreeMap::new(),
+            vocab: HashSet::new(),
+        }
+    }
+
+    /// Adds sample text to the profile of `language`, creating it if needed.
+    ///
+    /// Samples accumulate, so a profile can be trained from many documents.
+    pub fn add_sample(&mut self, language: &str, text: &str) {
+        let text = normalize(text);
+        let profile = self.profiles.entry(language.to_string()).or_default();
+        for ngram in generate_char_ngrams(&text, &self.n_range) {
+            match profile.counts.get_mut(ngram) {
+                Some(count) => *count += 1,
+                None => {
+                    profile.counts.insert(ngram.to_string(), 1);
+                    self.vocab.insert(ngram.to_string());
+                }
+            }
+            profile.total += 1;
+        }
+    }
+
+    /// Returns the languages with a profile, in lexicographic order.
+    pub fn languages(&self) -> impl Iterator<Item = &str> + '_ {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Returns the posterior probability of every language for `text`, most likely
+    /// first.
+    ///
+    /// Probabilities sum to `1.0`. Ties, such as for a text without any n-gram, are
+    /// broken by language name.
+    pub fn scores(&self, text: &str) -> Vec<(&str, f64)> {
+        let text = normalize(text);
+        let ngrams = generate_char_ngrams(&text, &self.n_range);
+        let vocab = (self.vocab.len() + 1) as f64;
+
+        let log_likelihoods: Vec<(&str, f64)> = self
+            .profiles
+            .iter()
+            .map(|(language, profile)| {
+                let denominator = (profile.total as f64 + vocab).ln();
+                let score = ngrams
+                    .iter()
+                    .map(|ngram| {
+                        let count = profile.counts.get(*ngram).copied().unwrap_or(0);
+                        ((count + 1) as f64).ln() - denominator
+                    })
+                    .sum();
+                (language.as_str(), score)
+            })
+            .collect();
+
+        let max = log_likelihoods
+            .iter()
+            .map(|&(_, score)| score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let norm: f64 = log_likelihoods.iter().map(|&(_, s)| (s - max).exp()).sum();
+        let mut scores: Vec<(&str, f64)> = log_likelihoods
+            .into_iter()
+            .map(|(language, s)| (language, (s - max).exp() / norm))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        scores
+    }
+
+    /// Returns the most likely language of `text` with its posterior probability, or
+    /// `None` if no language has been added.
+    pub fn detect(&self, text: &str) -> Option<(&str, f64)> {
+        self.scores(text).into_iter().next()
+    }
+}
+
+/// Lowercases `text`, collapses whitespace to single spaces and pads it with a space
+/// on each side.
+fn normalize(text: &str) -> String {
+    let lower = text.to_lowercase();
+    let mut normalized = String::with_capacity(lower.len() + 2);
+    normalized.push(' ');
+    for token in tokenize_whitespace(&lower) {
+        normalized.push_str(token);
+        normalized.push(' ');
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that posteriors are normalized and ranked, and that ties fall back to names
+    #[test]
+    fn test_language_detector_scores() {
+        let mut detector = LanguageDetector::new(&[1, 2, 3]);
+        assert_eq!(detector.detect("anything"), None);
+
+        detector.add_sample(
+            "de",
+            "der schnelle braune fuchs springt über den faulen hund",
+        );
+        detector.add_sample("en", "the quick brown fox jumps over the lazy dog");
+        detector.add_sample("en", "a dog and a fox");
+        assert_eq!(detector.languages().collect::<Vec<_>>(), ["de", "en"]);
+
+        let scores = detector.scores("Der  HUND springt");
+        assert_eq!(scores[0].0, "de");
+        assert!((scores.iter().map(|s| s.1).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let mut tied = LanguageDetector::new(&[4]);
+        tied.add_sample("b", "xyz");
+        tied.add_sample("a", "uvw");
+        assert_eq!(tied.detect(""), Some(("a", 0.5)));
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// N-gram methods on slices of tokens, joined with a single space.
+///
+/// A thin layer over [`crate::generate_ngrams`] and [`crate::count_ngrams_into`] that
+/// reads naturally in iterator chains. It is implemented for slices of anything that
+/// can be viewed as a `str`, so `Vec<String>`, `Vec<&str>` and arrays all get the
+/// methods.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::NGramExt;
+///
+/// let words = ["to", "be", "or", "not", "to", "be"];
+/// assert_eq!(words.ngrams(2)[..2], ["to be", "be or"]);
+/// assert_eq!(words.ngrams_range(1..=3).len(), 6 + 5 + 4);
+///
+/// let bigrams = words.ngrams(2);
+/// let from_to = bigrams.iter().filter(|ngram| ngram.starts_with("to ")).count();
+/// assert_eq!(from_to, 2);
+/// ```
+pub trait NGramExt {
+    /// Returns the n-grams of size `n`, borrowing the tokens when `n` is 1.
+    fn ngrams(&self, n: usize) -> Vec<Cow<'_, str>>;
+
+    /// Returns the n-grams of every size in `range`, all n-grams of the smallest size
+    /// first.
+    fn ngrams_range(&self, range: RangeInclusive<usize>) -> Vec<Cow<'_, str>>;
+
+    /// Counts the n-grams of size `n`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramExt;
+    ///
+    /// let counts = ["to", "be", "or", "not", "to", "be"].ngram_counts(2);
+    /// assert_eq!(counts["to be"], 2);
+    /// assert_eq!(counts.len(), 4);
+    /// ```
+    #[cfg(feature = "std")]
+    fn ngram_counts(&self, n: usize) -> HashMap<alloc::string::String, usize>;
+}
+
+impl<S: AsRef<str>> NGramExt for [S] {
+    fn ngrams(&self, n: usize) -> Vec<Cow<'_, str>> {
+        crate::generate_ngrams(self, &[n], None)
+    }
+
+    fn ngrams_range(&self, range: RangeInclusive<usize>) -> Vec<Cow<'_, str>> {
+        let n_range: Vec<usize> = range.collect();
+        crate::generate_ngrams(self, &n_range, None)
+    }
+
+    #[cfg(feature = "std")]
+    fn ngram_counts(&self, n: usize) -> HashMap<alloc::string::String, usize> {
+        let mut counts = HashMap::new();
+        crate::count_ngrams_into(self, &[n], None, &mut counts);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    /// Tests the methods on owned and borrowed tokens, including empty ranges
+    #[test]
+    fn test_ngram_ext() {
+        let words: Vec<String> = ["a", "b", "a", "b"].map(String::from).to_vec();
+        assert_eq!(words.ngrams(3), ["a b a", "b a b"]);
+        assert_eq!(words.ngrams(5), Vec::<Cow<str>>::new());
+        assert_eq!(
+            words[..2].ngrams_range(1..=2),
+            ["a", "b", "a b"].map(Cow::Borrowed)
+        );
+        assert!(words.ngrams_range(5..=6).is_empty());
+    }
+
+    /// Tests counting overlapping n-grams
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ngram_counts_ext() {
+        use alloc::string::ToString;
+
+        let counts = ["x", "x", "x"].ngram_counts(2);
+        assert_eq!(counts, HashMap::from([("x x".to_string(), 2)]));
+    }
+}
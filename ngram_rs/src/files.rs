@@ -0,0 +1,248 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::Mutex;
+
+use rayon::prelude::*;
+
+use crate::{CancelToken, NGramCounter, tokenize_whitespace};
+
+/// Bytes read from one file between two progress reports.
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+
+/// Progress callback of [`count_files`], given the files finished and bytes read so far.
+type Progress = Box<dyn Fn(usize, u64) + Send + Sync>;
+
+/// What [`count_files`] counts and how it reports progress.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::CountOptions;
+///
+/// let options = CountOptions::new(&[1, 2])
+///     .delimiter("_")
+///     .progress(|files, bytes| eprintln!("{files} files, {bytes} bytes"));
+/// ```
+pub struct CountOptions {
+    n_range: Vec<usize>,
+    delimiter: String,
+    progress: Option<Progress>,
+    cancel: Option<CancelToken>,
+}
+
+impl fmt::Debug for CountOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountOptions")
+            .field("n_range", &self.n_range)
+            .field("delimiter", &self.delimiter)
+            .field("progress", &self.progress.is_some())
+            .field("cancel", &self.cancel)
+            .finish()
+    }
+}
+
+impl CountOptions {
+    /// Creates options counting the n-gram sizes in `n_range`, joined with a single
+    /// space, without progress reports.
+    pub fn new(n_range: &[usize]) -> Self {
+        CountOptions {
+            n_range: n_range.to_vec(),
+            delimiter: " ".to_string(),
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    /// Sets the delimiter placed between tokens.
+    pub fn delimiter(mut self, delimiter: &str) -> Self {
+        self.delimiter = delimiter.to_string();
+        self
+    }
+
+    /// Sets a callback receiving the number of files finished and of bytes read so
+    /// far.
+    ///
+    /// It is called after every file and every mebibyte read within a file. Calls come
+    /// from the worker threads but never overlap, and the totals they receive never
+    /// decrease, so the callback should return quickly.
+    pub fn progress(mut self, progress: impl Fn(usize, u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Sets a token checked before every line, aborting the count once cancelled.
+    pub fn cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+/// Counts the n-grams of text files in parallel on the rayon thread pool.
+///
+/// Every line of a file is one whitespace-tokenized document. Each file is counted
+/// into its own counter on one thread and the counters are merged as threads finish.
+/// The merged counts are the same for every run and thread count; list them with
+/// [`NGramCounter::entries`] for output in a reproducible order.
+///
+/// # Arguments
+///
+/// * `paths` - The UTF-8 text files to count
+/// * `options` - The n-gram sizes, delimiter and progress callback
+///
+/// # Errors
+///
+/// Fails with the first I/O or UTF-8 error met, prefixed by the path of its file, or
+/// with an [`io::ErrorKind::Interrupted`] error once the cancel token is cancelled.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use ngram_rs::{CountOptions, count_files};
+///
+/// let dir = std::env::temp_dir();
+/// let paths = [dir.join("ngram_rs_doc_a.txt"), dir.join("ngram_rs_doc_b.txt")];
+/// std::fs::write(&paths[0], "to be or\nnot to be\n").unwrap();
+/// std::fs::write(&paths[1], "to be\n").unwrap();
+///
+/// let files = Arc::new(AtomicUsize::new(0));
+/// let seen = Arc::clone(&files);
+/// let options = CountOptions::new(&[2]).progress(move |done, _| seen.store(done, Ordering::Relaxed));
+/// let counter = count_files(&paths, &options).unwrap();
+///
+/// assert_eq!(counter.get("to be"), 3);
+/// assert_eq!(files.load(Ordering::Relaxed), 2);
+/// # for path in paths { std::fs::remove_file(path).unwrap(); }
+/// ```
+pub fn count_files<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    options: &CountOptions,
+) -> io::Result<NGramCounter> {
+    let totals = Mutex::new((0, 0));
+    let report = |files: usize, bytes: u64| {
+        let mut totals = totals
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        totals.0 += files;
+        totals.1 += bytes;
+        if let Some(progress) = &options.progress {
+            progress(totals.0, totals.1);
+        }
+    };
+
+    paths
+        .par_iter()
+        .map(|path| {
+            let path = path.as_ref();
+            count_file(path, options, &report)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+        })
+        .try_reduce(NGramCounter::new, |mut a, b| {
+            a.merge(&b);
+            Ok(a)
+        })
+}
+
+/// Counts the lines of one file, reporting the bytes read along the way.
+fn count_file(
+    path: &Path,
+    options: &CountOptions,
+    report: &impl Fn(usize, u64),
+) -> io::Result<NGramCounter> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut counter = NGramCounter::new();
+    let mut line = String::new();
+    let mut unreported = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)? as u64;
+        if read == 0 {
+            break;
+        }
+        if let Some(cancel) = &options.cancel {
+            cancel.check()?;
+        }
+        let tokens: Vec<&str> = tokenize_whitespace(&line).collect();
+        counter.add_document(&tokens, &options.n_range, Some(&options.delimiter));
+        unreported += read;
+        if unreported >= PROGRESS_INTERVAL {
+            report(0, unreported);
+            unreported = 0;
+        }
+    }
+    report(1, unreported);
+    Ok(counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntryOrder;
+    use std::sync::Arc;
+
+    /// Tests that parallel counting matches sequential counting, with monotonic
+    /// progress, and that errors name their file
+    #[test]
+    fn test_count_files() {
+        let dir = std::env::temp_dir().join("ngram_rs_test_count_files");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut paths = Vec::new();
+        let mut expected = NGramCounter::new();
+        let mut bytes = 0;
+        for i in 0..12 {
+            let text: String = (0..50)
+                .map(|j| format!("w{} w{} x\n", j % (i + 2), j % 3))
+                .collect();
+            for line in text.lines() {
+                let tokens: Vec<&str> = tokenize_whitespace(line).collect();
+                expected.add_document(&tokens, &[1, 2], Some("+"));
+            }
+            bytes += text.len() as u64;
+            let path = dir.join(format!("{i}.txt"));
+            std::fs::write(&path, text).unwrap();
+            paths.push(path);
+        }
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&reports);
+        let options = CountOptions::new(&[1, 2])
+            .delimiter("+")
+            .progress(move |files, bytes| log.lock().unwrap().push((files, bytes)));
+        assert_eq!(count_files(&paths, &options).unwrap(), expected);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 12);
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(reports.last(), Some(&(12, bytes)));
+
+        paths.push(dir.join("missing.txt"));
+        let error = count_files(&paths, &CountOptions::new(&[1])).unwrap_err();
+        assert!(error.to_string().contains("missing.txt"));
+
+        let options = CountOptions::new(&[2, 1]).delimiter("+");
+        for threads in [1, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let counted = pool
+                .install(|| count_files(&paths[..12], &options))
+                .unwrap();
+            assert_eq!(
+                counted.entries(EntryOrder::Lexicographic),
+                expected.entries(EntryOrder::Lexicographic)
+            );
+        }
+
+        let token = CancelToken::new();
+        token.cancel();
+        let options = CountOptions::new(&[1]).cancel_token(token);
+        let error = count_files(&paths[..3], &options).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
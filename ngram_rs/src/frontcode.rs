@@ -0,0 +1,188 @@
+use std::io::{self, Read, Write};
+
+use crate::NGramCounter;
+
+const MAGIC: &[u8; 4] = b"NGFC";
+const VERSION: u32 = 1;
+
+impl NGramCounter {
+    /// Writes the counts in a compact binary format read by
+    /// [`NGramCounter::read_front_coded`].
+    ///
+    /// The n-grams of each order are sorted and front-coded: each one stores only the
+    /// length of the prefix it shares with the previous one and the bytes after it.
+    /// Lengths and counts are LEB128 varints, so most take a single byte. Sorted
+    /// n-grams share long prefixes, so the whole table, counts included, usually takes
+    /// less space than the text of its n-grams alone. The same counter always produces
+    /// the same bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["the", "theory", "of", "the", "thermostat"], &[1, 2], None);
+    /// let mut bytes = Vec::new();
+    /// counter.write_front_coded(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(NGramCounter::read_front_coded(&mut bytes.as_slice()).unwrap(), counter);
+    /// ```
+    pub fn write_front_coded<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        write_varint(writer, self.orders().count() as u64)?;
+        for n in self.orders() {
+            let entries = self.sorted_order(n);
+            write_varint(writer, n as u64)?;
+            write_varint(writer, entries.len() as u64)?;
+            let mut previous: &[u8] = &[];
+            for (ngram, count) in entries {
+                let ngram = ngram.as_bytes();
+                let shared = previous
+                    .iter()
+                    .zip(ngram)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                write_varint(writer, shared as u64)?;
+                write_varint(writer, (ngram.len() - shared) as u64)?;
+                writer.write_all(&ngram[shared..])?;
+                write_varint(writer, count as u64)?;
+                previous = ngram;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads counts written by [`NGramCounter::write_front_coded`].
+    pub fn read_front_coded<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        if &magic != MAGIC || u32::from_le_bytes(version) != VERSION {
+            return Err(invalid_data("not a front-coded ngram_rs count table"));
+        }
+
+        let mut counter = NGramCounter::new();
+        for _ in 0..read_varint(reader)? {
+            let n = read_varint(reader)? as usize;
+            let mut ngram: Vec<u8> = Vec::new();
+            for _ in 0..read_varint(reader)? {
+                let shared = read_varint(reader)? as usize;
+                if shared > ngram.len() {
+                    return Err(invalid_data(
+                        "shared prefix longer than the previous n-gram",
+                    ));
+                }
+                ngram.truncate(shared);
+                let suffix = read_varint(reader)?;
+                let read = reader.take(suffix).read_to_end(&mut ngram)?;
+                if read as u64 != suffix {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                let text = std::str::from_utf8(&ngram)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                counter.add(text, n, read_varint(reader)? as usize);
+            }
+        }
+        Ok(counter)
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Writes `value` as an unsigned LEB128 varint: 7 bits per byte, low bits first, with
+/// the high bit set on every byte but the last.
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    let mut bytes = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&bytes[..len])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0; 1];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint longer than 64 bits"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests varints at byte boundaries
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, 16_383, 16_384, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), value);
+        }
+        let mut bytes = Vec::new();
+        write_varint(&mut bytes, 300).unwrap();
+        assert_eq!(bytes, [0xac, 0x02]);
+        assert!(read_varint(&mut [0xff; 11].as_slice()).is_err());
+    }
+
+    /// Tests a round trip of multi-byte n-grams, the size gain and corrupt input
+    #[test]
+    fn test_front_coded_round_trip() {
+        let mut counter = NGramCounter::new();
+        let words: Vec<String> = (0..500).map(|i| format!("préfixe_{}", i % 97)).collect();
+        counter.add_document(&words, &[1, 2, 3], Some("·"));
+        counter.add("big", 1, 1 << 40);
+
+        let mut bytes = Vec::new();
+        counter.write_front_coded(&mut bytes).unwrap();
+        assert_eq!(
+            NGramCounter::read_front_coded(&mut bytes.as_slice()).unwrap(),
+            counter
+        );
+
+        let text: usize = counter.iter().map(|(ngram, _, _)| ngram.len()).sum();
+        assert!(bytes.len() < text, "{} vs {text}", bytes.len());
+
+        let mut again = Vec::new();
+        counter.write_front_coded(&mut again).unwrap();
+        assert_eq!(again, bytes);
+
+        assert!(NGramCounter::read_front_coded(&mut &bytes[..bytes.len() - 1]).is_err());
+        assert!(NGramCounter::read_front_coded(&mut &b"NGLM\x01\x00\x00\x00"[..]).is_err());
+        // One order of one entry claiming a shared prefix with nothing before it
+        let bad = [
+            &MAGIC[..],
+            &VERSION.to_le_bytes(),
+            &[1, 1, 1, 3, 1, b'a', 1],
+        ]
+        .concat();
+        assert!(NGramCounter::read_front_coded(&mut bad.as_slice()).is_err());
+
+        let empty = NGramCounter::new();
+        let mut bytes = Vec::new();
+        empty.write_front_coded(&mut bytes).unwrap();
+        assert_eq!(
+            NGramCounter::read_front_coded(&mut bytes.as_slice()).unwrap(),
+            empty
+        );
+    }
+}
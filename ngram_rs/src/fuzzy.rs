@@ -0,0 +1,272 @@
+use crate::NGramIndex;
+
+/// The edit distance a [`FuzzyMatcher`] ranks its candidates by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EditDistance {
+    /// Insertions, deletions and substitutions, see [`levenshtein`]
+    #[default]
+    Levenshtein,
+    /// Levenshtein plus transpositions of adjacent characters, see
+    /// [`damerau_levenshtein`]
+    DamerauLevenshtein,
+}
+
+impl EditDistance {
+    /// Computes this distance between `a` and `b`.
+    pub fn between(self, a: &str, b: &str) -> usize {
+        match self {
+            EditDistance::Levenshtein => levenshtein(a, b),
+            EditDistance::DamerauLevenshtein => damerau_levenshtein(a, b),
+        }
+    }
+}
+
+/// Computes the Levenshtein distance between two strings: the fewest character
+/// insertions, deletions and substitutions turning one into the other.
+///
+/// Characters are Unicode scalar values, so `é` counts as one edit.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::levenshtein;
+///
+/// assert_eq!(levenshtein("kitten", "sitting"), 3);
+/// assert_eq!(levenshtein("café", "cafe"), 1);
+/// ```
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Computes the Damerau-Levenshtein distance between two strings, counting a swap of
+/// two adjacent characters as a single edit.
+///
+/// This is the optimal string alignment variant: a transposed pair is not edited
+/// again, which matches how typos occur and keeps the computation to three rows.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{damerau_levenshtein, levenshtein};
+///
+/// assert_eq!(damerau_levenshtein("recieve", "receive"), 1);
+/// assert_eq!(levenshtein("recieve", "receive"), 2);
+/// ```
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for i in 0..a.len() {
+        current[0] = i + 1;
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            let mut best = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                best = best.min(before[j - 1] + 1);
+            }
+            current[j + 1] = best;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// A string found by [`FuzzyMatcher::find`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch<'a> {
+    /// The id of the string in the underlying index
+    pub id: usize,
+    /// The matched string
+    pub text: &'a str,
+    /// Its edit distance to the query
+    pub distance: usize,
+    /// `1 - distance / max length`, in characters: `1.0` for an exact match
+    pub score: f64,
+    /// The Jaccard score of the character n-grams that made it a candidate
+    pub ngram_score: f64,
+}
+
+/// Two-stage fuzzy lookup: an [`NGramIndex`] proposes candidates sharing character
+/// n-grams with the query, and an edit distance ranks them.
+///
+/// The n-gram prefilter keeps lookups fast on large vocabularies, since the edit
+/// distance is only computed for the best `candidates` strings by n-gram score. A
+/// match that shares no n-gram with the query, such as a string shorter than `n`
+/// characters, is never found.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{EditDistance, FuzzyMatcher};
+///
+/// let mut matcher = FuzzyMatcher::new(2).distance(EditDistance::DamerauLevenshtein);
+/// for city in ["Amsterdam", "Rotterdam", "Antwerp", "Amstelveen"] {
+///     matcher.insert(city);
+/// }
+///
+/// let matches = matcher.find("Amsetrdam", 3, 2);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!((matches[0].text, matches[0].distance), ("Amsterdam", 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FuzzyMatcher {
+    index: NGramIndex,
+    distance: EditDistance,
+    candidates: usize,
+}
+
+impl FuzzyMatcher {
+    /// Creates an empty matcher over character n-grams of size `n`, ranking by
+    /// Levenshtein distance among 50 candidates.
+    pub fn new(n: usize) -> Self {
+        Self::from_index(NGramIndex::new(n))
+    }
+
+    /// Creates a matcher over an already built index.
+    pub fn from_index(index: NGramIndex) -> Self {
+        FuzzyMatcher {
+            index,
+            distance: EditDistance::default(),
+            candidates: 50,
+        }
+    }
+
+    /// Sets the edit distance used to rank candidates.
+    pub fn distance(mut self, distance: EditDistance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Sets how many of the best n-gram candidates are ranked by edit distance.
+    ///
+    /// More candidates find matches with more edits, at the cost of more distance
+    /// computations per query.
+    pub fn candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Returns the underlying n-gram index.
+    pub fn index(&self) -> &NGramIndex {
+        &self.index
+    }
+
+    /// Adds a string and returns its id, as [`NGramIndex::insert`] does.
+    pub fn insert(&mut self, text: &str) -> usize {
+        self.index.insert(text)
+    }
+
+    /// Finds the indexed strings closest to `query`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The string to look up
+    /// * `limit` - The maximum number of matches returned
+    /// * `max_distance` - The largest edit distance accepted
+    ///
+    /// # Returns
+    ///
+    /// The matches by increasing distance, ties broken by decreasing n-gram score, then
+    /// by ascending id.
+    pub fn find(&self, query: &str, limit: usize, max_distance: usize) -> Vec<FuzzyMatch<'_>> {
+        let query_len = query.chars().count();
+        let mut matches: Vec<FuzzyMatch> = self
+            .index
+            .search(query, self.candidates, 0.0)
+            .into_iter()
+            .filter_map(|(id, ngram_score)| {
+                let text = self.index.get(id)?;
+                let text_len = text.chars().count();
+                // The length difference alone is a lower bound on the distance
+                if text_len.abs_diff(query_len) > max_distance {
+                    return None;
+                }
+                let distance = self.distance.between(query, text);
+                (distance <= max_distance).then(|| FuzzyMatch {
+                    id,
+                    text,
+                    distance,
+                    score: 1.0 - distance as f64 / query_len.max(text_len).max(1) as f64,
+                    ngram_score,
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(b.ngram_score.total_cmp(&a.ngram_score))
+                .then(a.id.cmp(&b.id))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests both distances on empty, multi-byte and transposed inputs
+    #[test]
+    fn test_edit_distances() {
+        for distance in [levenshtein, damerau_levenshtein] {
+            assert_eq!(distance("", ""), 0);
+            assert_eq!(distance("", "abc"), 3);
+            assert_eq!(distance("abc", ""), 3);
+            assert_eq!(distance("flaw", "lawn"), 2);
+            assert_eq!(distance("naïve", "naive"), 1);
+        }
+        assert_eq!(levenshtein("ab", "ba"), 2);
+        assert_eq!(damerau_levenshtein("ab", "ba"), 1);
+        // The optimal string alignment variant does not edit a transposed pair again
+        assert_eq!(damerau_levenshtein("ca", "abc"), 3);
+        assert_eq!(EditDistance::DamerauLevenshtein.between("abcd", "acbd"), 1);
+    }
+
+    /// Tests ranking, the distance cut-off and the candidate limit
+    #[test]
+    fn test_fuzzy_matcher() {
+        let mut matcher = FuzzyMatcher::new(2);
+        for word in ["colour", "color", "collar", "dolour", "colours"] {
+            matcher.insert(word);
+        }
+
+        let found: Vec<(&str, usize)> = matcher
+            .find("colour", 10, 2)
+            .iter()
+            .map(|m| (m.text, m.distance))
+            .collect();
+        // Equal distances are ranked by n-gram score
+        assert_eq!(
+            found,
+            [
+                ("colour", 0),
+                ("colours", 1),
+                ("dolour", 1),
+                ("color", 1),
+                ("collar", 2)
+            ]
+        );
+        assert_eq!(matcher.find("colour", 1, 0)[0].score, 1.0);
+        assert_eq!(matcher.find("colour", 10, 0).len(), 1);
+
+        let narrow = FuzzyMatcher::from_index(matcher.index().clone()).candidates(1);
+        assert_eq!(narrow.find("colour", 10, 5).len(), 1);
+        assert!(matcher.find("xyz", 10, 10).is_empty());
+    }
+}
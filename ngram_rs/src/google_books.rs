@@ -0,0 +1,170 @@
+use std::io::{self, BufRead, Write};
+use std::ops::RangeBounds;
+
+use crate::NGramCounter;
+
+/// One line of a Google Books Ngram file: `ngram TAB year TAB match_count TAB volume_count`.
+///
+/// Tokens of the n-gram are separated by single spaces, as in the published datasets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoogleBooksRecord {
+    /// The n-gram, tokens separated by spaces
+    pub ngram: String,
+    /// Publication year
+    pub year: u32,
+    /// Number of occurrences in books of that year
+    pub match_count: u64,
+    /// Number of distinct books of that year containing the n-gram
+    pub volume_count: u64,
+}
+
+impl GoogleBooksRecord {
+    /// Parses one line, without its trailing newline.
+    ///
+    /// Returns `None` if the line does not have exactly four tab-separated fields or a
+    /// number fails to parse.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let record = GoogleBooksRecord {
+            ngram: fields.next()?.to_string(),
+            year: fields.next()?.parse().ok()?,
+            match_count: fields.next()?.parse().ok()?,
+            volume_count: fields.next()?.parse().ok()?,
+        };
+        fields.next().is_none().then_some(record)
+    }
+
+    /// Returns the order of the n-gram, i.e. its number of space-separated tokens.
+    pub fn n(&self) -> usize {
+        self.ngram.split(' ').count()
+    }
+
+    /// Writes the record as one line, including the trailing newline.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{}\t{}\t{}\t{}",
+            self.ngram, self.year, self.match_count, self.volume_count
+        )
+    }
+}
+
+/// Iterates over the records of a Google Books Ngram file.
+///
+/// Empty lines are skipped; a malformed line yields an `InvalidData` error naming its
+/// line number.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::google_books_records;
+///
+/// let data = "black cat\t1999\t12\t7\nblack cat\t2000\t30\t11\n";
+/// let records: Vec<_> = google_books_records(data.as_bytes())
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(records[1].year, 2000);
+/// assert_eq!(records[1].n(), 2);
+/// ```
+pub fn google_books_records<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = io::Result<GoogleBooksRecord>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(l) if l.is_empty()))
+        .map(|(i, line)| {
+            let line = line?;
+            GoogleBooksRecord::parse(&line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed Google Books ngram record on line {}", i + 1),
+                )
+            })
+        })
+}
+
+impl NGramCounter {
+    /// Loads a Google Books Ngram file, summing match counts over the selected years.
+    ///
+    /// The order of each n-gram is its number of space-separated tokens, so files of
+    /// different orders can be read into the same counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The uncompressed TSV data
+    /// * `years` - The years to aggregate, e.g. `1990..2000` or `..` for all of them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let data = "black cat\t1999\t12\t7\nblack cat\t2000\t30\t11\ncat\t2000\t5\t2\n";
+    /// let counter = NGramCounter::read_google_books(data.as_bytes(), 2000..).unwrap();
+    ///
+    /// assert_eq!(counter.get_order("black cat", 2), 30);
+    /// assert_eq!(counter.get_order("cat", 1), 5);
+    /// ```
+    pub fn read_google_books<R: BufRead>(
+        reader: R,
+        years: impl RangeBounds<u32>,
+    ) -> io::Result<Self> {
+        let mut counter = NGramCounter::new();
+        for record in google_books_records(reader) {
+            let record = record?;
+            if years.contains(&record.year) {
+                counter.add(&record.ngram, record.n(), record.match_count as usize);
+            }
+        }
+        Ok(counter)
+    }
+
+    /// Writes the counts in the Google Books Ngram format, all attributed to `year`.
+    ///
+    /// The counter does not track volumes, so the whole counter is treated as a single
+    /// volume and `volume_count` is written as 1. N-grams must use a single space as
+    /// delimiter to be read back with the right order. Lines are sorted by order and
+    /// then by n-gram.
+    pub fn write_google_books<W: Write>(&self, mut writer: W, year: u32) -> io::Result<()> {
+        for n in self.orders() {
+            for (ngram, count) in self.sorted_order(n) {
+                writeln!(writer, "{ngram}\t{year}\t{count}\t1")?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests record parsing, malformed lines and a write/read round trip
+    #[test]
+    fn test_google_books_round_trip() {
+        assert_eq!(GoogleBooksRecord::parse("a b\t1900\t3"), None);
+        assert_eq!(GoogleBooksRecord::parse("a b\t1900\t3\t1\textra"), None);
+        assert_eq!(GoogleBooksRecord::parse("a b\tyear\t3\t1"), None);
+
+        let bad = "ok\t2000\t1\t1\n\nbroken line\n";
+        let err = NGramCounter::read_google_books(bad.as_bytes(), ..).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 3"));
+
+        let mut counter = NGramCounter::new();
+        counter.add_document(&["the", "cat", "sat", "the", "cat"], &[1, 2], None);
+        let mut out = Vec::new();
+        counter.write_google_books(&mut out, 2024).unwrap();
+        assert!(String::from_utf8_lossy(&out).starts_with("cat\t2024\t2\t1\n"));
+
+        let restored = NGramCounter::read_google_books(out.as_slice(), 2024..=2024).unwrap();
+        assert_eq!(restored, counter);
+        assert!(
+            NGramCounter::read_google_books(out.as_slice(), ..2024)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
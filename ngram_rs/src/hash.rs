@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+/// Separates tokens inside a hashed n-gram; the byte never occurs in UTF-8 text
+const SEPARATOR: u8 = 0xff;
+
+/// Hashes every n-gram of `words` to a `u64` without joining any strings.
+///
+/// Meant for feature hashing, where the joined n-grams would only be hashed and
+/// dropped. Each n-gram is hashed with FNV-1a over its tokens, separated by a byte
+/// that cannot occur in text, and the result is passed through a 64-bit finalizer so
+/// that the low bits can be used directly as bucket indices. The hash depends only on
+/// the tokens and the seed, and is stable across platforms and releases.
+///
+/// # Arguments
+///
+/// * `words` - A slice of strings representing the input text as individual words
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `seed` - Seed mixed into every hash, to draw independent hash functions
+///
+/// # Returns
+///
+/// One hash per n-gram, in the same order as [`generate_ngrams`](crate::generate_ngrams)
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::hash_ngrams;
+///
+/// let hashes = hash_ngrams(&["a", "b", "a", "b"], &[2], 0);
+///
+/// assert_eq!(hashes.len(), 3);
+/// assert_eq!(hashes[0], hashes[2]);
+/// assert_ne!(hashes[0], hashes[1]);
+/// assert_ne!(hashes, hash_ngrams(&["a", "b", "a", "b"], &[2], 1));
+/// ```
+pub fn hash_ngrams<S: AsRef<str>>(words: &[S], n_range: &[usize], seed: u64) -> Vec<u64> {
+    let start = FNV_OFFSET ^ mix(seed);
+    let mut hashes = Vec::with_capacity(crate::ngram_count(words.len(), n_range));
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            let mut hash = start;
+            for (i, word) in window.iter().enumerate() {
+                if i > 0 {
+                    hash = fnv1a(hash, &[SEPARATOR]);
+                }
+                hash = fnv1a(hash, word.as_ref().as_bytes());
+            }
+            hashes.push(mix(hash));
+        }
+    }
+    hashes
+}
+
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// The SplitMix64 finalizer, spreading every input bit over the whole output.
+pub(crate) fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that hashes follow n-gram order and tell token boundaries apart
+    #[test]
+    fn test_hash_ngrams() {
+        let words = ["ab", "c", "a", "bc"];
+        let hashes = hash_ngrams(&words, &[1, 2, 5], 7);
+        assert_eq!(hashes.len(), crate::ngram_count(words.len(), &[1, 2, 5]));
+        assert_eq!(hashes[0], hash_ngrams(&["ab"], &[1], 7)[0]);
+        assert_eq!(hashes[4], hash_ngrams(&["ab", "c"], &[2], 7)[0]);
+        // "ab c" and "a bc" join to the same text without the separator
+        assert_ne!(hashes[4], hash_ngrams(&["a", "bc"], &[2], 7)[0]);
+        assert!(hash_ngrams(&words, &[0, 5], 7).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{CancelToken, Cancelled, generate_char_ngrams};
+
+/// An inverted index of character n-grams for fuzzy string lookup.
+///
+/// Each inserted string is broken into its set of character n-grams; a query is scored
+/// against every indexed string sharing at least one n-gram, using the Jaccard
+/// similarity of the two n-gram sets. Strings shorter than `n` characters have no
+/// n-grams and can never be matched.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::NGramIndex;
+///
+/// let mut index = NGramIndex::new(2);
+/// index.insert("apple");
+/// let banana = index.insert("banana");
+///
+/// let hits = index.search("bananas", 5, 0.5);
+/// assert_eq!(hits[0].0, banana);
+/// assert_eq!(index.get(banana), Some("banana"));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct NGramIndex {
+    n: usize,
+    entries: Vec<String>,
+    gram_counts: Vec<usize>,
+    postings: HashMap<String, Vec<usize>>,
+}
+
+impl NGramIndex {
+    /// Creates an empty index over character n-grams of size `n`.
+    pub fn new(n: usize) -> Self {
+        NGramIndex {
+            n,
+            entries: Vec::new(),
+            gram_counts: Vec::new(),
+            postings: HashMap::new(),
+        }
+    }
+
+    /// Returns the n-gram size used by the index.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of indexed strings.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no string has been indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the string stored under `id`.
+    pub fn get(&self, id: usize) -> Option<&str> {
+        self.entries.get(id).map(String::as_str)
+    }
+
+    /// Adds a string to the index and returns its id.
+    ///
+    /// Ids are assigned sequentially from zero; inserting the same string twice gives
+    /// two distinct ids.
+    pub fn insert(&mut self, text: &str) -> usize {
+        let id = self.entries.len();
+        let grams: HashSet<&str> = generate_char_ngrams(text, &[self.n]).into_iter().collect();
+
+        for gram in &grams {
+            match self.postings.get_mut(*gram) {
+                Some(ids) => ids.push(id),
+                None => {
+                    self.postings.insert(gram.to_string(), vec![id]);
+                }
+            }
+        }
+        self.gram_counts.push(grams.len());
+        self.entries.push(text.to_string());
+        id
+    }
+
+    /// Adds many strings to the index, checking `cancel` before each one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Cancelled`] as soon as the token is cancelled; the strings added
+    /// before that stay in the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{CancelToken, NGramIndex};
+    ///
+    /// let mut index = NGramIndex::new(2);
+    /// index.insert_all(["apple", "maple"], &CancelToken::new()).unwrap();
+    /// assert_eq!(index.len(), 2);
+    /// ```
+    pub fn insert_all<I, S>(&mut self, texts: I, cancel: &CancelToken) -> Result<(), Cancelled>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for text in texts {
+            cancel.check()?;
+            self.insert(text.as_ref());
+        }
+        Ok(())
+    }
+
+    /// Finds the indexed strings most similar to `query`.
+    ///
+    /// Returns at most `limit` `(id, score)` pairs whose Jaccard score is at least
+    /// `min_score`, best first; ties are broken by ascending id.
+    pub fn search(&self, query: &str, limit: usize, min_score: f64) -> Vec<(usize, f64)> {
+        let grams: HashSet<&str> = generate_char_ngrams(query, &[self.n]).into_iter().collect();
+
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for gram in &grams {
+            if let Some(ids) = self.postings.get(*gram) {
+                for &id in ids {
+                    *shared.entry(id).or_insert(0) += 1;
+                }
+            }
+        }
+
+        rank_matches(
+            grams.len(),
+            shared,
+            |id| self.gram_counts[id],
+            limit,
+            min_score,
+        )
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedNGramIndex {
+    /// Returns the number of indexed strings.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no string has been indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the string stored under `id`.
+    pub fn get(&self, id: usize) -> Option<&str> {
+        self.entries.get(id).map(|s| s.as_str())
+    }
+
+    /// Finds the indexed strings most similar to `query`, reading the archive in place.
+    ///
+    /// Behaves exactly like [`NGramIndex::search`], without deserializing the index first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{ArchivedNGramIndex, NGramIndex};
+    ///
+    /// let mut index = NGramIndex::new(2);
+    /// index.insert("hello");
+    /// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&index).unwrap();
+    ///
+    /// let archived = rkyv::access::<ArchivedNGramIndex, rkyv::rancor::Error>(&bytes).unwrap();
+    /// assert_eq!(archived.search("help", 5, 0.0), index.search("help", 5, 0.0));
+    /// ```
+    pub fn search(&self, query: &str, limit: usize, min_score: f64) -> Vec<(usize, f64)> {
+        let n = self.n.to_native() as usize;
+        let grams: HashSet<&str> = generate_char_ngrams(query, &[n]).into_iter().collect();
+
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for gram in &grams {
+            if let Some(ids) = self.postings.get(*gram) {
+                for id in ids.iter() {
+                    *shared.entry(id.to_native() as usize).or_insert(0) += 1;
+                }
+            }
+        }
+
+        rank_matches(
+            grams.len(),
+            shared,
+            |id| self.gram_counts[id].to_native() as usize,
+            limit,
+            min_score,
+        )
+    }
+}
+
+/// Turns shared n-gram counts into Jaccard scores and keeps the best `limit` of them.
+fn rank_matches(
+    query_grams: usize,
+    shared: HashMap<usize, usize>,
+    gram_count: impl Fn(usize) -> usize,
+    limit: usize,
+    min_score: f64,
+) -> Vec<(usize, f64)> {
+    let mut hits: Vec<(usize, f64)> = shared
+        .into_iter()
+        .map(|(id, inter)| {
+            let union = query_grams + gram_count(id) - inter;
+            (id, inter as f64 / union as f64)
+        })
+        .filter(|&(_, score)| score >= min_score)
+        .collect();
+
+    hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests ranking, thresholds and limits of index search
+    #[test]
+    fn test_index_search() {
+        let mut index = NGramIndex::new(3);
+        for name in ["jonathan", "johnathan", "jon", "nathan", "mary"] {
+            index.insert(name);
+        }
+
+        let hits = index.search("jonathan", 10, 0.0);
+        assert_eq!(hits[0], (0, 1.0));
+        assert!(hits.iter().all(|&(id, _)| id != 4));
+
+        let hits = index.search("jonathan", 1, 0.0);
+        assert_eq!(hits.len(), 1);
+
+        assert!(index.search("jonathan", 10, 0.99).len() == 1);
+        assert!(index.search("zzz", 10, 0.0).is_empty());
+    }
+
+    /// Tests that bulk insertion keeps what was added before a cancellation
+    #[test]
+    fn test_insert_all_cancelled() {
+        let token = CancelToken::new();
+        let mut index = NGramIndex::new(2);
+        let names = ["ab", "bc", "cd"].into_iter().inspect(|&name| {
+            if name == "bc" {
+                token.cancel();
+            }
+        });
+        assert_eq!(index.insert_all(names, &token), Err(Cancelled));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.get(0), Some("ab"));
+    }
+
+    /// Tests that a serialized index answers queries like the original
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_index_serde_round_trip() {
+        let mut index = NGramIndex::new(2);
+        index.insert("hello");
+        index.insert("help");
+
+        let json = serde_json::to_string(&index).unwrap();
+        let restored: NGramIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            restored.search("hell", 5, 0.0),
+            index.search("hell", 5, 0.0)
+        );
+    }
+
+    /// Tests that an archived index validates and answers queries in place
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_index_rkyv_in_place() {
+        let mut index = NGramIndex::new(3);
+        for name in ["jonathan", "johnathan", "mary"] {
+            index.insert(name);
+        }
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&index).unwrap();
+
+        let archived = rkyv::access::<ArchivedNGramIndex, rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived.get(2), Some("mary"));
+        assert_eq!(
+            archived.search("jonathon", 5, 0.0),
+            index.search("jonathon", 5, 0.0)
+        );
+        assert!(rkyv::access::<ArchivedNGramIndex, rkyv::rancor::Error>(&bytes[1..]).is_err());
+    }
+}
@@ -16,7 +16,143 @@
 //! ]);
 //! ```
 
-use std::borrow::Cow;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "bumpalo")]
+mod arena;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "lm")]
+mod autocomplete;
+#[cfg(feature = "std")]
+mod cancel;
+mod char_ngrams;
+#[cfg(feature = "chunked")]
+mod chunked;
+#[cfg(feature = "compact_str")]
+mod compact;
+#[cfg(feature = "corpus")]
+mod corpus;
+#[cfg(feature = "std")]
+mod counter;
+#[cfg(feature = "std")]
+mod detect;
+mod ext;
+#[cfg(feature = "rayon")]
+mod files;
+#[cfg(feature = "std")]
+mod frontcode;
+#[cfg(feature = "similarity")]
+mod fuzzy;
+#[cfg(feature = "std")]
+mod google_books;
+mod hash;
+#[cfg(feature = "similarity")]
+mod index;
+#[cfg(feature = "lm")]
+mod lm;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "similarity")]
+mod minhash;
+mod ngram;
+#[cfg(feature = "parquet")]
+mod parquet;
+mod phonetic;
+mod placeholder;
+#[cfg(feature = "rand")]
+mod sampling;
+#[cfg(feature = "similarity")]
+mod similarity;
+mod skipgram;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "async")]
+mod stream;
+#[cfg(feature = "similarity")]
+mod string_kernel;
+#[cfg(feature = "std")]
+mod temporal;
+mod tokenize;
+
+#[cfg(feature = "bumpalo")]
+pub use arena::generate_ngrams_in;
+#[cfg(feature = "arrow")]
+pub use arrow::{ngrams_list_array, ngrams_string_array};
+#[cfg(feature = "lm")]
+pub use autocomplete::{Autocomplete, Completion};
+#[cfg(feature = "std")]
+pub use cancel::{CancelToken, Cancelled};
+#[cfg(feature = "unicode")]
+pub use char_ngrams::generate_grapheme_ngrams;
+pub use char_ngrams::{
+    SymbolAction, SymbolPolicy, generate_byte_ngrams, generate_char_ngrams,
+    generate_char_ngrams_with,
+};
+#[cfg(feature = "chunked")]
+pub use chunked::ChunkedProcessor;
+#[cfg(feature = "compact_str")]
+pub use compact::generate_ngrams_compact;
+#[cfg(feature = "corpus")]
+pub use corpus::{CsvDocuments, JsonlDocuments, csv_documents, jsonl_documents};
+#[cfg(feature = "std")]
+pub use counter::{CorpusStats, Coverage, EntryOrder, NGramCounter, OrderStats, RankFrequency};
+#[cfg(feature = "std")]
+pub use detect::LanguageDetector;
+pub use ext::NGramExt;
+#[cfg(feature = "rayon")]
+pub use files::{CountOptions, count_files};
+#[cfg(feature = "similarity")]
+pub use fuzzy::{EditDistance, FuzzyMatch, FuzzyMatcher, damerau_levenshtein, levenshtein};
+#[cfg(feature = "std")]
+pub use google_books::{GoogleBooksRecord, google_books_records};
+pub use hash::hash_ngrams;
+#[cfg(feature = "rkyv")]
+pub use index::ArchivedNGramIndex;
+#[cfg(feature = "similarity")]
+pub use index::NGramIndex;
+#[cfg(all(feature = "lm", feature = "rayon"))]
+pub use lm::BatchPerplexity;
+#[cfg(feature = "lm")]
+pub use lm::{CachedModel, InterpolatedModel, LanguageModel, Smoothing, TokenTrace};
+#[cfg(feature = "std")]
+pub use metrics::{Keyness, RougeScore, bleu, keyness, rouge_n};
+#[cfg(feature = "similarity")]
+pub use minhash::{MinHash, minhash_similarity};
+pub use ngram::{NGram, NGramWindowIterator, ngram_windows};
+pub use phonetic::{PhoneticCode, metaphone, soundex};
+pub use placeholder::Placeholders;
+#[cfg(feature = "rand")]
+pub use sampling::Sampling;
+#[cfg(feature = "similarity")]
+pub use similarity::{char_ngram_jaccard, dice_similarity, jaccard_similarity};
+pub use skipgram::{generate_skipgrams, write_skipgrams_into};
+#[cfg(feature = "async")]
+pub use stream::{AsyncLineDocuments, NGramStream, async_line_documents};
+#[cfg(feature = "similarity")]
+pub use string_kernel::{
+    spectrum_gram_matrix, spectrum_kernel, subsequence_gram_matrix, subsequence_kernel,
+};
+#[cfg(feature = "std")]
+pub use temporal::{Burst, TemporalCounter};
+pub use tokenize::{
+    CaseLocale, CjkPolicy, CjkTokens, IntoTokens, ScriptSplit, WhitespaceTokens, lowercase,
+    tokenize_cjk, tokenize_whitespace,
+};
+#[cfg(feature = "unicode")]
+pub use tokenize::{
+    NormalizationForm, normalize_unicode, strip_diacritics, tokenize_unicode_words,
+};
 
 /// Generates n-grams from a sequence of words with configurable n-gram sizes and delimiter.
 ///
@@ -26,22 +162,60 @@ use std::borrow::Cow;
 ///
 /// # Arguments
 ///
-/// * `words` - A slice of String objects representing the input text as individual words
+/// * `words` - The words, such as a slice of `String` or `&str`, or raw text split on whitespace (see [`IntoTokens`])
 /// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
 /// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
 ///
 /// # Returns
 ///
 /// A vector of `Cow<str>` where:
-/// - Unigrams (n=1) are returned as `Cow::Borrowed` to avoid allocation
+/// - Unigrams (n=1) are returned as `Cow::Borrowed` to avoid allocation, unless `words` is an owned `Vec<String>`
 /// - Bigrams and higher n-grams are returned as `Cow::Owned` strings
-pub fn generate_ngrams<'a>(
-    words: &'a [String],
+pub fn generate_ngrams<'a, T: IntoTokens<'a>>(
+    words: T,
     n_range: &[usize],
     delimiter: Option<&str>,
 ) -> Vec<Cow<'a, str>> {
-    let delimiter = delimiter.unwrap_or(" ");
-    let mut result = Vec::new();
+    generate(words, n_range, delimiter.unwrap_or(" "), false)
+}
+
+/// Generates n-grams like [`generate_ngrams`], with the tokens of each n-gram joined
+/// last to first.
+///
+/// Reversed n-grams put the context after the word, as suffix-context language models
+/// and backward search indexes expect. Only the join order changes: the n-grams are
+/// still emitted in document order, and unigrams are unaffected.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_ngrams_reversed;
+///
+/// let ngrams = generate_ngrams_reversed(&["the", "quick", "fox"], &[2], None);
+/// assert_eq!(ngrams, ["quick the", "fox quick"]);
+/// ```
+pub fn generate_ngrams_reversed<'a, T: IntoTokens<'a>>(
+    words: T,
+    n_range: &[usize],
+    delimiter: Option<&str>,
+) -> Vec<Cow<'a, str>> {
+    generate(words, n_range, delimiter.unwrap_or(" "), true)
+}
+
+fn generate<'a, T: IntoTokens<'a>>(
+    words: T,
+    n_range: &[usize],
+    delimiter: &str,
+    reversed: bool,
+) -> Vec<Cow<'a, str>> {
+    let tokens = words.into_tokens();
+    let words: &[T::Token] = &tokens;
+    let join = if reversed {
+        join_window_reversed
+    } else {
+        join_window
+    };
+    let mut result = Vec::with_capacity(ngram_count(words.len(), n_range));
 
     for &n in n_range {
         if n == 0 || n > words.len() {
@@ -51,24 +225,13 @@ pub fn generate_ngrams<'a>(
         match n {
             1 => {
                 // For unigrams, we can use references directly
-                result.extend(words.iter().map(|w| Cow::Borrowed(w.as_str())));
-            }
-            2 => {
-                // For bigrams, we can avoid some intermediate allocations
-                for window in words.windows(2) {
-                    let mut ngram =
-                        String::with_capacity(window[0].len() + window[1].len() + delimiter.len());
-                    ngram.push_str(&window[0]);
-                    ngram.push_str(delimiter);
-                    ngram.push_str(&window[1]);
-                    result.push(Cow::Owned(ngram));
-                }
+                result.extend((0..words.len()).map(|i| T::unigram(&tokens, i)));
             }
             _ => {
-                // For higher n-grams, use the standard join
+                // For bigrams and higher n-grams, size each string from its window
+                // and fill it directly instead of going through `slice::join`
                 for window in words.windows(n) {
-                    let ngram = window.join(delimiter);
-                    result.push(Cow::Owned(ngram));
+                    result.push(Cow::Owned(join(window, delimiter)));
                 }
             }
         }
@@ -77,6 +240,27 @@ pub fn generate_ngrams<'a>(
     result
 }
 
+/// Returns the number of n-grams generated for `num_words` tokens and the given `n_range`.
+///
+/// Each valid n contributes `num_words - n + 1` n-grams; zero and n-values larger than
+/// `num_words` contribute nothing, mirroring how the generators skip them.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::ngram_count;
+///
+/// assert_eq!(ngram_count(4, &[1, 2, 3]), 4 + 3 + 2);
+/// assert_eq!(ngram_count(2, &[0, 3]), 0);
+/// ```
+pub fn ngram_count(num_words: usize, n_range: &[usize]) -> usize {
+    n_range
+        .iter()
+        .filter(|&&n| n != 0 && n <= num_words)
+        .map(|&n| num_words - n + 1)
+        .sum()
+}
+
 /// Generates n-grams and returns owned strings, useful for integration with Polars.
 ///
 /// This is a convenience wrapper around `generate_ngrams` that converts all results
@@ -85,7 +269,7 @@ pub fn generate_ngrams<'a>(
 ///
 /// # Arguments
 ///
-/// * `words` - A slice of String objects representing the input text as individual words
+/// * `words` - The words, such as a slice of `String` or `&str`, or raw text split on whitespace (see [`IntoTokens`])
 /// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
 /// * `delimiter` - Delimiter string to use between words in n-grams
 ///
@@ -102,14 +286,429 @@ pub fn generate_ngrams<'a>(
 /// let ngrams = generate_ngrams_owned(&words, &[2], "-");
 ///
 /// assert_eq!(ngrams, vec!["hello-world".to_string()]);
+///
+/// // Tokens borrowed from a larger string work the same way
+/// let tokens = ["hello", "world"];
+/// assert_eq!(generate_ngrams_owned(&tokens, &[2], "-"), ngrams);
 /// ```
-pub fn generate_ngrams_owned(words: &[String], n_range: &[usize], delimiter: &str) -> Vec<String> {
+pub fn generate_ngrams_owned<'a, T: IntoTokens<'a>>(
+    words: T,
+    n_range: &[usize],
+    delimiter: &str,
+) -> Vec<String> {
     generate_ngrams(words, n_range, Some(delimiter))
         .into_iter()
         .map(|cow| cow.into_owned())
         .collect()
 }
 
+/// Writes n-grams into a caller-provided vector, reusing its existing allocations.
+///
+/// Strings already present in `out` are cleared and overwritten in place, so a vector
+/// kept across documents stops allocating once its buffers are large enough. The
+/// vector is truncated to the number of n-grams written, which is also returned.
+///
+/// # Arguments
+///
+/// * `words` - The words, or raw text split on whitespace (see [`IntoTokens`])
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
+/// * `out` - Output vector whose contents are replaced by the generated n-grams
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::write_ngrams_into;
+///
+/// let mut out = Vec::new();
+/// for doc in [vec!["a".to_string(), "b".to_string()], vec!["c".to_string()]] {
+///     write_ngrams_into(&doc, &[1, 2], None, &mut out);
+/// }
+///
+/// assert_eq!(out, vec!["c".to_string()]);
+/// ```
+pub fn write_ngrams_into<'a, T: IntoTokens<'a>>(
+    words: T,
+    n_range: &[usize],
+    delimiter: Option<&str>,
+    out: &mut Vec<String>,
+) -> usize {
+    let tokens = words.into_tokens();
+    let words: &[T::Token] = &tokens;
+    let delimiter = delimiter.unwrap_or(" ");
+    let mut written = 0;
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            if written < out.len() {
+                let ngram = &mut out[written];
+                ngram.clear();
+                push_window(ngram, window, delimiter);
+            } else {
+                let mut ngram = String::new();
+                push_window(&mut ngram, window, delimiter);
+                out.push(ngram);
+            }
+            written += 1;
+        }
+    }
+
+    out.truncate(written);
+    written
+}
+
+/// Counts n-grams into a caller-provided map, accumulating across calls.
+///
+/// A single scratch buffer is used to build each n-gram, so only n-grams not yet
+/// present in `counts` allocate a new key.
+///
+/// # Arguments
+///
+/// * `words` - The words, or raw text split on whitespace (see [`IntoTokens`])
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
+/// * `counts` - Map from n-gram to occurrence count, updated in place
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ngram_rs::count_ngrams_into;
+///
+/// let mut counts = HashMap::new();
+/// let words = vec!["a".to_string(), "a".to_string(), "a".to_string()];
+/// count_ngrams_into(&words, &[2], None, &mut counts);
+///
+/// assert_eq!(counts["a a"], 2);
+/// ```
+#[cfg(feature = "std")]
+pub fn count_ngrams_into<'a, T: IntoTokens<'a>>(
+    words: T,
+    n_range: &[usize],
+    delimiter: Option<&str>,
+    counts: &mut HashMap<String, usize>,
+) {
+    let tokens = words.into_tokens();
+    count_into(&tokens, n_range, delimiter.unwrap_or(" "), false, counts);
+}
+
+/// Counts the n-grams of `words` into `counts`, joining their tokens last to first
+/// when `reversed` is set.
+#[cfg(feature = "std")]
+pub(crate) fn count_into<S: AsRef<str>>(
+    words: &[S],
+    n_range: &[usize],
+    delimiter: &str,
+    reversed: bool,
+    counts: &mut HashMap<String, usize>,
+) {
+    let mut scratch = String::new();
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            scratch.clear();
+            if reversed {
+                push_window_reversed(&mut scratch, window, delimiter);
+            } else {
+                push_window(&mut scratch, window, delimiter);
+            }
+            match counts.get_mut(scratch.as_str()) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.insert(scratch.clone(), 1);
+                }
+            }
+        }
+    }
+}
+
+/// Generates n-grams into a single contiguous buffer plus the byte range of each n-gram.
+///
+/// This layout matches Arrow/Polars string arrays (one values buffer and offsets) and
+/// performs exactly two allocations regardless of the number of n-grams: the buffer
+/// and the range vector are both sized up front.
+///
+/// # Arguments
+///
+/// * `words` - A slice of String objects representing the input text as individual words
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
+///
+/// # Returns
+///
+/// A tuple of the packed buffer and, for each n-gram in generation order, its byte
+/// range within that buffer.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_ngrams_packed;
+///
+/// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let (buffer, ranges) = generate_ngrams_packed(&words, &[2], None);
+///
+/// assert_eq!(buffer, "a bb c");
+/// assert_eq!(&buffer[ranges[1].clone()], "b c");
+/// ```
+pub fn generate_ngrams_packed(
+    words: &[String],
+    n_range: &[usize],
+    delimiter: Option<&str>,
+) -> (String, Vec<Range<usize>>) {
+    let delimiter = delimiter.unwrap_or(" ");
+
+    let mut buffer = String::with_capacity(ngram_bytes(words, n_range, delimiter));
+    let mut ranges = Vec::with_capacity(ngram_count(words.len(), n_range));
+
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+
+        for window in words.windows(n) {
+            let start = buffer.len();
+            push_window(&mut buffer, window, delimiter);
+            ranges.push(start..buffer.len());
+        }
+    }
+
+    (buffer, ranges)
+}
+
+/// Returns the total byte length of all n-grams generated from `words`.
+pub(crate) fn ngram_bytes<S: AsRef<str>>(words: &[S], n_range: &[usize], delimiter: &str) -> usize {
+    // prefix[i] is the total byte length of words[..i]
+    let mut prefix = Vec::with_capacity(words.len() + 1);
+    prefix.push(0);
+    for word in words {
+        prefix.push(prefix[prefix.len() - 1] + word.as_ref().len());
+    }
+
+    let mut total_bytes = 0;
+    for &n in n_range {
+        if n == 0 || n > words.len() {
+            continue;
+        }
+        for start in 0..=words.len() - n {
+            total_bytes += prefix[start + n] - prefix[start] + (n - 1) * delimiter.len();
+        }
+    }
+    total_bytes
+}
+
+/// Appends the words of `window` to `buf`, separated by `delimiter`.
+fn push_window<S: AsRef<str>>(buf: &mut String, window: &[S], delimiter: &str) {
+    for (i, word) in window.iter().enumerate() {
+        if i > 0 {
+            buf.push_str(delimiter);
+        }
+        buf.push_str(word.as_ref());
+    }
+}
+
+/// Appends the words of `window` to `buf` last to first, separated by `delimiter`.
+fn push_window_reversed<S: AsRef<str>>(buf: &mut String, window: &[S], delimiter: &str) {
+    for (i, word) in window.iter().rev().enumerate() {
+        if i > 0 {
+            buf.push_str(delimiter);
+        }
+        buf.push_str(word.as_ref());
+    }
+}
+
+/// Returns the length of `window` joined with `delimiter`.
+fn joined_len<S: AsRef<str>>(window: &[S], delimiter: &str) -> usize {
+    window.iter().map(|w| w.as_ref().len()).sum::<usize>()
+        + window.len().saturating_sub(1) * delimiter.len()
+}
+
+/// Joins `window` with `delimiter` into a string allocated once at its final size.
+pub(crate) fn join_window<S: AsRef<str>>(window: &[S], delimiter: &str) -> String {
+    let mut ngram = String::with_capacity(joined_len(window, delimiter));
+    push_window(&mut ngram, window, delimiter);
+    ngram
+}
+
+/// Joins `window` last to first, like [`join_window`].
+pub(crate) fn join_window_reversed<S: AsRef<str>>(window: &[S], delimiter: &str) -> String {
+    let mut ngram = String::with_capacity(joined_len(window, delimiter));
+    push_window_reversed(&mut ngram, window, delimiter);
+    ngram
+}
+
+/// Character used to escape delimiters (and itself) inside tokens.
+pub const ESCAPE_CHAR: char = '\\';
+
+/// Escapes a single token so it can be joined with `delimiter` unambiguously.
+///
+/// Every character of the token that also occurs in `delimiter`, and every escape
+/// character, is prefixed with [`ESCAPE_CHAR`]. Escaping whole delimiter occurrences
+/// would not be enough for multi-character delimiters: with `"--"`, the token `"a-"`
+/// followed by the delimiter would read as `"a"` followed by a delimiter and `"-"`.
+/// Tokens that need no escaping, and every token when the delimiter is empty, are
+/// returned as `Cow::Borrowed`.
+///
+/// # Panics
+///
+/// Panics if `delimiter` contains the escape character, which would make escaped
+/// characters and delimiters ambiguous.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::escape_token;
+///
+/// assert_eq!(escape_token("New York", " "), "New\\ York");
+/// assert_eq!(escape_token("a-", "--"), "a\\-");
+/// assert_eq!(escape_token("plain", " "), "plain");
+/// ```
+pub fn escape_token<'a>(token: &'a str, delimiter: &str) -> Cow<'a, str> {
+    assert_escapable(delimiter);
+    let needs_escape = |c: char| c == ESCAPE_CHAR || delimiter.contains(c);
+    if delimiter.is_empty() || !token.contains(needs_escape) {
+        return Cow::Borrowed(token);
+    }
+
+    let mut escaped = String::with_capacity(token.len() + 2);
+    for c in token.chars() {
+        if needs_escape(c) {
+            escaped.push(ESCAPE_CHAR);
+        }
+        escaped.push(c);
+    }
+    Cow::Owned(escaped)
+}
+
+fn assert_escapable(delimiter: &str) {
+    assert!(
+        !delimiter.contains(ESCAPE_CHAR),
+        "the delimiter {delimiter:?} contains the escape character {ESCAPE_CHAR:?}"
+    );
+}
+
+/// Generates n-grams whose tokens are escaped so the output can be split back reliably.
+///
+/// Behaves like [`generate_ngrams`], except that each token is passed through
+/// [`escape_token`] before joining. Multi-word tokens such as `"New York"` therefore
+/// stay distinguishable from two separate tokens, and [`split_ngram`] recovers the
+/// original tokens exactly.
+///
+/// # Arguments
+///
+/// * `words` - A slice of String objects representing the input text as individual words
+/// * `n_range` - A slice of usize values specifying which n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string to use between words in n-grams (defaults to space)
+///
+/// # Panics
+///
+/// Panics if `delimiter` contains [`ESCAPE_CHAR`].
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{generate_ngrams_escaped, split_ngram};
+///
+/// let words = vec!["New York".to_string(), "City".to_string()];
+/// let ngrams = generate_ngrams_escaped(&words, &[2], None);
+///
+/// assert_eq!(ngrams, vec!["New\\ York City"]);
+/// assert_eq!(split_ngram(&ngrams[0], " "), vec!["New York", "City"]);
+/// ```
+pub fn generate_ngrams_escaped<'a>(
+    words: &'a [String],
+    n_range: &[usize],
+    delimiter: Option<&str>,
+) -> Vec<Cow<'a, str>> {
+    let delimiter = delimiter.unwrap_or(" ");
+    let escaped: Vec<Cow<'a, str>> = words.iter().map(|w| escape_token(w, delimiter)).collect();
+    let mut result = Vec::new();
+
+    for &n in n_range {
+        if n == 0 || n > escaped.len() {
+            continue;
+        }
+
+        if n == 1 {
+            result.extend(escaped.iter().cloned());
+        } else {
+            for window in escaped.windows(n) {
+                result.push(Cow::Owned(join_window(window, delimiter)));
+            }
+        }
+    }
+
+    result
+}
+
+/// Splits an n-gram produced by [`generate_ngrams_escaped`] back into its tokens.
+///
+/// Unescaped occurrences of `delimiter` separate tokens, and every escaped character
+/// is restored. Tokens without escapes are borrowed from the input. An empty
+/// delimiter yields the whole input as a single token.
+///
+/// # Panics
+///
+/// Panics if `delimiter` contains [`ESCAPE_CHAR`].
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::split_ngram;
+///
+/// assert_eq!(split_ngram("a-b\\-c", "-"), vec!["a", "b-c"]);
+/// assert_eq!(split_ngram("a\\---b", "--"), vec!["a-", "b"]);
+/// ```
+pub fn split_ngram<'a>(ngram: &'a str, delimiter: &str) -> Vec<Cow<'a, str>> {
+    assert_escapable(delimiter);
+    if delimiter.is_empty() {
+        return vec![Cow::Borrowed(ngram)];
+    }
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut owned: Option<String> = None;
+    let mut i = 0;
+
+    while i < ngram.len() {
+        let rest = &ngram[i..];
+        // Escapes come first, so that an escaped character never starts a delimiter
+        if rest.starts_with(ESCAPE_CHAR) && rest.len() > 1 {
+            let token = owned.get_or_insert_with(|| ngram[start..i].to_string());
+            let escaped = &rest[1..];
+            let skip = escaped.chars().next().map_or(0, char::len_utf8);
+            token.push_str(&escaped[..skip]);
+            i += 1 + skip;
+        } else if rest.starts_with(delimiter) {
+            tokens.push(match owned.take() {
+                Some(token) => Cow::Owned(token),
+                None => Cow::Borrowed(&ngram[start..i]),
+            });
+            i += delimiter.len();
+            start = i;
+        } else {
+            let c_len = rest.chars().next().map_or(1, char::len_utf8);
+            if let Some(token) = owned.as_mut() {
+                token.push_str(&rest[..c_len]);
+            }
+            i += c_len;
+        }
+    }
+
+    tokens.push(match owned {
+        Some(token) => Cow::Owned(token),
+        None => Cow::Borrowed(&ngram[start..]),
+    });
+    tokens
+}
+
 /// An iterator that generates n-grams lazily for memory-efficient processing.
 ///
 /// This iterator produces n-grams on-demand rather than generating all at once,
@@ -123,24 +722,86 @@ pub fn generate_ngrams_owned(words: &[String], n_range: &[usize], delimiter: &st
 /// * `current_n` - Current index in the n_range being processed
 /// * `current_window` - Current starting position for the sliding window
 /// * `delimiter` - Delimiter to use between words
+/// * `reversed` - Whether the tokens of each n-gram are joined last to first
 pub struct NGramIterator<'a> {
     words: &'a [String],
     n_range: &'a [usize],
     current_n: usize,
     current_window: usize,
     delimiter: &'a str,
+    reversed: bool,
 }
 
-impl<'a> Iterator for NGramIterator<'a> {
-    type Item = Cow<'a, str>;
+impl<'a> NGramIterator<'a> {
+    /// Joins the tokens of every n-gram last to first, as [`generate_ngrams_reversed`]
+    /// does.
+    ///
+    /// Spans from [`NGramIterator::with_spans`] still index the tokens in document
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::ngrams_as_iterator;
+    ///
+    /// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    /// let ngrams: Vec<_> = ngrams_as_iterator(&words, &[3], Some("-")).reversed().collect();
+    /// assert_eq!(ngrams, ["c-b-a"]);
+    /// ```
+    pub fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
 
-    /// Returns the next n-gram in the sequence, or None when all n-grams have been generated.
+    /// Tags every n-gram with its order, the number of tokens it spans.
+    ///
+    /// Consumers of a mixed `n_range` get `n` directly instead of counting
+    /// delimiters, which would also miscount tokens containing the delimiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::ngrams_as_iterator;
+    ///
+    /// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    /// let tagged: Vec<(usize, String)> = ngrams_as_iterator(&words, &[1, 3], None)
+    ///     .with_order()
+    ///     .map(|(n, ngram)| (n, ngram.into_owned()))
+    ///     .collect();
+    ///
+    /// assert_eq!(tagged[2], (1, "c".to_string()));
+    /// assert_eq!(tagged[3], (3, "a b c".to_string()));
+    /// ```
+    pub fn with_order(self) -> OrderedNGramIterator<'a> {
+        OrderedNGramIterator { inner: self }
+    }
+
+    /// Tags every n-gram with the range of token indices it covers.
+    ///
+    /// The spans align n-grams back to token positions, for span labeling or
+    /// highlighting, without a second pass over the words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::ngrams_as_iterator;
+    ///
+    /// let words = vec!["the".to_string(), "quick".to_string(), "fox".to_string()];
+    /// let mut iter = ngrams_as_iterator(&words, &[2], None).with_spans();
+    ///
+    /// let (span, ngram) = iter.next().unwrap();
+    /// assert_eq!((span, ngram.as_ref()), (0..2, "the quick"));
+    /// assert_eq!(iter.next().unwrap().0, 1..3);
+    /// ```
+    pub fn with_spans(self) -> SpannedNGramIterator<'a> {
+        SpannedNGramIterator { inner: self }
+    }
+
+    /// Advances the state machine and returns the token span of the next n-gram.
     ///
-    /// This implementation uses a state machine that:
     /// 1. Iterates through each n-value in n_range
     /// 2. For each n-value, slides a window through the words
-    /// 3. Returns borrowed strings for unigrams, owned strings for higher n-grams
-    fn next(&mut self) -> Option<Self::Item> {
+    fn next_span(&mut self) -> Option<Range<usize>> {
         while self.current_n < self.n_range.len() {
             let n = self.n_range[self.current_n];
 
@@ -153,14 +814,9 @@ impl<'a> Iterator for NGramIterator<'a> {
 
             // Check if we have more windows to process for current n-value
             if self.current_window + n <= self.words.len() {
-                let window = &self.words[self.current_window..self.current_window + n];
+                let start = self.current_window;
                 self.current_window += 1;
-
-                return if n == 1 {
-                    Some(Cow::Borrowed(window[0].as_str()))
-                } else {
-                    Some(Cow::Owned(window.join(self.delimiter)))
-                };
+                return Some(start..start + n);
             } else {
                 self.current_n += 1;
                 self.current_window = 0;
@@ -169,6 +825,56 @@ impl<'a> Iterator for NGramIterator<'a> {
 
         None
     }
+
+    /// Returns borrowed strings for unigrams, owned strings for higher n-grams.
+    fn ngram(&self, span: Range<usize>) -> Cow<'a, str> {
+        let words: &'a [String] = self.words;
+        match &words[span] {
+            [word] => Cow::Borrowed(word.as_str()),
+            window if self.reversed => Cow::Owned(join_window_reversed(window, self.delimiter)),
+            window => Cow::Owned(join_window(window, self.delimiter)),
+        }
+    }
+}
+
+impl<'a> Iterator for NGramIterator<'a> {
+    type Item = Cow<'a, str>;
+
+    /// Returns the next n-gram in the sequence, or None when all n-grams have been generated.
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.next_span()?;
+        Some(self.ngram(span))
+    }
+}
+
+/// An [`NGramIterator`] yielding `(n, ngram)` pairs, created by
+/// [`NGramIterator::with_order`].
+pub struct OrderedNGramIterator<'a> {
+    inner: NGramIterator<'a>,
+}
+
+impl<'a> Iterator for OrderedNGramIterator<'a> {
+    type Item = (usize, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.inner.next_span()?;
+        Some((span.len(), self.inner.ngram(span)))
+    }
+}
+
+/// An [`NGramIterator`] yielding `(span, ngram)` pairs, where `span` is the range of
+/// token indices covered, created by [`NGramIterator::with_spans`].
+pub struct SpannedNGramIterator<'a> {
+    inner: NGramIterator<'a>,
+}
+
+impl<'a> Iterator for SpannedNGramIterator<'a> {
+    type Item = (Range<usize>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self.inner.next_span()?;
+        Some((span.clone(), self.inner.ngram(span)))
+    }
 }
 
 /// Creates an iterator that generates n-grams lazily.
@@ -211,6 +917,74 @@ pub fn ngrams_as_iterator<'a>(
         current_n: 0,
         current_window: 0,
         delimiter: delimiter.unwrap_or(" "),
+        reversed: false,
+    }
+}
+
+/// An iterator that owns its input and yields n-grams as owned `String`s.
+///
+/// Unlike [`NGramIterator`] it borrows nothing, so it can be moved into another thread,
+/// a task or a `'static` adapter. Created by [`ngrams_into_iter`].
+#[derive(Debug, Clone)]
+pub struct NGramIntoIter {
+    words: Vec<String>,
+    n_range: Vec<usize>,
+    delimiter: String,
+    current_n: usize,
+    current_window: usize,
+}
+
+impl Iterator for NGramIntoIter {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&n) = self.n_range.get(self.current_n) {
+            if n != 0 && self.current_window + n <= self.words.len() {
+                let window = &self.words[self.current_window..self.current_window + n];
+                self.current_window += 1;
+                return Some(join_window(window, &self.delimiter));
+            }
+            self.current_n += 1;
+            self.current_window = 0;
+        }
+        None
+    }
+}
+
+/// Creates an iterator that takes ownership of its input and generates n-grams lazily.
+///
+/// # Arguments
+///
+/// * `words` - The input words, moved into the iterator
+/// * `n_range` - The n-gram sizes to generate
+/// * `delimiter` - Optional delimiter string (defaults to space)
+///
+/// # Returns
+///
+/// An `NGramIntoIter` that yields n-grams as `String` values
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::ngrams_into_iter;
+///
+/// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let iter = ngrams_into_iter(words, vec![2], Some("-"));
+///
+/// let handle = std::thread::spawn(move || iter.collect::<Vec<_>>());
+/// assert_eq!(handle.join().unwrap(), ["a-b", "b-c"]);
+/// ```
+pub fn ngrams_into_iter(
+    words: Vec<String>,
+    n_range: Vec<usize>,
+    delimiter: Option<&str>,
+) -> NGramIntoIter {
+    NGramIntoIter {
+        words,
+        n_range,
+        delimiter: delimiter.unwrap_or(" ").to_string(),
+        current_n: 0,
+        current_window: 0,
     }
 }
 
@@ -287,6 +1061,75 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    /// Tests that order tags survive delimiters inside tokens and skipped n-values
+    #[test]
+    fn test_ngram_iterator_with_order() {
+        let words = vec!["New York".to_string(), "is".to_string(), "big".to_string()];
+        let tagged: Vec<(usize, Cow<str>)> = ngrams_as_iterator(&words, &[2, 0, 4, 1], None)
+            .with_order()
+            .collect();
+
+        let orders: Vec<usize> = tagged.iter().map(|(n, _)| *n).collect();
+        assert_eq!(orders, [2, 2, 1, 1, 1]);
+        assert_eq!(tagged[0], (2, Cow::Owned("New York is".to_string())));
+        assert_eq!(tagged[2], (1, Cow::Borrowed("New York")));
+    }
+
+    /// Tests that spans index the tokens each n-gram was built from
+    #[test]
+    fn test_ngram_iterator_with_spans() {
+        let words: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        for (span, ngram) in ngrams_as_iterator(&words, &[1, 2, 3], Some("_")).with_spans() {
+            assert_eq!(ngram, words[span].join("_"));
+        }
+
+        let spans: Vec<Range<usize>> = ngrams_as_iterator(&words, &[3, 1], None)
+            .with_spans()
+            .map(|(span, _)| span)
+            .collect();
+        assert_eq!(spans, [0..3, 0..1, 1..2, 2..3]);
+    }
+
+    /// Tests that reversed generation, iteration and counting agree
+    #[test]
+    fn test_reversed_ngrams() {
+        let words: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        let ngrams = generate_ngrams_reversed(&words, &[1, 3, 2], Some("+"));
+        assert_eq!(ngrams, ["a", "b", "c", "c+b+a", "b+a", "c+b"]);
+
+        let iterated: Vec<(Range<usize>, Cow<str>)> =
+            ngrams_as_iterator(&words, &[1, 3, 2], Some("+"))
+                .reversed()
+                .with_spans()
+                .collect();
+        assert_eq!(
+            iterated
+                .iter()
+                .map(|(_, ngram)| ngram.clone())
+                .collect::<Vec<_>>(),
+            ngrams
+        );
+        assert_eq!(iterated[3].0, 0..3);
+
+        #[cfg(feature = "std")]
+        {
+            let mut counter = NGramCounter::new();
+            counter.add_document_reversed(&words, &[2], Some("+"));
+            assert_eq!(counter.get("b+a"), 1);
+            assert_eq!(counter.get_order("c+b", 2), 1);
+        }
+    }
+
+    /// Tests that the owning iterator matches the eager generator
+    #[test]
+    fn test_ngrams_into_iter() {
+        let words: Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let expected = generate_ngrams_owned(&words, &[3, 0, 1, 9], "+");
+
+        let iter = ngrams_into_iter(words, vec![3, 0, 1, 9], Some("+"));
+        assert_eq!(iter.collect::<Vec<_>>(), expected);
+    }
+
     /// Tests the owned strings version
     #[test]
     fn test_owned_version() {
@@ -295,4 +1138,117 @@ mod tests {
 
         assert_eq!(result, vec!["alpha+beta".to_string()]);
     }
+
+    /// Tests that the output buffer is reused and truncated between documents
+    #[test]
+    fn test_write_ngrams_into() {
+        let first = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let second = vec!["d".to_string(), "e".to_string()];
+        let mut out = Vec::new();
+
+        assert_eq!(write_ngrams_into(&first, &[1, 2], Some("-"), &mut out), 5);
+        assert_eq!(out, vec!["a", "b", "c", "a-b", "b-c"]);
+
+        assert_eq!(write_ngrams_into(&second, &[2], Some("-"), &mut out), 1);
+        assert_eq!(out, vec!["d-e"]);
+    }
+
+    /// Tests that counts accumulate across documents
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_count_ngrams_into() {
+        let mut counts = HashMap::new();
+        let doc = vec!["to".to_string(), "be".to_string(), "to".to_string()];
+
+        count_ngrams_into(&doc, &[1, 2], None, &mut counts);
+        count_ngrams_into(&doc, &[1], None, &mut counts);
+
+        assert_eq!(counts["to"], 4);
+        assert_eq!(counts["be"], 2);
+        assert_eq!(counts["to be"], 1);
+        assert_eq!(counts.len(), 4);
+    }
+
+    /// Tests the packed buffer layout and its exact pre-sizing
+    #[test]
+    fn test_packed_output() {
+        let words = vec!["ab".to_string(), "c".to_string(), "def".to_string()];
+        let (buffer, ranges) = generate_ngrams_packed(&words, &[1, 3], Some("--"));
+
+        assert_eq!(buffer.len(), buffer.capacity());
+        let ngrams: Vec<&str> = ranges.iter().map(|r| &buffer[r.clone()]).collect();
+        assert_eq!(ngrams, generate_ngrams_owned(&words, &[1, 3], "--"));
+    }
+
+    /// Tests that the result vector is sized exactly
+    #[test]
+    fn test_exact_capacity() {
+        let words: Vec<String> = "a b c d e".split(' ').map(String::from).collect();
+        let result = generate_ngrams(&words, &[0, 1, 3, 9], None);
+
+        assert_eq!(result.len(), ngram_count(words.len(), &[0, 1, 3, 9]));
+        assert_eq!(result.len(), result.capacity());
+        assert_eq!(result[5], "a b c");
+    }
+
+    /// Tests that escaped n-grams split back into the original tokens
+    #[test]
+    fn test_escaped_round_trip() {
+        let words = vec!["New York".to_string(), "a\\b".to_string(), "c".to_string()];
+
+        let result = generate_ngrams_escaped(&words, &[1, 3], None);
+        assert_eq!(result[3], "New\\ York a\\\\b c");
+
+        for ngram in &result[..3] {
+            assert_eq!(split_ngram(ngram, " ").len(), 1);
+        }
+        assert_eq!(split_ngram(&result[3], " "), vec!["New York", "a\\b", "c"]);
+    }
+
+    /// Tests escaping with a multi-character delimiter
+    #[test]
+    fn test_escaped_multichar_delimiter() {
+        let words = vec!["x | y".to_string(), "z".to_string()];
+
+        let result = generate_ngrams_escaped(&words, &[2], Some(" | "));
+        assert_eq!(result, vec!["x\\ \\|\\ y | z"]);
+        assert_eq!(split_ngram(&result[0], " | "), vec!["x | y", "z"]);
+    }
+
+    /// Tests that every short token sequence over delimiter characters, escape
+    /// characters and multi-byte characters splits back into itself
+    #[test]
+    fn test_escaped_round_trip_exhaustive() {
+        let alphabet = ["a", "-", "\\", "|", " ", "é"];
+        let mut tokens = vec![String::new()];
+        for _ in 0..3 {
+            let longer: Vec<String> = tokens
+                .iter()
+                .flat_map(|token| alphabet.iter().map(move |c| format!("{token}{c}")))
+                .collect();
+            tokens.extend(longer);
+        }
+        tokens.sort();
+        tokens.dedup();
+        let short: Vec<&String> = tokens.iter().filter(|t| t.chars().count() <= 1).collect();
+
+        for delimiter in ["-", "--", " | ", "-a", "é", "a-a"] {
+            for a in &tokens {
+                for b in &tokens {
+                    let words = [a.clone(), b.clone()];
+                    let ngram = &generate_ngrams_escaped(&words, &[2], Some(delimiter))[0];
+                    assert_eq!(split_ngram(ngram, delimiter), words, "{delimiter:?}");
+                }
+            }
+            for a in &short {
+                for b in &short {
+                    for c in &short {
+                        let words = [a.to_string(), b.to_string(), c.to_string()];
+                        let ngram = &generate_ngrams_escaped(&words, &[3], Some(delimiter))[0];
+                        assert_eq!(split_ngram(ngram, delimiter), words, "{delimiter:?}");
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,1567 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use crate::{CancelToken, Cancelled};
+
+/// Padding token placed before the first word of a sentence
+const BOS: &str = "<s>";
+/// Token predicted after the last word of a sentence
+pub(crate) const EOS: &str = "</s>";
+
+const MAGIC: &[u8; 4] = b"NGLM";
+const VERSION: u32 = 1;
+/// The most entries reserved ahead of reading a table of a saved model.
+const MAX_RESERVED: usize = 1 << 16;
+
+/// Smoothing method used by a [`LanguageModel`] to give unseen n-grams probability mass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Smoothing {
+    /// Add-one smoothing of the highest-order counts.
+    Laplace,
+    /// Interpolated Kneser-Ney, with one discount per order estimated from the
+    /// counts of counts.
+    KneserNey,
+}
+
+/// Perplexities of a batch of sentences, computed by
+/// [`LanguageModel::perplexity_batch`].
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchPerplexity {
+    /// The perplexity of each sentence, in input order
+    pub sentences: Vec<f64>,
+    /// The per-token perplexity of the whole batch, as [`LanguageModel::perplexity`]
+    /// computes it
+    pub corpus: f64,
+}
+
+/// How a [`LanguageModel`] scored one token, returned by [`LanguageModel::trace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenTrace {
+    /// The predicted token, `</s>` for the end of the sentence
+    pub token: String,
+    /// The natural log-probability of the token given its context
+    pub log_prob: f64,
+    /// The size of the longest n-gram ending at the token that was seen in training:
+    /// the model order when the full context was seen, lower when the model had to
+    /// back off, and 0 for an unknown word
+    pub order: usize,
+}
+
+/// A word n-gram language model.
+///
+/// Sentences are padded with `order - 1` `<s>` tokens and terminated by `</s>`,
+/// which is predicted like any other word. Words never seen in training share the
+/// probability of a single unknown word, so every query returns a non-zero
+/// probability.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{LanguageModel, Smoothing};
+///
+/// let corpus = [vec!["the", "cat", "sat"], vec!["the", "dog", "sat"]];
+/// let model = LanguageModel::train(3, Smoothing::KneserNey, &corpus);
+///
+/// assert!(model.prob(&["the"], "cat") > model.prob(&["the"], "sat"));
+/// assert!(model.perplexity(&[vec!["the", "cat", "sat"]]) < 3.0);
+/// ```
+///
+/// With the `serde` feature, a model serializes its order, smoothing and counts; the
+/// smoothing statistics are left out and rebuilt on the first query after
+/// deserializing.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LanguageModel {
+    order: usize,
+    smoothing: Smoothing,
+    /// `counts[k - 1]` holds the raw counts of k-grams
+    #[cfg_attr(feature = "serde", serde(with = "count_tables"))]
+    counts: Vec<HashMap<Vec<String>, usize>>,
+    /// Smoothing statistics derived from the counts, computed on the first query
+    /// after training, an update or deserializing
+    #[cfg_attr(feature = "serde", serde(skip))]
+    estimates: OnceLock<Estimates>,
+}
+
+/// Serializes the count tables as lists of `(gram, count)` pairs, since formats such
+/// as JSON only allow string map keys.
+#[cfg(feature = "serde")]
+mod count_tables {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    type Table = HashMap<Vec<String>, usize>;
+
+    pub(super) fn serialize<S: Serializer>(
+        counts: &[Table],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let tables: Vec<Vec<(&Vec<String>, &usize)>> =
+            counts.iter().map(|table| table.iter().collect()).collect();
+        tables.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Table>, D::Error> {
+        let tables: Vec<Vec<(Vec<String>, usize)>> = Vec::deserialize(deserializer)?;
+        Ok(tables
+            .into_iter()
+            .map(|table| table.into_iter().collect())
+            .collect())
+    }
+}
+
+/// The statistics a [`LanguageModel`] derives from its raw counts.
+#[derive(Debug, Clone)]
+struct Estimates {
+    /// `continuations[k - 1]` holds, for each k-gram below the highest order, the
+    /// number of distinct words seen before it
+    continuations: Vec<HashMap<Vec<String>, usize>>,
+    /// `contexts[k - 1]` maps each (k - 1)-token context to the sum of the k-gram
+    /// counts that extend it and the number of distinct words that follow it
+    contexts: Vec<HashMap<Vec<String>, (usize, usize)>>,
+    discounts: Vec<f64>,
+}
+
+/// Models are equal when they have the same order, smoothing and counts, whether or
+/// not their smoothing statistics have been computed yet.
+impl PartialEq for LanguageModel {
+    fn eq(&self, other: &Self) -> bool {
+        self.order == other.order
+            && self.smoothing == other.smoothing
+            && self.counts == other.counts
+    }
+}
+
+impl LanguageModel {
+    /// Trains a model of the given order on tokenized sentences.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The largest n-gram size, e.g. 3 for a trigram model
+    /// * `smoothing` - How probability mass is reserved for unseen n-grams
+    /// * `sentences` - The training sentences, each a sequence of tokens
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is 0.
+    pub fn train<I, S, T>(order: usize, smoothing: Smoothing, sentences: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        let mut model = LanguageModel::empty(order, smoothing);
+        model.update(sentences);
+        model
+    }
+
+    /// Trains a model like [`LanguageModel::train`], checking `cancel` before every
+    /// sentence.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Cancelled`] as soon as the token is cancelled; the partly trained
+    /// model is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is 0.
+    pub fn train_cancellable<I, S, T>(
+        order: usize,
+        smoothing: Smoothing,
+        sentences: I,
+        cancel: &CancelToken,
+    ) -> Result<Self, Cancelled>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        let mut model = LanguageModel::empty(order, smoothing);
+        for sentence in sentences {
+            cancel.check()?;
+            model.count_sentence(sentence.as_ref());
+        }
+        Ok(model)
+    }
+
+    /// Creates a model with no counts.
+    fn empty(order: usize, smoothing: Smoothing) -> Self {
+        assert!(order > 0, "a language model needs an order of at least 1");
+        LanguageModel {
+            order,
+            smoothing,
+            counts: vec![HashMap::new(); order],
+            estimates: OnceLock::new(),
+        }
+    }
+
+    /// Adds more training sentences to the model, without retraining on the earlier
+    /// ones.
+    ///
+    /// The new sentences are counted right away; the smoothing statistics, which
+    /// depend on all counts, are recomputed once on the next query. A long-running
+    /// service can therefore learn from a stream of small batches and pay for the
+    /// recomputation only when it actually scores text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let mut model = LanguageModel::train(2, Smoothing::KneserNey, [["the", "cat", "sat"]]);
+    /// let before = model.prob(&["the"], "dog");
+    /// model.update([["the", "dog", "sat"]]);
+    ///
+    /// assert!(model.prob(&["the"], "dog") > before);
+    /// assert_eq!(
+    ///     model,
+    ///     LanguageModel::train(2, Smoothing::KneserNey, [["the", "cat", "sat"], ["the", "dog", "sat"]])
+    /// );
+    /// ```
+    pub fn update<I, S, T>(&mut self, sentences: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        let mut counted = false;
+        for sentence in sentences {
+            self.count_sentence(sentence.as_ref());
+            counted = true;
+        }
+        if counted {
+            self.estimates.take();
+        }
+    }
+
+    /// Returns the largest n-gram size of the model.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the smoothing method of the model.
+    pub fn smoothing(&self) -> Smoothing {
+        self.smoothing
+    }
+
+    /// Returns the number of words the model predicts: every training word, `</s>`
+    /// and the unknown word.
+    pub fn vocab_size(&self) -> usize {
+        self.counts[0].len() + 1
+    }
+
+    /// Returns the probability of `word` following `context`.
+    ///
+    /// Only the last `order - 1` words of `context` are used; a shorter context is
+    /// treated as the start of a sentence.
+    pub fn prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        self.window_prob(&self.window(context, word))
+    }
+
+    /// Returns the distribution of the word following `context`, most probable first.
+    ///
+    /// Every training word and `</s>` is listed, ties in order of the words, but not
+    /// the unknown word, so the probabilities sum to slightly less than 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, [["a", "b"], ["a", "b"], ["a", "c"]]);
+    /// let next = model.distribution(&["a"]);
+    ///
+    /// assert_eq!(next.iter().map(|&(word, _)| word).collect::<Vec<_>>(), ["b", "c", "</s>", "a"]);
+    /// assert_eq!(next[0].1, model.prob(&["a"], "b"));
+    /// ```
+    pub fn distribution<S: AsRef<str>>(&self, context: &[S]) -> Vec<(&str, f64)> {
+        let mut distribution = self.word_probs(self.window(context, ""));
+        distribution.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        distribution
+    }
+
+    /// Finds the most probable continuations of `context` with beam search.
+    ///
+    /// At each step every hypothesis is extended by its `beam_width` most probable
+    /// next words and only the `beam_width` best extensions are kept. A hypothesis
+    /// ends when `</s>` is chosen, which is scored but not returned, or when it
+    /// reaches `max_len` words, in which case it is returned unfinished. Scores are
+    /// natural log-probabilities with no length normalization, so shorter sequences
+    /// are favored.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The words the text continues; empty to start a sentence
+    /// * `beam_width` - How many hypotheses are kept, and returned, at most
+    /// * `max_len` - The largest number of words generated
+    ///
+    /// # Returns
+    ///
+    /// Up to `beam_width` `(words, log-probability)` pairs, best first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let corpus = [["the", "cat", "sat"], ["the", "cat", "sat"], ["the", "dog", "ran"]];
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, corpus);
+    /// let beams = model.generate_beam(&["the"], 3, 5);
+    ///
+    /// assert_eq!(beams.len(), 3);
+    /// assert_eq!(beams[0].0, ["cat", "sat"]);
+    /// assert!(beams[0].1 >= beams[1].1);
+    /// ```
+    pub fn generate_beam<S: AsRef<str>>(
+        &self,
+        context: &[S],
+        beam_width: usize,
+        max_len: usize,
+    ) -> Vec<(Vec<String>, f64)> {
+        self.generate_constrained(context, &[] as &[&[&str]], beam_width, max_len)
+    }
+
+    /// Finds the most probable continuations of `context` that contain every one of
+    /// the required `phrases`, by lexically constrained beam search.
+    ///
+    /// Hypotheses are grouped by how many phrase tokens they already cover and each
+    /// group keeps its own `beam_width` best, so hypotheses working on a phrase are not
+    /// crowded out by likelier ones that ignore it. Besides its most probable next
+    /// words, every hypothesis is also extended by the next token of each phrase it
+    /// has yet to complete. `</s>` is only allowed once all phrases appear, and
+    /// hypotheses reaching `max_len` words are only returned if they contain all
+    /// phrases. Phrase words never seen in training are scored as the unknown word.
+    /// Without phrases this is [`LanguageModel::generate_beam`].
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The words the text continues; empty to start a sentence
+    /// * `phrases` - The token sequences the generated words must contain; empty ones
+    ///   are ignored
+    /// * `beam_width` - How many hypotheses are kept per group, and returned, at most
+    /// * `max_len` - The largest number of words generated
+    ///
+    /// # Returns
+    ///
+    /// Up to `beam_width` `(words, log-probability)` pairs, best first; fewer, or none,
+    /// when the phrases do not fit in `max_len` words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let corpus = [
+    ///     vec!["the", "cat", "sat", "on", "the", "mat"],
+    ///     vec!["the", "dog", "sat", "on", "the", "log"],
+    ///     vec!["the", "cat", "sat"],
+    /// ];
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, &corpus);
+    ///
+    /// let best = &model.generate_constrained(&[] as &[&str], &[["the", "log"]], 4, 8)[0];
+    /// assert!(best.0.windows(2).any(|pair| pair == ["the", "log"]));
+    /// ```
+    pub fn generate_constrained<S, P, T>(
+        &self,
+        context: &[S],
+        phrases: &[P],
+        beam_width: usize,
+        max_len: usize,
+    ) -> Vec<(Vec<String>, f64)>
+    where
+        S: AsRef<str>,
+        P: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        let context: Vec<&str> = context.iter().map(AsRef::as_ref).collect();
+        let phrases: Vec<Vec<&str>> = phrases
+            .iter()
+            .map(|phrase| phrase.as_ref().iter().map(AsRef::as_ref).collect())
+            .filter(|phrase: &Vec<&str>| !phrase.is_empty())
+            .collect();
+        let required: usize = phrases.iter().map(Vec::len).sum();
+        let by_score = |a: &(Vec<String>, f64), b: &(Vec<String>, f64)| {
+            b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0))
+        };
+
+        let mut beams: Vec<(Vec<String>, f64)> = vec![(Vec::new(), 0.0)];
+        let mut finished: Vec<(Vec<String>, f64)> = Vec::new();
+        if beam_width == 0 {
+            return finished;
+        }
+
+        for _ in 0..max_len {
+            // (words, score, ended with `</s>`, phrase tokens covered)
+            let mut candidates: Vec<(Vec<String>, f64, bool, usize)> = Vec::new();
+            for (words, score) in &beams {
+                let history: Vec<&str> = context
+                    .iter()
+                    .copied()
+                    .chain(words.iter().map(String::as_str))
+                    .collect();
+                let tail = &history[history.len().saturating_sub(self.order - 1)..];
+                let (_, forced) = phrase_progress(words, &phrases);
+                let mut options: Vec<(&str, f64)> = self.distribution(tail);
+                options.truncate(beam_width);
+                for &word in &forced {
+                    if !options.iter().any(|&(option, _)| option == word) {
+                        options.push((word, self.prob(tail, word)));
+                    }
+                }
+
+                for (word, prob) in options {
+                    let score = score + prob.ln();
+                    if word == EOS {
+                        if forced.is_empty() {
+                            candidates.push((words.clone(), score, true, required));
+                        }
+                        continue;
+                    }
+                    let mut extended = words.clone();
+                    extended.push(word.to_string());
+                    let (covered, _) = phrase_progress(&extended, &phrases);
+                    candidates.push((extended, score, false, covered));
+                }
+            }
+            candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+            beams.clear();
+            let mut kept = vec![0; required + 1];
+            for (words, score, ended, covered) in candidates {
+                if kept[covered] == beam_width {
+                    continue;
+                }
+                kept[covered] += 1;
+                if ended {
+                    finished.push((words, score));
+                } else {
+                    beams.push((words, score));
+                }
+            }
+            // Scores only decrease, so hypotheses behind enough finished ones are done
+            finished.sort_by(by_score);
+            let best_active = beams.first().map_or(f64::NEG_INFINITY, |beam| beam.1);
+            if finished.len() >= beam_width && finished[beam_width - 1].1 >= best_active {
+                beams.clear();
+            }
+            if beams.is_empty() {
+                break;
+            }
+        }
+
+        finished.extend(
+            beams
+                .into_iter()
+                .filter(|(words, _)| phrase_progress(words, &phrases).1.is_empty()),
+        );
+        finished.sort_by(by_score);
+        finished.truncate(beam_width);
+        finished
+    }
+
+    /// Returns the natural logarithm of [`LanguageModel::prob`].
+    pub fn log_prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        self.prob(context, word).ln()
+    }
+
+    /// Returns the natural log-probability of a whole sentence, including its `</s>`.
+    pub fn sentence_log_prob<S: AsRef<str>>(&self, sentence: &[S]) -> f64 {
+        let padded = self.pad(sentence);
+        (self.order - 1..padded.len())
+            .map(|i| self.window_prob(&padded[i + 1 - self.order..=i]).ln())
+            .sum()
+    }
+
+    /// Scores a sentence token by token, including its `</s>`, to show where the model
+    /// is weak.
+    ///
+    /// The log-probabilities add up to [`LanguageModel::sentence_log_prob`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let model = LanguageModel::train(3, Smoothing::KneserNey, [["the", "cat", "sat"]]);
+    /// let trace = model.trace(&["the", "cat", "ran"]);
+    ///
+    /// let orders: Vec<usize> = trace.iter().map(|t| t.order).collect();
+    /// assert_eq!(orders, [3, 3, 0, 1]);
+    /// assert_eq!(trace[3].token, "</s>");
+    /// assert!(trace[2].log_prob < trace[1].log_prob);
+    /// ```
+    pub fn trace<S: AsRef<str>>(&self, sentence: &[S]) -> Vec<TokenTrace> {
+        let padded = self.pad(sentence);
+        (self.order - 1..padded.len())
+            .map(|i| {
+                let window = &padded[i + 1 - self.order..=i];
+                let order = (1..=self.order)
+                    .rev()
+                    .find(|&k| self.counts[k - 1].contains_key(&window[self.order - k..]))
+                    .unwrap_or(0);
+                TokenTrace {
+                    token: padded[i].clone(),
+                    log_prob: self.window_prob(window).ln(),
+                    order,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the per-token perplexity of the model on tokenized sentences.
+    ///
+    /// Every word and the `</s>` of every sentence count as one token. Returns `1.0`
+    /// when there is nothing to score.
+    pub fn perplexity<I, S, T>(&self, sentences: I) -> f64
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        corpus_perplexity(sentences, |sentence| self.sentence_log_prob(sentence))
+    }
+
+    /// Combines models into a mixture that scores each token with the weighted sum of
+    /// their probabilities, e.g. to back a small domain model with a general one.
+    ///
+    /// Weights are normalized to sum to 1 and can later be tuned on held-out text
+    /// with [`InterpolatedModel::fit_weights`]. The models may differ in order and
+    /// smoothing; the mixture is a proper distribution when they share a vocabulary,
+    /// since each model gives its own unknown-word probability to words it never saw.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The models with their relative weights
+    ///
+    /// # Panics
+    ///
+    /// Panics if `components` is empty, or if a weight is negative or not finite, or
+    /// if all weights are 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let general = LanguageModel::train(2, Smoothing::KneserNey, [["the", "cat", "sat"]]);
+    /// let medical = LanguageModel::train(2, Smoothing::KneserNey, [["the", "patient", "sat"]]);
+    /// let mixture = LanguageModel::interpolate(&[(&general, 3.0), (&medical, 1.0)]);
+    ///
+    /// assert_eq!(mixture.weights().collect::<Vec<_>>(), [0.75, 0.25]);
+    /// let expected = 0.75 * general.prob(&["the"], "patient") + 0.25 * medical.prob(&["the"], "patient");
+    /// assert!((mixture.prob(&["the"], "patient") - expected).abs() < 1e-12);
+    /// ```
+    pub fn interpolate<'a>(components: &[(&'a LanguageModel, f64)]) -> InterpolatedModel<'a> {
+        assert!(
+            !components.is_empty(),
+            "an interpolated model needs at least one component"
+        );
+        assert!(
+            components
+                .iter()
+                .all(|&(_, weight)| weight.is_finite() && weight >= 0.0),
+            "interpolation weights must be finite and non-negative"
+        );
+        let total: f64 = components.iter().map(|&(_, weight)| weight).sum();
+        assert!(total > 0.0, "interpolation weights must not all be 0");
+        InterpolatedModel {
+            components: components
+                .iter()
+                .map(|&(model, weight)| (model, weight / total))
+                .collect(),
+        }
+    }
+
+    /// Puts a cache of next-word distributions in front of the model.
+    ///
+    /// The first query after a context scores the whole vocabulary once; later queries
+    /// after the same context are answered from the cache until it is evicted. This
+    /// pays off when the same frequent contexts come up again and again, as in most
+    /// scoring workloads.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - How many contexts are kept, least recently used ones being
+    ///   evicted first
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, [["the", "cat"], ["the", "dog"]]);
+    /// let cached = model.with_cache(1024);
+    ///
+    /// assert_eq!(cached.prob(&["the"], "cat"), model.prob(&["the"], "cat"));
+    /// assert_eq!(cached.prob(&["the"], "dog"), model.prob(&["the"], "dog"));
+    /// assert_eq!((cached.hits(), cached.misses()), (1, 1));
+    /// ```
+    pub fn with_cache(&self, capacity: usize) -> CachedModel<'_> {
+        assert!(capacity > 0, "a query cache needs a capacity of at least 1");
+        CachedModel {
+            model: self,
+            capacity,
+            cache: Mutex::new(QueryCache::default()),
+        }
+    }
+
+    /// Scores many sentences in parallel on the rayon thread pool.
+    ///
+    /// Each sentence perplexity counts its words and its `</s>`, like the corpus
+    /// perplexity, which is `1.0` for an empty batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, [["a", "b"], ["b", "a"]]);
+    /// let heldout = vec![vec!["a", "b"], vec!["b", "b", "a"]];
+    /// let batch = model.perplexity_batch(&heldout);
+    ///
+    /// assert_eq!(batch.sentences.len(), 2);
+    /// assert!((batch.corpus - model.perplexity(&heldout)).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn perplexity_batch<S, T>(&self, sentences: &[S]) -> BatchPerplexity
+    where
+        S: AsRef<[T]> + Sync,
+        T: AsRef<str>,
+    {
+        use rayon::prelude::*;
+
+        let log_probs: Vec<f64> = sentences
+            .par_iter()
+            .map(|sentence| self.sentence_log_prob(sentence.as_ref()))
+            .collect();
+        let mut tokens = 0;
+        let per_sentence = sentences
+            .iter()
+            .zip(&log_probs)
+            .map(|(sentence, log_prob)| {
+                let len = sentence.as_ref().len() + 1;
+                tokens += len;
+                (-log_prob / len as f64).exp()
+            })
+            .collect();
+        let corpus = if tokens == 0 {
+            1.0
+        } else {
+            (-log_probs.iter().sum::<f64>() / tokens as f64).exp()
+        };
+        BatchPerplexity {
+            sentences: per_sentence,
+            corpus,
+        }
+    }
+
+    /// Writes the model in a compact binary format read by [`LanguageModel::read_from`].
+    ///
+    /// Only the raw counts are stored; smoothing statistics are recomputed after loading.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        write_u64(writer, self.order as u64)?;
+        writer.write_all(&[match self.smoothing {
+            Smoothing::Laplace => 0,
+            Smoothing::KneserNey => 1,
+        }])?;
+        for counts in &self.counts {
+            write_u64(writer, counts.len() as u64)?;
+            for (gram, &count) in counts {
+                for token in gram {
+                    write_u64(writer, token.len() as u64)?;
+                    writer.write_all(token.as_bytes())?;
+                }
+                write_u64(writer, count as u64)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a model written by [`LanguageModel::write_to`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let model = LanguageModel::train(2, Smoothing::Laplace, [["a", "b"], ["b", "a"]]);
+    /// let mut bytes = Vec::new();
+    /// model.write_to(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(LanguageModel::read_from(&mut bytes.as_slice()).unwrap(), model);
+    /// ```
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        if &magic != MAGIC || u32::from_le_bytes(version) != VERSION {
+            return Err(invalid_data("not an ngram_rs language model"));
+        }
+        let order = read_len(reader)?;
+        if order == 0 {
+            return Err(invalid_data("language model order is 0"));
+        }
+        let mut smoothing = [0; 1];
+        reader.read_exact(&mut smoothing)?;
+        let smoothing = match smoothing[0] {
+            0 => Smoothing::Laplace,
+            1 => Smoothing::KneserNey,
+            _ => return Err(invalid_data("unknown smoothing method")),
+        };
+
+        // The order and table sizes come from the file, so containers only reserve
+        // a bounded amount up front and grow as entries are actually read
+        let mut counts = Vec::with_capacity(order.min(MAX_RESERVED));
+        for k in 1..=order {
+            let entries = read_len(reader)?;
+            let mut table = HashMap::with_capacity(entries.min(MAX_RESERVED));
+            for _ in 0..entries {
+                let gram = (0..k)
+                    .map(|_| read_string(reader))
+                    .collect::<io::Result<Vec<String>>>()?;
+                table.insert(gram, read_len(reader)?);
+            }
+            counts.push(table);
+        }
+
+        Ok(LanguageModel {
+            order,
+            smoothing,
+            counts,
+            estimates: OnceLock::new(),
+        })
+    }
+
+    /// Builds the window scoring `word` after the last `order - 1` words of `context`,
+    /// padded with `<s>` at the start of a sentence.
+    fn window<S: AsRef<str>>(&self, context: &[S], word: &str) -> Vec<String> {
+        let history = self.order - 1;
+        let mut window: Vec<String> = Vec::with_capacity(self.order);
+        window.extend((context.len()..history).map(|_| BOS.to_string()));
+        let start = context.len().saturating_sub(history);
+        window.extend(context[start..].iter().map(|w| w.as_ref().to_string()));
+        window.push(word.to_string());
+        window
+    }
+
+    /// Scores every training word and `</s>` in the last position of `window`.
+    fn word_probs(&self, mut window: Vec<String>) -> Vec<(&str, f64)> {
+        let last = self.order - 1;
+        self.counts[0]
+            .keys()
+            .map(|gram| {
+                window[last].clone_from(&gram[0]);
+                (gram[0].as_str(), self.window_prob(&window))
+            })
+            .collect()
+    }
+
+    /// Pads a sentence with `order - 1` `<s>` tokens and a final `</s>`.
+    fn pad<S: AsRef<str>>(&self, sentence: &[S]) -> Vec<String> {
+        let mut padded = vec![BOS.to_string(); self.order - 1];
+        padded.extend(sentence.iter().map(|w| w.as_ref().to_string()));
+        padded.push(EOS.to_string());
+        padded
+    }
+
+    /// Counts, for every predicted token, the k-grams of each order ending at it.
+    fn count_sentence<S: AsRef<str>>(&mut self, sentence: &[S]) {
+        let padded = self.pad(sentence);
+        for i in self.order - 1..padded.len() {
+            for k in 1..=self.order {
+                let gram = &padded[i + 1 - k..=i];
+                match self.counts[k - 1].get_mut(gram) {
+                    Some(count) => *count += 1,
+                    None => {
+                        self.counts[k - 1].insert(gram.to_vec(), 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the counts used as numerators at order `k`: raw counts at the highest
+    /// order and, for Kneser-Ney, continuation counts below it.
+    fn numerators<'a>(
+        &'a self,
+        continuations: &'a [HashMap<Vec<String>, usize>],
+        k: usize,
+    ) -> &'a HashMap<Vec<String>, usize> {
+        if k == self.order || self.smoothing == Smoothing::Laplace {
+            &self.counts[k - 1]
+        } else {
+            &continuations[k - 1]
+        }
+    }
+
+    /// Returns the smoothing statistics, computing them from the counts if they were
+    /// invalidated by training or an update.
+    fn estimates(&self) -> &Estimates {
+        self.estimates.get_or_init(|| self.estimate())
+    }
+
+    /// Computes continuation counts, context totals and discounts from the counts.
+    fn estimate(&self) -> Estimates {
+        let continuations: Vec<HashMap<Vec<String>, usize>> = (1..self.order)
+            .map(|k| {
+                let mut continuations: HashMap<Vec<String>, usize> = HashMap::new();
+                for gram in self.counts[k].keys() {
+                    *continuations.entry(gram[1..].to_vec()).or_default() += 1;
+                }
+                continuations
+            })
+            .collect();
+
+        let mut all_contexts = Vec::with_capacity(self.order);
+        let mut discounts = Vec::with_capacity(self.order);
+        for k in 1..=self.order {
+            let mut contexts: HashMap<Vec<String>, (usize, usize)> = HashMap::new();
+            let (mut once, mut twice) = (0, 0);
+            for (gram, &count) in self.numerators(&continuations, k) {
+                let entry = contexts.entry(gram[..k - 1].to_vec()).or_default();
+                entry.0 += count;
+                entry.1 += 1;
+                match count {
+                    1 => once += 1,
+                    2 => twice += 1,
+                    _ => {}
+                }
+            }
+            // Ney's estimate, falling back to the usual 0.75 on tiny corpora
+            let discount = if once > 0 && twice > 0 {
+                once as f64 / (once + 2 * twice) as f64
+            } else {
+                0.75
+            };
+            all_contexts.push(contexts);
+            discounts.push(discount);
+        }
+        Estimates {
+            continuations,
+            contexts: all_contexts,
+            discounts,
+        }
+    }
+
+    /// Returns the probability of the last token of `window` given the tokens before
+    /// it; `window` holds exactly `order` tokens.
+    fn window_prob(&self, window: &[String]) -> f64 {
+        let estimates = self.estimates();
+        let vocab = self.vocab_size() as f64;
+        match self.smoothing {
+            Smoothing::Laplace => {
+                let total = estimates.contexts[self.order - 1]
+                    .get(&window[..self.order - 1])
+                    .map_or(0, |&(total, _)| total);
+                let count = self.counts[self.order - 1]
+                    .get(window)
+                    .copied()
+                    .unwrap_or(0);
+                (count + 1) as f64 / (total as f64 + vocab)
+            }
+            Smoothing::KneserNey => {
+                let mut prob = 1.0 / vocab;
+                for k in 1..=self.order {
+                    let gram = &window[self.order - k..];
+                    let Some(&(total, types)) = estimates.contexts[k - 1].get(&gram[..k - 1])
+                    else {
+                        continue;
+                    };
+                    let discount = estimates.discounts[k - 1];
+                    let count = self
+                        .numerators(&estimates.continuations, k)
+                        .get(gram)
+                        .copied()
+                        .unwrap_or(0);
+                    prob = (count as f64 - discount).max(0.0) / total as f64
+                        + discount * types as f64 / total as f64 * prob;
+                }
+                prob
+            }
+        }
+    }
+}
+
+/// A query-time mixture of language models built by [`LanguageModel::interpolate`].
+///
+/// Each probability is the weighted sum of the component probabilities, so the
+/// mixture borrows its models rather than merging their counts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpolatedModel<'a> {
+    components: Vec<(&'a LanguageModel, f64)>,
+}
+
+impl InterpolatedModel<'_> {
+    /// Iterates over the normalized weights, in the order the models were given.
+    pub fn weights(&self) -> impl Iterator<Item = f64> + '_ {
+        self.components.iter().map(|&(_, weight)| weight)
+    }
+
+    /// Returns the probability of `word` following `context`.
+    ///
+    /// Each component reads as much of `context` as its own order allows.
+    pub fn prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        self.components
+            .iter()
+            .map(|&(model, weight)| weight * model.prob(context, word))
+            .sum()
+    }
+
+    /// Returns the natural logarithm of [`InterpolatedModel::prob`].
+    pub fn log_prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        self.prob(context, word).ln()
+    }
+
+    /// Returns the natural log-probability of a whole sentence, including its `</s>`.
+    pub fn sentence_log_prob<S: AsRef<str>>(&self, sentence: &[S]) -> f64 {
+        (0..=sentence.len())
+            .map(|i| self.log_prob(&sentence[..i], predicted(sentence, i)))
+            .sum()
+    }
+
+    /// Returns the per-token perplexity of the mixture on tokenized sentences, counted
+    /// as in [`LanguageModel::perplexity`].
+    pub fn perplexity<I, S, T>(&self, sentences: I) -> f64
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        corpus_perplexity(sentences, |sentence| self.sentence_log_prob(sentence))
+    }
+
+    /// Tunes the weights by expectation-maximization to minimize the perplexity on
+    /// held-out sentences, and returns the perplexity reached.
+    ///
+    /// Each iteration sets every weight to the share of the held-out tokens its model
+    /// is responsible for, which never increases the perplexity. Nothing changes when
+    /// there is nothing to score.
+    ///
+    /// # Arguments
+    ///
+    /// * `heldout` - Tokenized sentences not used to train any component
+    /// * `iterations` - How many EM steps to run
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Smoothing};
+    ///
+    /// let general = LanguageModel::train(2, Smoothing::KneserNey, [["the", "cat", "sat"]]);
+    /// let medical = LanguageModel::train(2, Smoothing::KneserNey, [["the", "patient", "sat"]]);
+    /// let mut mixture = LanguageModel::interpolate(&[(&general, 1.0), (&medical, 1.0)]);
+    ///
+    /// let heldout = [["the", "patient", "sat"]];
+    /// let before = mixture.perplexity(&heldout);
+    /// let after = mixture.fit_weights(&heldout, 10);
+    /// assert!(after < before);
+    /// assert!(mixture.weights().nth(1).unwrap() > 0.5);
+    /// ```
+    pub fn fit_weights<I, S, T>(&mut self, heldout: I, iterations: usize) -> f64
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        // The component probabilities never change, so score every token once
+        let mut token_probs: Vec<Vec<f64>> = Vec::new();
+        for sentence in heldout {
+            let sentence = sentence.as_ref();
+            for i in 0..=sentence.len() {
+                let word = predicted(sentence, i);
+                token_probs.push(
+                    self.components
+                        .iter()
+                        .map(|&(model, _)| model.prob(&sentence[..i], word))
+                        .collect(),
+                );
+            }
+        }
+        if token_probs.is_empty() {
+            return 1.0;
+        }
+
+        for _ in 0..iterations {
+            let mut responsibilities = vec![0.0; self.components.len()];
+            for probs in &token_probs {
+                let mixed: f64 = self.weighted(probs).sum();
+                for (total, share) in responsibilities.iter_mut().zip(self.weighted(probs)) {
+                    *total += share / mixed;
+                }
+            }
+            for ((_, weight), total) in self.components.iter_mut().zip(responsibilities) {
+                *weight = total / token_probs.len() as f64;
+            }
+        }
+        let log_prob: f64 = token_probs
+            .iter()
+            .map(|probs| self.weighted(probs).sum::<f64>().ln())
+            .sum();
+        (-log_prob / token_probs.len() as f64).exp()
+    }
+
+    /// Multiplies the component probabilities of one token by their weights.
+    fn weighted<'p>(&'p self, probs: &'p [f64]) -> impl Iterator<Item = f64> + 'p {
+        self.components
+            .iter()
+            .zip(probs)
+            .map(|(&(_, weight), &prob)| weight * prob)
+    }
+}
+
+/// A [`LanguageModel`] answering queries from a least-recently-used cache of
+/// next-word distributions, built by [`LanguageModel::with_cache`].
+///
+/// The cache is behind a mutex, so one cached model can be shared by scoring
+/// threads. Distributions are computed outside the lock.
+#[derive(Debug)]
+pub struct CachedModel<'a> {
+    model: &'a LanguageModel,
+    capacity: usize,
+    cache: Mutex<QueryCache>,
+}
+
+/// The probabilities of the words following one context, shared with callers.
+type WordProbs = Arc<HashMap<String, f64>>;
+
+/// The state of a [`CachedModel`].
+#[derive(Debug, Default)]
+struct QueryCache {
+    /// Each cached context window with the probabilities of the words following it
+    /// and the tick of its last use
+    entries: HashMap<Vec<String>, (WordProbs, u64)>,
+    /// The cached windows by tick of last use, oldest first
+    recency: BTreeMap<u64, Vec<String>>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl CachedModel<'_> {
+    /// Returns the probability of `word` following `context`, as
+    /// [`LanguageModel::prob`] does.
+    ///
+    /// Words never seen in training are scored by the model directly and are not
+    /// cached.
+    pub fn prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        let mut window = self.model.window(context, "");
+        window.pop();
+        match self.lookup(window).get(word) {
+            Some(&prob) => prob,
+            None => self.model.prob(context, word),
+        }
+    }
+
+    /// Returns the natural logarithm of [`CachedModel::prob`].
+    pub fn log_prob<S: AsRef<str>>(&self, context: &[S], word: &str) -> f64 {
+        self.prob(context, word).ln()
+    }
+
+    /// Returns the natural log-probability of a whole sentence, including its `</s>`.
+    pub fn sentence_log_prob<S: AsRef<str>>(&self, sentence: &[S]) -> f64 {
+        (0..=sentence.len())
+            .map(|i| self.log_prob(&sentence[..i], predicted(sentence, i)))
+            .sum()
+    }
+
+    /// Returns the per-token perplexity on tokenized sentences, counted as in
+    /// [`LanguageModel::perplexity`].
+    pub fn perplexity<I, S, T>(&self, sentences: I) -> f64
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[T]>,
+        T: AsRef<str>,
+    {
+        corpus_perplexity(sentences, |sentence| self.sentence_log_prob(sentence))
+    }
+
+    /// Returns the maximum number of cached contexts.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached contexts.
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Returns `true` if no context is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many queries were answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.state().hits
+    }
+
+    /// Returns how many queries had to compute a distribution.
+    pub fn misses(&self) -> u64 {
+        self.state().misses
+    }
+
+    /// Empties the cache and resets its hit and miss counts.
+    pub fn clear(&self) {
+        *self.state() = QueryCache::default();
+    }
+
+    /// Locks the cache, which stays consistent even if a thread panicked holding it.
+    fn state(&self) -> MutexGuard<'_, QueryCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the cached distribution after a context window, computing and caching
+    /// it on a miss.
+    fn lookup(&self, history: Vec<String>) -> WordProbs {
+        {
+            let mut guard = self.state();
+            let cache = &mut *guard;
+            cache.tick += 1;
+            if let Some((probs, last_used)) = cache.entries.get_mut(&history) {
+                let window = cache.recency.remove(last_used).expect("cached window");
+                cache.recency.insert(cache.tick, window);
+                *last_used = cache.tick;
+                cache.hits += 1;
+                return Arc::clone(probs);
+            }
+            cache.misses += 1;
+        }
+
+        let mut window = history.clone();
+        window.push(String::new());
+        let probs: WordProbs = Arc::new(
+            self.model
+                .word_probs(window)
+                .into_iter()
+                .map(|(word, prob)| (word.to_string(), prob))
+                .collect(),
+        );
+
+        let mut guard = self.state();
+        let cache = &mut *guard;
+        cache.tick += 1;
+        // Another thread may have cached the same window meanwhile
+        if let Some((_, last_used)) = cache.entries.remove(&history) {
+            cache.recency.remove(&last_used);
+        }
+        if cache.entries.len() == self.capacity
+            && let Some((_, oldest)) = cache.recency.pop_first()
+        {
+            cache.entries.remove(&oldest);
+        }
+        cache.recency.insert(cache.tick, history.clone());
+        cache
+            .entries
+            .insert(history, (Arc::clone(&probs), cache.tick));
+        probs
+    }
+}
+
+/// Returns the per-token perplexity of tokenized sentences, each scored by
+/// `sentence_log_prob`, counting every word and the `</s>` of every sentence.
+fn corpus_perplexity<I, S, T>(sentences: I, sentence_log_prob: impl Fn(&[T]) -> f64) -> f64
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[T]>,
+    T: AsRef<str>,
+{
+    let mut log_prob = 0.0;
+    let mut tokens = 0;
+    for sentence in sentences {
+        let sentence = sentence.as_ref();
+        log_prob += sentence_log_prob(sentence);
+        tokens += sentence.len() + 1;
+    }
+    if tokens == 0 {
+        return 1.0;
+    }
+    (-log_prob / tokens as f64).exp()
+}
+
+/// Returns the token predicted at position `i` of a sentence: its `i`-th word, or
+/// `</s>` just past the end.
+fn predicted<S: AsRef<str>>(sentence: &[S], i: usize) -> &str {
+    sentence.get(i).map_or(EOS, AsRef::as_ref)
+}
+
+/// Returns how many phrase tokens `words` covers, counting a phrase in full once it
+/// appears and otherwise the longest start of it that `words` ends with, along with
+/// the next token of every phrase not yet complete.
+fn phrase_progress<'p>(words: &[String], phrases: &[Vec<&'p str>]) -> (usize, Vec<&'p str>) {
+    let mut covered = 0;
+    let mut next = Vec::new();
+    for phrase in phrases {
+        if words
+            .windows(phrase.len())
+            .any(|window| window == phrase.as_slice())
+        {
+            covered += phrase.len();
+            continue;
+        }
+        let matched = (1..phrase.len().min(words.len() + 1))
+            .rev()
+            .find(|&k| words[words.len() - k..] == phrase[..k])
+            .unwrap_or(0);
+        covered += matched;
+        next.push(phrase[matched]);
+    }
+    (covered, next)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_u64<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Reads a length or count, rejecting values that don't fit in a `usize`.
+fn read_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    usize::try_from(read_u64(reader)?).map_err(|_| invalid_data("length out of range"))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = read_len(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<Vec<&'static str>> {
+        vec![
+            vec!["the", "cat", "sat", "on", "the", "mat"],
+            vec!["the", "dog", "sat", "on", "the", "log"],
+            vec!["a", "cat", "saw", "the", "dog"],
+        ]
+    }
+
+    /// Tests that both smoothing methods give a proper distribution over the vocabulary
+    #[test]
+    fn test_probabilities_sum_to_one() {
+        for smoothing in [Smoothing::Laplace, Smoothing::KneserNey] {
+            let model = LanguageModel::train(3, smoothing, corpus());
+            let mut words: Vec<&str> = model.counts[0].keys().map(|g| g[0].as_str()).collect();
+            words.push("<unk>");
+            assert_eq!(words.len(), model.vocab_size());
+
+            for context in [&["on", "the"][..], &["the"], &[], &["never", "seen"]] {
+                let total: f64 = words.iter().map(|w| model.prob(context, w)).sum();
+                assert!(
+                    (total - 1.0).abs() < 1e-9,
+                    "{smoothing:?} {context:?}: {total}"
+                );
+            }
+        }
+    }
+
+    /// Tests that seen text scores a lower perplexity than shuffled text
+    #[test]
+    fn test_perplexity_prefers_seen_text() {
+        let model = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let seen = model.perplexity([["the", "cat", "sat", "on", "the", "log"]]);
+        let shuffled = model.perplexity([["log", "the", "on", "sat", "cat", "the"]]);
+        assert!(seen < shuffled);
+        assert!(model.prob(&["on", "the"], "zebra") > 0.0);
+        assert_eq!(model.perplexity(Vec::<Vec<&str>>::new()), 1.0);
+    }
+
+    /// Tests that a model survives a write/read round trip and rejects other data
+    #[test]
+    fn test_model_round_trip() {
+        let model = LanguageModel::train(2, Smoothing::KneserNey, corpus());
+        let mut bytes = Vec::new();
+        model.write_to(&mut bytes).unwrap();
+        let restored = LanguageModel::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(restored, model);
+        assert!(LanguageModel::read_from(&mut &bytes[..bytes.len() - 3]).is_err());
+        assert!(LanguageModel::read_from(&mut &b"not a model"[..]).is_err());
+    }
+
+    /// Tests that a deserialized model rebuilds its estimates and scores like the original
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_model_serde_round_trip() {
+        for smoothing in [Smoothing::Laplace, Smoothing::KneserNey] {
+            let model = LanguageModel::train(3, smoothing, corpus());
+            let before = model.prob(&["the"], "cat");
+
+            let json = serde_json::to_string(&model).unwrap();
+            let restored: LanguageModel = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, model);
+            assert!(restored.estimates.get().is_none());
+            assert_eq!(restored.prob(&["the"], "cat"), before);
+            assert_eq!(restored.perplexity(corpus()), model.perplexity(corpus()));
+        }
+    }
+
+    /// Tests that bogus orders and table sizes in a header fail without reserving
+    /// memory for them
+    #[test]
+    fn test_model_bogus_header() {
+        let header = |order: u64, entries: u64| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(VERSION.to_le_bytes());
+            bytes.extend(order.to_le_bytes());
+            bytes.push(1);
+            bytes.extend(entries.to_le_bytes());
+            bytes
+        };
+        for (order, entries) in [(u64::MAX, 1), (2, u64::MAX), (u64::MAX, u64::MAX)] {
+            let error = LanguageModel::read_from(&mut header(order, entries).as_slice());
+            assert_eq!(error.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        }
+        let mut bytes = header(1, 1);
+        bytes.extend(u64::MAX.to_le_bytes());
+        let error = LanguageModel::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(LanguageModel::read_from(&mut &header(1, 0)[..]).is_ok());
+    }
+
+    /// Tests that a mixture of models sharing a vocabulary stays a distribution and that EM moves weight to the
+    /// model matching the held-out text
+    #[test]
+    fn test_interpolated_model() {
+        let general = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let domain = LanguageModel::train(
+            2,
+            Smoothing::Laplace,
+            [["the", "dog", "barked"], ["the", "dog", "ran"]],
+        );
+        let unigram = LanguageModel::train(1, Smoothing::Laplace, corpus());
+        let shared = LanguageModel::interpolate(&[(&general, 2.0), (&unigram, 1.0)]);
+        let mut words: Vec<&str> = general.counts[0].keys().map(|g| g[0].as_str()).collect();
+        words.push("<unk>");
+        let total: f64 = words.iter().map(|w| shared.prob(&["the"], w)).sum();
+        assert!((total - 1.0).abs() < 1e-9, "{total}");
+
+        let mut mixture = LanguageModel::interpolate(&[(&general, 1.0), (&domain, 1.0)]);
+
+        let sentence = ["the", "dog", "barked"];
+        let by_token: f64 = (0..=3)
+            .map(|i| mixture.log_prob(&sentence[..i], predicted(&sentence, i)))
+            .sum();
+        assert!((mixture.sentence_log_prob(&sentence) - by_token).abs() < 1e-12);
+
+        let heldout = [vec!["the", "dog", "barked"], vec!["the", "dog", "ran"]];
+        let before = mixture.perplexity(&heldout);
+        let after = mixture.fit_weights(&heldout, 20);
+        assert!((mixture.perplexity(&heldout) - after).abs() < 1e-9);
+        assert!(after < before);
+        let weights: Vec<f64> = mixture.weights().collect();
+        assert!(weights[1] > weights[0]);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        assert_eq!(mixture.fit_weights(Vec::<Vec<&str>>::new(), 5), 1.0);
+        assert_eq!(mixture.weights().collect::<Vec<_>>(), weights);
+    }
+
+    /// Tests that beam search scores match the model and widen with the beam
+    #[test]
+    fn test_generate_beam() {
+        let model = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let beams = model.generate_beam(&[] as &[&str], 4, 10);
+        assert_eq!(beams.len(), 4);
+        for pair in beams.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+        for (words, score) in &beams {
+            assert!(words.len() <= 10);
+            assert!((model.sentence_log_prob(words) - score).abs() < 1e-9);
+        }
+
+        // A beam of one follows the most probable word at every step
+        let greedy = model.generate_beam(&["the"], 1, 10);
+        assert_eq!(greedy.len(), 1);
+        let mut history = vec!["the".to_string()];
+        for word in &greedy[0].0 {
+            assert_eq!(model.distribution(&history)[0].0, word);
+            history.push(word.clone());
+        }
+
+        // Unfinished hypotheses are returned at the length limit
+        let cut = model.generate_beam(&["the"], 2, 1);
+        assert!(cut.iter().all(|(words, _)| words.len() <= 1));
+        assert!(model.generate_beam(&["the"], 0, 5).is_empty());
+        assert_eq!(model.generate_beam(&["the"], 3, 0), [(Vec::new(), 0.0)]);
+    }
+
+    /// Tests that constrained search covers every phrase, even unlikely or unseen ones
+    #[test]
+    fn test_generate_constrained() {
+        let model = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let phrases = [vec!["the", "log"], vec!["a", "cat"], vec!["zebra"]];
+        let beams = model.generate_constrained(&[] as &[&str], &phrases, 5, 12);
+        assert!(!beams.is_empty());
+        for (words, score) in &beams {
+            for phrase in &phrases {
+                assert!(words.windows(phrase.len()).any(|w| w == phrase.as_slice()));
+            }
+            assert!(words.len() <= 12);
+            assert!(score.is_finite());
+        }
+
+        // The phrases need five words, so four are not enough
+        assert!(
+            model
+                .generate_constrained(&[] as &[&str], &phrases, 5, 4)
+                .is_empty()
+        );
+        assert_eq!(
+            model.generate_constrained(&["on"], &[[] as [&str; 0]], 3, 6),
+            model.generate_beam(&["on"], 3, 6)
+        );
+
+        let (covered, next) = phrase_progress(
+            &["x".to_string(), "the".to_string()],
+            &[vec!["the", "log"], vec!["x"]],
+        );
+        assert_eq!((covered, next), (2, vec!["log"]));
+    }
+
+    /// Tests that parallel scoring matches sequential scoring
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_perplexity_batch() {
+        let model = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let mut heldout: Vec<Vec<String>> = Vec::new();
+        for i in 0..200 {
+            let sentence = &corpus()[i % 3];
+            heldout.push(sentence[i % 2..].iter().map(|w| w.to_string()).collect());
+        }
+        let batch = model.perplexity_batch(&heldout);
+        assert!((batch.corpus - model.perplexity(&heldout)).abs() < 1e-9);
+        for (sentence, perplexity) in heldout.iter().zip(&batch.sentences) {
+            assert!((perplexity - model.perplexity([sentence])).abs() < 1e-9);
+        }
+        assert_eq!(
+            model.perplexity_batch(&[] as &[Vec<String>]),
+            BatchPerplexity {
+                sentences: Vec::new(),
+                corpus: 1.0
+            }
+        );
+    }
+
+    /// Tests that traces add up to the sentence score and report backoff orders
+    #[test]
+    fn test_trace() {
+        for smoothing in [Smoothing::Laplace, Smoothing::KneserNey] {
+            let model = LanguageModel::train(3, smoothing, corpus());
+            let sentence = ["the", "cat", "sat", "on", "the", "dog"];
+            let trace = model.trace(&sentence);
+            let total: f64 = trace.iter().map(|t| t.log_prob).sum();
+            assert!((total - model.sentence_log_prob(&sentence)).abs() < 1e-12);
+            assert_eq!(
+                trace.iter().map(|t| t.order).collect::<Vec<_>>(),
+                [3, 3, 3, 3, 3, 2, 3]
+            );
+
+            let trace = model.trace(&["a", "zebra", "sat"]);
+            let found: Vec<(&str, usize)> =
+                trace.iter().map(|t| (t.token.as_str(), t.order)).collect();
+            assert_eq!(found, [("a", 3), ("zebra", 0), ("sat", 1), ("</s>", 1)]);
+            assert_eq!(model.trace(&[] as &[&str]).len(), 1);
+        }
+    }
+
+    /// Tests that cached queries match the model and that the least recently used
+    /// context is evicted first
+    #[test]
+    fn test_cached_model() {
+        let model = LanguageModel::train(3, Smoothing::KneserNey, corpus());
+        let cached = model.with_cache(2);
+        for context in [&["on", "the"][..], &["the"], &[], &["never", "seen"]] {
+            for word in ["the", "cat", "</s>", "zebra"] {
+                assert_eq!(cached.prob(context, word), model.prob(context, word));
+            }
+        }
+        assert_eq!(cached.len(), 2);
+        let sentences = corpus();
+        assert!((cached.perplexity(&sentences) - model.perplexity(&sentences)).abs() < 1e-9);
+
+        cached.clear();
+        assert!(cached.is_empty());
+        cached.prob(&["on", "the"], "cat");
+        cached.prob(&["the"], "cat");
+        cached.prob(&["on", "the"], "dog");
+        // ["the"] is now the least recently used context and makes way for ["sat"]
+        cached.prob(&["sat"], "on");
+        cached.prob(&["on", "the"], "mat");
+        cached.prob(&["the"], "dog");
+        assert_eq!((cached.hits(), cached.misses()), (2, 4));
+        assert_eq!(cached.capacity(), 2);
+    }
+
+    /// Tests that training stops once cancelled and is unaffected otherwise
+    #[test]
+    fn test_train_cancellable() {
+        let token = CancelToken::new();
+        let model = LanguageModel::train_cancellable(3, Smoothing::KneserNey, corpus(), &token);
+        assert_eq!(
+            model,
+            Ok(LanguageModel::train(3, Smoothing::KneserNey, corpus()))
+        );
+
+        let seen = std::cell::Cell::new(0);
+        let sentences = corpus().into_iter().inspect(|_| {
+            seen.set(seen.get() + 1);
+            if seen.get() == 2 {
+                token.cancel();
+            }
+        });
+        let result = LanguageModel::train_cancellable(3, Smoothing::Laplace, sentences, &token);
+        assert_eq!(result, Err(Cancelled));
+        assert_eq!(seen.get(), 2);
+    }
+
+    /// Tests that updating a queried model matches training on all sentences at once
+    #[test]
+    fn test_incremental_update() {
+        let sentences = corpus();
+        for smoothing in [Smoothing::Laplace, Smoothing::KneserNey] {
+            let full = LanguageModel::train(3, smoothing, &sentences);
+            let mut model = LanguageModel::train(3, smoothing, &sentences[..1]);
+            let first = model.prob(&["the"], "cat");
+            assert!(model.estimates.get().is_some());
+
+            model.update(&sentences[1..]);
+            assert!(model.estimates.get().is_none());
+            assert_eq!(model, full);
+            for (context, word) in [(&["on", "the"][..], "log"), (&["the"], "cat"), (&[], "a")] {
+                assert_eq!(model.prob(context, word), full.prob(context, word));
+            }
+            assert_ne!(model.prob(&["the"], "cat"), first);
+
+            model.update(Vec::<Vec<&str>>::new());
+            assert!(model.estimates.get().is_some());
+        }
+    }
+}
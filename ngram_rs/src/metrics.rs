@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use crate::NGramCounter;
+
+/// Precision, recall and F1 of the n-grams shared by a candidate and a reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RougeScore {
+    /// Shared n-grams over the n-grams of the candidate
+    pub precision: f64,
+    /// Shared n-grams over the n-grams of the reference
+    pub recall: f64,
+    /// Harmonic mean of precision and recall
+    pub f1: f64,
+}
+
+/// How strongly one n-gram characterizes a target corpus against a reference corpus,
+/// found by [`keyness`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyness<'a> {
+    /// The n-gram
+    pub ngram: &'a str,
+    /// Its count in the target corpus
+    pub count: usize,
+    /// Its count in the reference corpus
+    pub reference_count: usize,
+    /// Dunning's log-likelihood ratio (G²), the significance of the difference; above
+    /// 3.84 it is significant at p < 0.05
+    pub log_likelihood: f64,
+    /// Hardie's log ratio, the binary logarithm of the relative frequency in the
+    /// target over the one in the reference: each point doubles the difference
+    pub log_ratio: f64,
+    /// The 95% confidence interval of the log ratio, as `(low, high)`
+    pub log_ratio_interval: (f64, f64),
+}
+
+/// Finds the n-grams of size `n` that are characteristic of a target corpus compared
+/// with a reference corpus.
+///
+/// Only n-grams relatively more frequent in the target are returned, ordered by
+/// decreasing log-likelihood, ties broken by log ratio and then n-gram; swap the
+/// corpora to get those characteristic of the reference. Relative frequencies are
+/// taken over the total count of order `n` in each corpus, and an n-gram absent from
+/// the reference is given a count of 0.5 in the log ratio and its interval so they
+/// stay finite. Nothing is returned when either corpus has no n-gram of size `n`.
+///
+/// # Arguments
+///
+/// * `target` - The counts of the corpus to characterize
+/// * `reference` - The counts of the corpus it is compared with
+/// * `n` - The n-gram size to compare
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{NGramCounter, keyness};
+///
+/// let mut news = NGramCounter::new();
+/// news.add("election", 1, 10);
+/// news.add("the", 1, 90);
+/// let mut fiction = NGramCounter::new();
+/// fiction.add("election", 1, 10);
+/// fiction.add("the", 1, 990);
+///
+/// let key = keyness(&news, &fiction, 1);
+/// assert_eq!(key.len(), 1);
+/// assert_eq!(key[0].ngram, "election");
+/// assert!((key[0].log_ratio - 10_f64.log2()).abs() < 1e-12);
+/// assert!((key[0].log_likelihood - 22.138).abs() < 1e-3);
+/// ```
+pub fn keyness<'a>(
+    target: &'a NGramCounter,
+    reference: &NGramCounter,
+    n: usize,
+) -> Vec<Keyness<'a>> {
+    let (Some(counts), target_total, reference_total) =
+        (target.order(n), target.total(n), reference.total(n))
+    else {
+        return Vec::new();
+    };
+    if target_total == 0 || reference_total == 0 {
+        return Vec::new();
+    }
+    let (c, d) = (target_total as f64, reference_total as f64);
+
+    let mut key: Vec<Keyness> = counts
+        .iter()
+        .filter_map(|(ngram, &count)| {
+            let reference_count = reference.get_order(ngram, n);
+            let (a, b) = (count as f64, reference_count as f64);
+            let b_smoothed = if reference_count == 0 { 0.5 } else { b };
+            let log_ratio = ((a / c) / (b_smoothed / d)).log2();
+            if log_ratio <= 0.0 {
+                return None;
+            }
+            let expected_a = c * (a + b) / (c + d);
+            let expected_b = d * (a + b) / (c + d);
+            let term = |observed: f64, expected: f64| {
+                if observed > 0.0 {
+                    observed * (observed / expected).ln()
+                } else {
+                    0.0
+                }
+            };
+            let log_likelihood = 2.0 * (term(a, expected_a) + term(b, expected_b));
+            let margin = 1.96 * (1.0 / a + 1.0 / b_smoothed).sqrt() / std::f64::consts::LN_2;
+            Some(Keyness {
+                ngram,
+                count,
+                reference_count,
+                log_likelihood,
+                log_ratio,
+                log_ratio_interval: (log_ratio - margin, log_ratio + margin),
+            })
+        })
+        .collect();
+    key.sort_by(|x, y| {
+        y.log_likelihood
+            .total_cmp(&x.log_likelihood)
+            .then(y.log_ratio.total_cmp(&x.log_ratio))
+            .then(x.ngram.cmp(y.ngram))
+    });
+    key
+}
+
+/// Computes the sentence-level BLEU score of a candidate against one reference.
+///
+/// The score is the geometric mean of the clipped n-gram precisions for every size
+/// from 1 to `max_n`, times a brevity penalty for candidates shorter than the
+/// reference. No smoothing is applied, so a candidate without any matching n-gram of
+/// some size scores `0.0`, as does an empty candidate or a `max_n` of 0.
+///
+/// # Arguments
+///
+/// * `candidate` - The tokens of the text being evaluated
+/// * `reference` - The tokens of the reference text
+/// * `max_n` - The largest n-gram size taken into account, usually 4
+///
+/// # Returns
+///
+/// A score between `0.0` and `1.0`, where `1.0` means the candidate is the reference.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::bleu;
+///
+/// let reference = ["the", "cat", "sat", "on", "the", "mat"];
+/// assert_eq!(bleu(&reference, &reference, 4), 1.0);
+///
+/// let candidate = ["the", "cat", "sat", "on", "a", "mat"];
+/// let score = bleu(&candidate, &reference, 2);
+/// assert!((score - (5.0_f64 / 6.0 * 3.0 / 5.0).sqrt()).abs() < 1e-12);
+/// ```
+pub fn bleu<S: AsRef<str>>(candidate: &[S], reference: &[S], max_n: usize) -> f64 {
+    let candidate: Vec<&str> = candidate.iter().map(AsRef::as_ref).collect();
+    let reference: Vec<&str> = reference.iter().map(AsRef::as_ref).collect();
+    if candidate.is_empty() || max_n == 0 {
+        return 0.0;
+    }
+
+    let mut log_precision = 0.0;
+    for n in 1..=max_n {
+        let total = candidate.len().saturating_sub(n - 1);
+        let matched = overlap(&candidate, &reference, n);
+        if matched == 0 {
+            return 0.0;
+        }
+        log_precision += (matched as f64 / total as f64).ln();
+    }
+
+    let (c, r) = (candidate.len() as f64, reference.len() as f64);
+    let brevity_penalty = if c > r { 1.0 } else { (1.0 - r / c).exp() };
+    brevity_penalty * (log_precision / max_n as f64).exp()
+}
+
+/// Computes ROUGE-N, the overlap of the n-grams of size `n` of a candidate and a
+/// reference.
+///
+/// Shared n-grams are clipped to their count on either side. Precision or recall is
+/// `0.0` when its side has no n-gram of size `n`, and F1 is `0.0` when both are.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::rouge_n;
+///
+/// let score = rouge_n(&["the", "cat", "sat"], &["the", "cat", "ran", "away"], 2);
+/// assert_eq!(score.precision, 0.5);
+/// assert_eq!(score.recall, 1.0 / 3.0);
+/// assert!((score.f1 - 0.4).abs() < 1e-12);
+/// ```
+pub fn rouge_n<S: AsRef<str>>(candidate: &[S], reference: &[S], n: usize) -> RougeScore {
+    let candidate: Vec<&str> = candidate.iter().map(AsRef::as_ref).collect();
+    let reference: Vec<&str> = reference.iter().map(AsRef::as_ref).collect();
+    if n == 0 {
+        return RougeScore {
+            precision: 0.0,
+            recall: 0.0,
+            f1: 0.0,
+        };
+    }
+    let matched = overlap(&candidate, &reference, n) as f64;
+    let ratio = |tokens: &[&str]| match tokens.len().saturating_sub(n - 1) {
+        0 => 0.0,
+        total => matched / total as f64,
+    };
+    let precision = ratio(&candidate);
+    let recall = ratio(&reference);
+    let f1 = if precision + recall > 0.0 {
+        2.0 * precision * recall / (precision + recall)
+    } else {
+        0.0
+    };
+    RougeScore {
+        precision,
+        recall,
+        f1,
+    }
+}
+
+/// Counts the n-grams of size `n` of `candidate`, each clipped to its count in
+/// `reference`.
+fn overlap(candidate: &[&str], reference: &[&str], n: usize) -> usize {
+    let mut counts: HashMap<&[&str], usize> = HashMap::new();
+    for ngram in reference.windows(n) {
+        *counts.entry(ngram).or_default() += 1;
+    }
+    candidate
+        .windows(n)
+        .filter(|ngram| match counts.get_mut(ngram) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the keyness statistics, their ordering and the empty cases
+    #[test]
+    fn test_keyness() {
+        let mut target = NGramCounter::new();
+        target.add_document(
+            &["red", "cat", "red", "cat", "red", "dog", "a", "b"],
+            &[1, 2],
+            None,
+        );
+        let mut reference = NGramCounter::new();
+        reference.add_document(
+            &["blue", "cat", "blue", "dog", "a", "b", "a", "b"],
+            &[1, 2],
+            None,
+        );
+
+        let key = keyness(&target, &reference, 1);
+        let found: Vec<(&str, usize, usize)> = key
+            .iter()
+            .map(|k| (k.ngram, k.count, k.reference_count))
+            .collect();
+        assert_eq!(found, [("red", 3, 0), ("cat", 2, 1)]);
+
+        // 10 of 100 against 10 of 1000, worked out by hand
+        let (a, b, c, d) = (10.0_f64, 10.0_f64, 100.0_f64, 1000.0_f64);
+        let mut small = NGramCounter::new();
+        small.add("x", 1, 10);
+        small.add("y", 1, 90);
+        let mut large = NGramCounter::new();
+        large.add("x", 1, 10);
+        large.add("z", 1, 990);
+        let key = keyness(&small, &large, 1);
+        assert_eq!(key.iter().map(|k| k.ngram).collect::<Vec<_>>(), ["y", "x"]);
+        let x = &key[1];
+        let (e1, e2) = (c * (a + b) / (c + d), d * (a + b) / (c + d));
+        let g2 = 2.0 * (a * (a / e1).ln() + b * (b / e2).ln());
+        assert!((x.log_likelihood - g2).abs() < 1e-9);
+        let (low, high) = x.log_ratio_interval;
+        assert!((high - x.log_ratio - (x.log_ratio - low)).abs() < 1e-12);
+        assert!((low - 2.05735).abs() < 1e-5 && (high - 4.58651).abs() < 1e-5);
+
+        // "y" never occurs in the reference but stays finite
+        assert!(key[0].log_ratio.is_finite() && key[0].log_ratio_interval.0.is_finite());
+
+        assert!(keyness(&target, &reference, 3).is_empty());
+        assert!(keyness(&target, &NGramCounter::new(), 1).is_empty());
+    }
+
+    /// Tests clipping, the brevity penalty and the zero cases of BLEU
+    #[test]
+    fn test_bleu() {
+        // Every candidate unigram is "the", but the reference only has two
+        let score = bleu(&["the"; 4], &["the", "cat", "the", "mat"], 1);
+        assert_eq!(score, 0.5);
+
+        // A matching but shorter candidate is penalized
+        let score = bleu(&["the", "cat"], &["the", "cat", "sat"], 2);
+        assert!((score - (1.0_f64 - 1.5).exp()).abs() < 1e-12);
+
+        assert_eq!(bleu(&["a", "b"], &["b", "a"], 2), 0.0);
+        assert_eq!(bleu(&["a"], &["a"], 2), 0.0);
+        assert_eq!(bleu::<&str>(&[], &["a"], 1), 0.0);
+        assert_eq!(bleu(&["a"], &["a"], 0), 0.0);
+    }
+
+    /// Tests ROUGE-N clipping and rows without n-grams of the requested size
+    #[test]
+    fn test_rouge_n() {
+        let score = rouge_n(&["a", "a", "b"], &["a", "b", "b"], 1);
+        assert_eq!(score.precision, 2.0 / 3.0);
+        assert_eq!(score.recall, 2.0 / 3.0);
+
+        let score = rouge_n(&["a"], &["a", "b"], 2);
+        assert_eq!((score.precision, score.recall, score.f1), (0.0, 0.0, 0.0));
+        let score = rouge_n(&["a", "b"], &["a", "b"], 0);
+        assert_eq!(score.f1, 0.0);
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::vec::Vec;
+
+use crate::hash::{FNV_OFFSET, fnv1a, mix};
+
+/// MinHash signatures and locality-sensitive hashing (LSH) band keys for finding
+/// similar sets without comparing every pair.
+///
+/// A signature keeps, for each of `num_hashes` hash functions, the smallest hash of
+/// the set's items; the share of equal positions in two signatures estimates the
+/// Jaccard similarity of the sets. Signatures are then cut into bands of `rows`
+/// positions and each band hashed to a key, so sets sharing any key are candidate
+/// pairs. A pair with similarity `s` shares a band with probability
+/// `1 - (1 - s^rows)^bands`, which [`threshold`](MinHash::threshold) tunes.
+///
+/// Items are hashed with FNV-1a and a 64-bit finalizer, so signatures and keys are
+/// stable across platforms and releases.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{MinHash, minhash_similarity};
+///
+/// let minhash = MinHash::new(128).threshold(0.5);
+/// let a = minhash.signature(["the cat", "cat sat", "sat on", "on the", "the mat"]);
+/// let b = minhash.signature(["the cat", "cat sat", "sat on", "on the", "the rug"]);
+/// let c = minhash.signature(["a dog", "dog ran"]);
+///
+/// assert!((minhash_similarity(&a, &b) - 4.0 / 6.0).abs() < 0.2);
+/// let keys = minhash.band_keys(&a);
+/// assert!(minhash.band_keys(&b).iter().any(|key| keys.contains(key)));
+/// assert!(!minhash.band_keys(&c).iter().any(|key| keys.contains(key)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinHash {
+    num_hashes: usize,
+    rows: usize,
+}
+
+impl MinHash {
+    /// Creates signatures of `num_hashes` hashes, with bands of a single row.
+    pub fn new(num_hashes: usize) -> Self {
+        MinHash {
+            num_hashes: num_hashes.max(1),
+            rows: 1,
+        }
+    }
+
+    /// Sets the number of signature positions per band, between 1 and `num_hashes`.
+    ///
+    /// Trailing positions that do not fill a band are not part of any key.
+    pub fn rows(mut self, rows: usize) -> Self {
+        self.rows = rows.clamp(1, self.num_hashes);
+        self
+    }
+
+    /// Picks the most rows per band with which a pair of similarity `threshold` still
+    /// shares a band with probability at least 0.99, keeping candidates below the
+    /// threshold few without missing those above it.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        let threshold = threshold.clamp(0.0, 1.0);
+        self.rows = (1..=self.num_hashes)
+            .rev()
+            .find(|&rows| {
+                let bands = (self.num_hashes / rows) as i32;
+                1.0 - (1.0 - threshold.powi(rows as i32)).powi(bands) >= 0.99
+            })
+            .unwrap_or(1);
+        self
+    }
+
+    /// Returns the number of hashes in a signature.
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+
+    /// Returns the number of band keys per signature.
+    pub fn num_bands(&self) -> usize {
+        self.num_hashes / self.rows
+    }
+
+    /// Computes the signature of a set of items.
+    ///
+    /// Duplicate items do not change the signature, and an empty set has every
+    /// position at `u64::MAX`.
+    pub fn signature<S, I>(&self, items: I) -> Vec<u64>
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = S>,
+    {
+        let mut signature = vec![u64::MAX; self.num_hashes];
+        for item in items {
+            let hash = mix(fnv1a(FNV_OFFSET, item.as_ref().as_bytes()));
+            for (i, min) in signature.iter_mut().enumerate() {
+                *min = (*min).min(mix(hash ^ mix(i as u64)));
+            }
+        }
+        signature
+    }
+
+    /// Hashes each band of `signature` to a key, mixing in the band index so that
+    /// equal rows in different bands do not collide.
+    pub fn band_keys(&self, signature: &[u64]) -> Vec<u64> {
+        signature
+            .chunks_exact(self.rows)
+            .enumerate()
+            .map(|(band, rows)| {
+                let hash = rows.iter().fold(
+                    fnv1a(FNV_OFFSET, &(band as u64).to_le_bytes()),
+                    |hash, row| fnv1a(hash, &row.to_le_bytes()),
+                );
+                mix(hash)
+            })
+            .collect()
+    }
+}
+
+/// Estimates the Jaccard similarity of two sets from their [`MinHash`] signatures, as
+/// the share of positions where they agree.
+///
+/// Signatures of different lengths are compared over the shorter one, and two empty
+/// signatures score `1.0`.
+pub fn minhash_similarity(a: &[u64], b: &[u64]) -> f64 {
+    let len = a.len().min(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / len as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that signature agreement tracks Jaccard similarity and ignores duplicates
+    #[test]
+    fn test_minhash_signature() {
+        let minhash = MinHash::new(256);
+        let items: Vec<String> = (0..100).map(|i| format!("item{i}")).collect();
+        let a = minhash.signature(&items[..60]);
+        let b = minhash.signature(&items[30..]);
+        // 30 shared items out of 100
+        assert!((minhash_similarity(&a, &b) - 0.3).abs() < 0.1);
+        assert_eq!(minhash.signature(items[..60].iter().chain(&items[..10])), a);
+
+        let empty = minhash.signature(core::iter::empty::<&str>());
+        assert!(empty.iter().all(|&hash| hash == u64::MAX));
+        assert_eq!(minhash_similarity(&[], &[]), 1.0);
+    }
+
+    /// Tests the band layout and the rows picked for a threshold
+    #[test]
+    fn test_minhash_bands() {
+        let minhash = MinHash::new(10).rows(3);
+        assert_eq!(minhash.num_bands(), 3);
+        let signature = minhash.signature(["a", "b"]);
+        let keys = minhash.band_keys(&signature);
+        assert_eq!(keys.len(), 3);
+        assert_ne!(keys[0], keys[1]);
+        assert_eq!(keys, minhash.band_keys(&minhash.signature(["b", "a"])));
+
+        // Equal rows in different bands get different keys
+        let uniform = MinHash::new(4).rows(2).band_keys(&[7, 7, 7, 7]);
+        assert_ne!(uniform[0], uniform[1]);
+
+        assert_eq!(MinHash::new(128).threshold(1.0).num_bands(), 1);
+        assert_eq!(MinHash::new(128).threshold(0.0).num_bands(), 128);
+        let strict = MinHash::new(128).threshold(0.9).num_bands();
+        let loose = MinHash::new(128).threshold(0.5).num_bands();
+        assert!(strict < loose);
+    }
+}
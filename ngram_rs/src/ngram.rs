@@ -0,0 +1,213 @@
+use alloc::string::String;
+use core::fmt;
+
+/// A single n-gram that borrows its tokens and joins them only when formatted.
+///
+/// `NGram` holds the window of words and the delimiter; no string is built until
+/// the value is displayed (e.g. via `to_string()` or `write!`). Comparison against
+/// `str` walks the tokens directly, so filtering n-grams does not allocate either.
+///
+/// The tokens keep the type of the words they were taken from, so n-grams over
+/// `&[&str]` hand back `&[&str]` from [`tokens`](NGram::tokens).
+#[derive(Debug)]
+pub struct NGram<'a, S = String> {
+    window: &'a [S],
+    delimiter: &'a str,
+}
+
+impl<S> Clone for NGram<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for NGram<'_, S> {}
+
+impl<'a, S: AsRef<str>> NGram<'a, S> {
+    /// Creates an n-gram over `window`, joined with `delimiter` when formatted.
+    pub fn new(window: &'a [S], delimiter: &'a str) -> Self {
+        NGram { window, delimiter }
+    }
+
+    /// Returns the order of the n-gram (number of tokens).
+    pub fn n(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns the tokens making up the n-gram, without re-splitting any text.
+    pub fn tokens(&self) -> &'a [S] {
+        self.window
+    }
+
+    /// Returns the delimiter used when the n-gram is displayed.
+    pub fn delimiter(&self) -> &'a str {
+        self.delimiter
+    }
+
+    /// Joins the tokens with an arbitrary delimiter, ignoring the stored one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGram;
+    ///
+    /// let words = ["a", "b"];
+    /// let ngram = NGram::new(&words, " ");
+    ///
+    /// assert_eq!(ngram.n(), 2);
+    /// let tokens: &[&str] = ngram.tokens();
+    /// assert_eq!(tokens, ["a", "b"]);
+    /// assert_eq!(ngram.joined("_"), "a_b");
+    /// ```
+    pub fn joined(&self, delimiter: &str) -> String {
+        crate::join_window(self.window, delimiter)
+    }
+}
+
+impl<S: AsRef<str>> fmt::Display for NGram<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, token) in self.window.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.delimiter)?;
+            }
+            f.write_str(token.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<str> for NGram<'_, S> {
+    fn eq(&self, other: &str) -> bool {
+        let mut rest = other;
+        for (i, token) in self.window.iter().enumerate() {
+            if i > 0 {
+                match rest.strip_prefix(self.delimiter) {
+                    Some(r) => rest = r,
+                    None => return false,
+                }
+            }
+            match rest.strip_prefix(token.as_ref()) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        }
+        rest.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<&str> for NGram<'_, S> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+/// An iterator that yields [`NGram`] values without joining any strings.
+///
+/// Windows are produced in the same order as [`crate::NGramIterator`]: all n-grams
+/// for the first n-value, then all for the next, and so on. Invalid n-values
+/// (zero or larger than the number of words) are skipped.
+pub struct NGramWindowIterator<'a, S = String> {
+    words: &'a [S],
+    n_range: &'a [usize],
+    current_n: usize,
+    current_window: usize,
+    delimiter: &'a str,
+}
+
+impl<'a, S: AsRef<str>> Iterator for NGramWindowIterator<'a, S> {
+    type Item = NGram<'a, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current_n < self.n_range.len() {
+            let n = self.n_range[self.current_n];
+
+            if n == 0 || n > self.words.len() {
+                self.current_n += 1;
+                self.current_window = 0;
+                continue;
+            }
+
+            if self.current_window + n <= self.words.len() {
+                let window = &self.words[self.current_window..self.current_window + n];
+                self.current_window += 1;
+                return Some(NGram::new(window, self.delimiter));
+            }
+
+            self.current_n += 1;
+            self.current_window = 0;
+        }
+
+        None
+    }
+}
+
+/// Creates an iterator yielding lazily-joined [`NGram`] values.
+///
+/// # Arguments
+///
+/// * `words` - A slice of string-like objects representing the input text
+/// * `n_range` - A slice of usize values specifying n-gram sizes
+/// * `delimiter` - Optional delimiter string (defaults to space)
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::ngram_windows;
+///
+/// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let mut iter = ngram_windows(&words, &[2], Some("-"));
+///
+/// let first = iter.next().unwrap();
+/// assert!(first == "a-b");
+/// assert_eq!(first.to_string(), "a-b");
+/// ```
+pub fn ngram_windows<'a, S: AsRef<str>>(
+    words: &'a [S],
+    n_range: &'a [usize],
+    delimiter: Option<&'a str>,
+) -> NGramWindowIterator<'a, S> {
+    NGramWindowIterator {
+        words,
+        n_range,
+        current_n: 0,
+        current_window: 0,
+        delimiter: delimiter.unwrap_or(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that lazy n-grams format and compare like joined strings
+    #[test]
+    fn test_ngram_windows() {
+        let words = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+        let ngrams: Vec<NGram> = ngram_windows(&words, &[1, 2], None).collect();
+
+        assert_eq!(ngrams.len(), 5);
+        assert!(ngrams[0] == "x");
+        assert!(ngrams[3] == "x y");
+        assert!(ngrams[3] != "x y ");
+        assert!(ngrams[3] != "x");
+        assert_eq!(ngrams[4].to_string(), "y z");
+    }
+
+    /// Tests the structured accessors of an n-gram
+    #[test]
+    fn test_ngram_accessors() {
+        let words = vec!["New York".to_string(), "City".to_string()];
+        let ngram = ngram_windows(&words, &[2], None).next().unwrap();
+
+        assert_eq!(ngram.n(), 2);
+        assert_eq!(ngram.tokens(), ["New York", "City"]);
+        assert_eq!(ngram.delimiter(), " ");
+        assert_eq!(ngram.joined("|"), "New York|City");
+
+        let words = ["a", "b", "c"];
+        let ngram = ngram_windows(&words, &[2], Some("-")).nth(1).unwrap();
+        let tokens: &[&str] = ngram.tokens();
+        assert_eq!(tokens, ["b", "c"]);
+        assert!(ngram == "b-c");
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Int32Type, Int64Type};
+use arrow_array::{ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::errors::ParquetError;
+
+use crate::NGramCounter;
+
+/// Schema of count tables: `ngram: Utf8, n: Int32, count: Int64`.
+///
+/// Signed integers are used because Spark and Hive have no unsigned types.
+fn counts_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("ngram", DataType::Utf8, false),
+        Field::new("n", DataType::Int32, false),
+        Field::new("count", DataType::Int64, false),
+    ]))
+}
+
+fn column_error(name: &str, expected: &str) -> ParquetError {
+    ParquetError::General(format!(
+        "expected a non-null `{name}` column of type {expected}"
+    ))
+}
+
+impl NGramCounter {
+    /// Writes the counts to a Parquet file with columns `(ngram, n, count)`.
+    ///
+    /// Rows are sorted by `n` and then by n-gram, one row group per order, so the same
+    /// counter always produces the same file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    ///
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["a", "rose", "is", "a", "rose"], &[1, 2], None);
+    ///
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("counts.parquet");
+    /// counter.write_parquet(&path).unwrap();
+    /// assert_eq!(NGramCounter::read_parquet(&path).unwrap(), counter);
+    /// ```
+    pub fn write_parquet(&self, path: impl AsRef<Path>) -> Result<(), ParquetError> {
+        let schema = counts_schema();
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema.clone(), None)?;
+
+        for n in self.orders() {
+            let entries = self.sorted_order(n);
+
+            let order = i32::try_from(n)
+                .map_err(|_| ParquetError::General(format!("order {n} does not fit in Int32")))?;
+            let columns: Vec<ArrayRef> = vec![
+                Arc::new(StringArray::from_iter_values(entries.iter().map(|e| e.0))),
+                Arc::new(Int32Array::from(vec![order; entries.len()])),
+                Arc::new(Int64Array::from_iter_values(
+                    entries.iter().map(|e| e.1 as i64),
+                )),
+            ];
+            writer.write(&RecordBatch::try_new(schema.clone(), columns)?)?;
+            writer.flush()?;
+        }
+
+        writer.close()?;
+        Ok(())
+    }
+
+    /// Reads counts written by [`NGramCounter::write_parquet`] or any tool producing the
+    /// same `(ngram, n, count)` columns.
+    ///
+    /// Counts of repeated rows are added together. Extra columns are ignored.
+    pub fn read_parquet(path: impl AsRef<Path>) -> Result<Self, ParquetError> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?.build()?;
+        let mut counter = NGramCounter::new();
+
+        for batch in reader {
+            let batch = batch?;
+            let ngrams = batch
+                .column_by_name("ngram")
+                .and_then(|c| c.as_string_opt::<i32>())
+                .ok_or_else(|| column_error("ngram", "Utf8"))?;
+            let orders = batch
+                .column_by_name("n")
+                .and_then(|c| c.as_primitive_opt::<Int32Type>())
+                .ok_or_else(|| column_error("n", "Int32"))?;
+            let counts = batch
+                .column_by_name("count")
+                .and_then(|c| c.as_primitive_opt::<Int64Type>())
+                .ok_or_else(|| column_error("count", "Int64"))?;
+
+            for ((ngram, n), count) in ngrams.iter().zip(orders.iter()).zip(counts.iter()) {
+                match (ngram, n, count) {
+                    (Some(ngram), Some(n), Some(count)) if n >= 0 && count >= 0 => {
+                        counter.add(ngram, n as usize, count as usize)
+                    }
+                    _ => {
+                        return Err(ParquetError::General(format!(
+                            "invalid row for `{ngram:?}`"
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(counter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that counts survive a Parquet round trip and that bad schemas are rejected
+    #[test]
+    fn test_counter_parquet_round_trip() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("ngram_rs_counts_{}.parquet", std::process::id()));
+
+        let mut counter = NGramCounter::new();
+        counter.add_document(&["x", "y", "x", "y", "z"], &[1, 2, 3], Some("|"));
+        counter.write_parquet(&path).unwrap();
+        let restored = NGramCounter::read_parquet(&path).unwrap();
+        assert_eq!(restored, counter);
+        assert_eq!(restored.get_order("x|y", 2), 2);
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "ngram",
+            DataType::Utf8,
+            false,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(StringArray::from(vec!["a"])) as ArrayRef],
+        )
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(File::create(&path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        assert!(NGramCounter::read_parquet(&path).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A phonetic code mapping words that sound alike to the same key, applied to tokens
+/// before n-grams are formed.
+///
+/// Only ASCII letters are encoded; other characters are ignored, so a token without
+/// any ASCII letter has an empty code.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{PhoneticCode, generate_ngrams};
+///
+/// let codes = PhoneticCode::Metaphone.encode_tokens(&["Catherine", "Smith"]);
+/// assert_eq!(codes, ["K0RN", "SM0"]);
+///
+/// // Spelling variants give the same phonetic n-grams
+/// let variant = PhoneticCode::Metaphone.encode_tokens(&["Kathryn", "Smyth"]);
+/// assert_eq!(generate_ngrams(&codes, &[2], None), generate_ngrams(&variant, &[2], None));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PhoneticCode {
+    /// American Soundex, see [`soundex`]
+    Soundex,
+    /// The original Metaphone, see [`metaphone`]
+    Metaphone,
+}
+
+impl PhoneticCode {
+    /// Returns the code of one word.
+    pub fn encode(self, word: &str) -> String {
+        match self {
+            PhoneticCode::Soundex => soundex(word),
+            PhoneticCode::Metaphone => metaphone(word),
+        }
+    }
+
+    /// Encodes every token, dropping the tokens whose code is empty.
+    pub fn encode_tokens<S: AsRef<str>>(self, tokens: &[S]) -> Vec<String> {
+        tokens
+            .iter()
+            .map(|token| self.encode(token.as_ref()))
+            .filter(|code| !code.is_empty())
+            .collect()
+    }
+}
+
+/// Returns the uppercased ASCII letters of `word`.
+fn ascii_letters(word: &str) -> Vec<u8> {
+    word.bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase())
+        .collect()
+}
+
+/// Computes the American Soundex code of a word: its first letter followed by three
+/// digits for the consonant sounds after it.
+///
+/// Letters sounding alike share a digit, adjacent letters with the same digit are
+/// coded once, even across an `H` or `W`, and a vowel between them codes both. Short
+/// codes are padded with zeros.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::soundex;
+///
+/// assert_eq!(soundex("Robert"), "R163");
+/// assert_eq!(soundex("Rupert"), "R163");
+/// assert_eq!(soundex("Ashcraft"), "A261");
+/// assert_eq!(soundex("Lee"), "L000");
+/// ```
+pub fn soundex(word: &str) -> String {
+    fn digit(letter: u8) -> Option<u8> {
+        match letter {
+            b'B' | b'F' | b'P' | b'V' => Some(b'1'),
+            b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => Some(b'2'),
+            b'D' | b'T' => Some(b'3'),
+            b'L' => Some(b'4'),
+            b'M' | b'N' => Some(b'5'),
+            b'R' => Some(b'6'),
+            // Vowels separate equal digits, H and W do not
+            b'H' | b'W' => None,
+            _ => Some(b'0'),
+        }
+    }
+
+    let letters = ascii_letters(word);
+    let Some((&first, rest)) = letters.split_first() else {
+        return String::new();
+    };
+    let mut code = String::with_capacity(4);
+    code.push(first as char);
+    let mut last = digit(first);
+    for &letter in rest {
+        let Some(current) = digit(letter) else {
+            continue;
+        };
+        if current != b'0' && Some(current) != last {
+            code.push(current as char);
+            if code.len() == 4 {
+                break;
+            }
+        }
+        last = Some(current);
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    code
+}
+
+/// Computes the Metaphone code of a word, following the original rules of Lawrence
+/// Philips (1990).
+///
+/// Metaphone models English pronunciation more closely than Soundex: it handles
+/// silent letters (`KN`, `GH`, `MB`), letter groups (`PH`, `TH` coded as `0`, `SH`
+/// and `CH` coded as `X`) and soft `C` and `G`. Vowels are only kept as the first
+/// letter, and the code has no fixed length.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::metaphone;
+///
+/// assert_eq!(metaphone("Knight"), "NT");
+/// assert_eq!(metaphone("Phillips"), "FLPS");
+/// assert_eq!(metaphone("Wright"), metaphone("Rite"));
+/// ```
+pub fn metaphone(word: &str) -> String {
+    fn is_vowel(letter: u8) -> bool {
+        matches!(letter, b'A' | b'E' | b'I' | b'O' | b'U')
+    }
+
+    // Doubled letters are coded once, except C, as in "accept"
+    let mut letters = ascii_letters(word);
+    letters.dedup_by(|b, a| a == b && *a != b'C');
+    let skip = match letters.get(..2) {
+        Some(b"KN" | b"GN" | b"PN" | b"AE" | b"WR") => 1,
+        _ => 0,
+    };
+    let w = &letters[skip..];
+    let at = |i: usize| w.get(i).copied().unwrap_or(0);
+
+    let mut code = String::new();
+    for (i, &letter) in w.iter().enumerate() {
+        let prev = if i > 0 { w[i - 1] } else { 0 };
+        let (next, after) = (at(i + 1), at(i + 2));
+        let coded: &str = match letter {
+            b'A' | b'E' | b'I' | b'O' | b'U' if i > 0 => "",
+            b'B' if prev == b'M' && i + 1 == w.len() => "",
+            b'C' if next == b'I' && after == b'A' => "X",
+            b'C' if next == b'H' && prev == b'S' => "K",
+            b'C' if next == b'H' => "X",
+            b'C' if matches!(next, b'I' | b'E' | b'Y') => {
+                if prev == b'S' {
+                    ""
+                } else {
+                    "S"
+                }
+            }
+            b'C' => "K",
+            b'D' if next == b'G' && matches!(after, b'E' | b'Y' | b'I') => "J",
+            b'D' => "T",
+            b'G' if prev == b'D' && matches!(next, b'E' | b'Y' | b'I') => "",
+            b'G' if next == b'H' && i + 2 < w.len() && !is_vowel(after) => "",
+            b'G' if next == b'N' && (i + 2 == w.len() || w[i + 2..] == *b"ED") => "",
+            b'G' if matches!(next, b'I' | b'E' | b'Y') && prev != b'G' => "J",
+            b'G' => "K",
+            b'H' if matches!(prev, b'C' | b'G' | b'P' | b'S' | b'T') => "",
+            b'H' if i == 1 && prev == b'W' => "",
+            b'H' if is_vowel(prev) && !is_vowel(next) => "",
+            b'K' if prev == b'C' => "",
+            b'P' if next == b'H' => "F",
+            b'Q' => "K",
+            b'S' if next == b'H' || next == b'I' && matches!(after, b'O' | b'A') => "X",
+            b'T' if next == b'I' && matches!(after, b'O' | b'A') => "X",
+            b'T' if next == b'H' => "0",
+            b'T' if next == b'C' && after == b'H' => "",
+            b'V' => "F",
+            b'W' if is_vowel(next) || i == 0 && next == b'H' => "W",
+            b'W' => "",
+            b'X' if i == 0 => "S",
+            b'X' => "KS",
+            b'Y' if is_vowel(next) => "Y",
+            b'Y' => "",
+            b'Z' => "S",
+            _ => {
+                code.push(letter as char);
+                continue;
+            }
+        };
+        code.push_str(coded);
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests Soundex on the classic reference names
+    #[test]
+    fn test_soundex() {
+        let cases = [
+            ("Tymczak", "T522"),
+            ("Pfister", "P236"),
+            ("Honeyman", "H555"),
+            ("Rubin", "R150"),
+            ("O'Hara", "O600"),
+            ("Washington", "W252"),
+        ];
+        for (name, code) in cases {
+            assert_eq!(soundex(name), code, "{name}");
+        }
+        assert_eq!(soundex("robert"), soundex("ROBERT"));
+        assert_eq!(soundex("42"), "");
+    }
+
+    /// Tests the Metaphone letter rules and that spelling variants share a code
+    #[test]
+    fn test_metaphone() {
+        let cases = [
+            ("Smith", "SM0"),
+            ("Schmidt", "SKMTT"),
+            ("science", "SNS"),
+            ("thumb", "0M"),
+            ("Xavier", "SFR"),
+            ("Wright", "RT"),
+            ("judge", "JJ"),
+            ("nation", "NXN"),
+            ("accept", "AKSPT"),
+        ];
+        for (word, code) in cases {
+            assert_eq!(metaphone(word), code, "{word}");
+        }
+        for (a, b) in [
+            ("Catherine", "Kathryn"),
+            ("Stephen", "Steven"),
+            ("phone", "fone"),
+        ] {
+            assert_eq!(metaphone(a), metaphone(b), "{a} {b}");
+        }
+        assert_eq!(metaphone(""), "");
+    }
+
+    /// Tests that tokens without letters are dropped
+    #[test]
+    fn test_encode_tokens() {
+        let codes = PhoneticCode::Soundex.encode_tokens(&["Anna", "2024", "Ana"]);
+        assert_eq!(codes, ["A500", "A500"]);
+    }
+}
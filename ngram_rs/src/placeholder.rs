@@ -0,0 +1,200 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Replaces number, URL and email address tokens with placeholder tokens before
+/// n-grams are counted.
+///
+/// Every price, phone number and link would otherwise be a type of its own, blowing
+/// up the vocabulary of a language model with entries seen once. The defaults are
+/// `<NUM>`, `<URL>` and `<EMAIL>`; each placeholder can be changed or disabled with the
+/// builder methods.
+///
+/// Tokens are matched whole, ignoring surrounding brackets, quotes and trailing
+/// sentence punctuation, which are replaced along with them:
+///
+/// * URLs start with `http://`, `https://`, `ftp://` or `www.`, in any case
+/// * Email addresses are `local@domain.tld`, with an alphabetic top-level domain
+/// * Numbers are ASCII digits, optionally signed, grouped with `,` or `.` and ending
+///   with `%`
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{Placeholders, generate_ngrams};
+///
+/// let placeholders = Placeholders::new();
+/// let tokens = ["mail", "bob@example.com", "or", "call", "555-0100"];
+/// assert_eq!(
+///     placeholders.replace_tokens(&tokens),
+///     ["mail", "<EMAIL>", "or", "call", "555-0100"]
+/// );
+///
+/// // Prices and links share their n-grams
+/// let a = placeholders.replace_tokens(&["costs", "12.50", "(see", "https://a.example)"]);
+/// let b = placeholders.replace_tokens(&["costs", "8", "(see", "www.b.example)"]);
+/// assert_eq!(generate_ngrams(&a, &[2], None), generate_ngrams(&b, &[2], None));
+///
+/// let custom = Placeholders::new().number(Some("#")).url(None);
+/// assert_eq!(custom.replace_tokens(&["42", "www.example.com"]), ["#", "www.example.com"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholders {
+    number: Option<String>,
+    url: Option<String>,
+    email: Option<String>,
+}
+
+impl Default for Placeholders {
+    fn default() -> Self {
+        Placeholders {
+            number: Some("<NUM>".to_string()),
+            url: Some("<URL>".to_string()),
+            email: Some("<EMAIL>".to_string()),
+        }
+    }
+}
+
+impl Placeholders {
+    /// Creates placeholders replacing numbers, URLs and email addresses with `<NUM>`,
+    /// `<URL>` and `<EMAIL>`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the placeholder of numbers, or stops replacing them with `None`.
+    pub fn number(mut self, placeholder: Option<&str>) -> Self {
+        self.number = placeholder.map(str::to_string);
+        self
+    }
+
+    /// Sets the placeholder of URLs, or stops replacing them with `None`.
+    pub fn url(mut self, placeholder: Option<&str>) -> Self {
+        self.url = placeholder.map(str::to_string);
+        self
+    }
+
+    /// Sets the placeholder of email addresses, or stops replacing them with `None`.
+    pub fn email(mut self, placeholder: Option<&str>) -> Self {
+        self.email = placeholder.map(str::to_string);
+        self
+    }
+
+    /// Returns the placeholder standing in for `token`, if it is a number, URL or email
+    /// address that is replaced.
+    pub fn placeholder(&self, token: &str) -> Option<&str> {
+        let token = token
+            .trim_start_matches(['(', '[', '{', '<', '"', '\''])
+            .trim_end_matches([')', ']', '}', '>', '"', '\'', '.', ',', ';', ':', '!', '?']);
+        let placeholder = if is_url(token) {
+            &self.url
+        } else if is_email(token) {
+            &self.email
+        } else if is_number(token) {
+            &self.number
+        } else {
+            return None;
+        };
+        placeholder.as_deref()
+    }
+
+    /// Returns the placeholder of `token`, or `token` itself.
+    pub fn replace<'a>(&'a self, token: &'a str) -> &'a str {
+        self.placeholder(token).unwrap_or(token)
+    }
+
+    /// Replaces every token that has a placeholder.
+    pub fn replace_tokens<'a, S: AsRef<str>>(&'a self, tokens: &'a [S]) -> Vec<&'a str> {
+        tokens
+            .iter()
+            .map(|token| self.replace(token.as_ref()))
+            .collect()
+    }
+}
+
+fn is_url(token: &str) -> bool {
+    ["http://", "https://", "ftp://", "www."]
+        .iter()
+        .any(|scheme| {
+            token.len() > scheme.len()
+                && token.as_bytes()[..scheme.len()].eq_ignore_ascii_case(scheme.as_bytes())
+        })
+}
+
+fn is_email(token: &str) -> bool {
+    let Some((local, domain)) = token.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty()
+        && local
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"._%+-".contains(&b));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let tld = labels[labels.len() - 1];
+    local_ok
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+        && tld.len() >= 2
+        && tld.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_number(token: &str) -> bool {
+    let token = token.strip_prefix(['+', '-']).unwrap_or(token);
+    let token = token.strip_suffix('%').unwrap_or(token);
+    token
+        .split(['.', ','])
+        .all(|group| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests what counts as a number, URL or email address, with surrounding punctuation
+    #[test]
+    fn test_placeholder_kinds() {
+        let placeholders = Placeholders::new();
+        let kinds = [
+            ("42", Some("<NUM>")),
+            ("-3.14", Some("<NUM>")),
+            ("1,000,000.", Some("<NUM>")),
+            ("(50%)", Some("<NUM>")),
+            ("1.2.3", Some("<NUM>")),
+            ("1..2", None),
+            ("3rd", None),
+            ("-", None),
+            ("HTTPS://Example.com/a?b=1", Some("<URL>")),
+            ("<www.example.org>", Some("<URL>")),
+            ("www.", None),
+            ("first.last+tag@mail.example.co.uk", Some("<EMAIL>")),
+            ("\"bob@example.com\",", Some("<EMAIL>")),
+            ("bob@localhost", None),
+            ("bob@example.c0m", None),
+            ("@example.com", None),
+            ("a@b@example.com", None),
+            ("hello", None),
+        ];
+        for (token, expected) in kinds {
+            assert_eq!(placeholders.placeholder(token), expected, "{token}");
+        }
+    }
+
+    /// Tests custom and disabled placeholders
+    #[test]
+    fn test_custom_placeholders() {
+        let placeholders = Placeholders::new()
+            .number(Some("0"))
+            .url(None)
+            .email(Some("@"));
+        assert_eq!(
+            placeholders.replace_tokens(&["7", "http://x.example", "a@b.io"]),
+            ["0", "http://x.example", "@"]
+        );
+        let none = Placeholders::new().number(None).url(None).email(None);
+        assert_eq!(none.replace("12"), "12");
+    }
+}
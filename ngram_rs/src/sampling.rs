@@ -0,0 +1,254 @@
+use rand::Rng;
+
+use crate::LanguageModel;
+use crate::lm::EOS;
+
+/// How [`LanguageModel::generate`] picks each next word.
+///
+/// The model distribution is first sharpened or flattened by the temperature, then
+/// cut down to the `top_k` most probable words and to the smallest set of most
+/// probable words holding `top_p` of the probability mass, and finally renormalized
+/// and sampled. The default samples the model distribution as is.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::Sampling;
+///
+/// let sampling = Sampling::new().temperature(0.7).top_k(40).top_p(0.9);
+/// assert_eq!(sampling, Sampling::default().top_p(0.9).top_k(40).temperature(0.7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    temperature: f64,
+    top_k: Option<usize>,
+    top_p: Option<f64>,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampling {
+    /// Creates settings that sample the model distribution unchanged.
+    pub fn new() -> Self {
+        Sampling {
+            temperature: 1.0,
+            top_k: None,
+            top_p: None,
+        }
+    }
+
+    /// Sets the temperature: below 1 favors probable words, above 1 flattens the
+    /// distribution, and 0 always picks the most probable word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `temperature` is negative or not finite.
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        assert!(
+            temperature.is_finite() && temperature >= 0.0,
+            "the temperature must be finite and non-negative"
+        );
+        self.temperature = temperature;
+        self
+    }
+
+    /// Keeps only the `top_k` most probable words (at least 1).
+    pub fn top_k(mut self, top_k: usize) -> Self {
+        self.top_k = Some(top_k.max(1));
+        self
+    }
+
+    /// Keeps only the most probable words whose probabilities add up to `top_p`
+    /// (nucleus sampling).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `top_p` is not in `(0, 1]`.
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        assert!(top_p > 0.0 && top_p <= 1.0, "top_p must be in (0, 1]");
+        self.top_p = Some(top_p);
+        self
+    }
+
+    /// Picks a word from a distribution sorted by decreasing probability, or returns
+    /// `None` when it is empty.
+    fn pick<'a, R: Rng + ?Sized>(
+        &self,
+        distribution: &[(&'a str, f64)],
+        rng: &mut R,
+    ) -> Option<&'a str> {
+        let &(best, max_prob) = distribution.first()?;
+        if self.temperature == 0.0 {
+            return Some(best);
+        }
+        // Scaling by the largest probability in log space keeps the most probable
+        // word at weight 1, so low temperatures can't underflow every weight to 0
+        let mut weights: Vec<f64> = distribution
+            .iter()
+            .map(|&(_, prob)| {
+                if self.temperature == 1.0 {
+                    prob
+                } else {
+                    ((prob.ln() - max_prob.ln()) / self.temperature).exp()
+                }
+            })
+            .collect();
+        if let Some(top_k) = self.top_k {
+            weights.truncate(top_k);
+        }
+        if let Some(top_p) = self.top_p {
+            let total: f64 = weights.iter().sum();
+            let mut mass = 0.0;
+            let kept = weights
+                .iter()
+                .position(|&weight| {
+                    mass += weight;
+                    mass >= top_p * total
+                })
+                .map_or(weights.len(), |i| i + 1);
+            weights.truncate(kept);
+        }
+
+        let total: f64 = weights.iter().sum();
+        let mut target = rng.random::<f64>() * total;
+        for (i, &weight) in weights.iter().enumerate() {
+            if target < weight {
+                return Some(distribution[i].0);
+            }
+            target -= weight;
+        }
+        // Rounding can leave a sliver of mass past the last word with any weight
+        let last = weights
+            .iter()
+            .rposition(|&weight| weight > 0.0)
+            .unwrap_or(0);
+        Some(distribution[last].0)
+    }
+}
+
+impl LanguageModel {
+    /// Generates text continuing `context`, one sampled word at a time.
+    ///
+    /// Generation stops when `</s>` is sampled, which is not returned, or after
+    /// `max_len` words. The unknown word is never generated.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The words the text continues; empty to start a sentence
+    /// * `max_len` - The largest number of words generated
+    /// * `sampling` - How each word is picked from the model distribution
+    /// * `rng` - The source of randomness, seeded for reproducible output
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::{LanguageModel, Sampling, Smoothing};
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    ///
+    /// let corpus = [["the", "cat", "sat"], ["the", "cat", "ran"], ["a", "cat", "sat"]];
+    /// let model = LanguageModel::train(2, Smoothing::KneserNey, corpus);
+    /// let mut rng = StdRng::seed_from_u64(7);
+    ///
+    /// let greedy = model.generate(&["the"], 10, &Sampling::new().temperature(0.0), &mut rng);
+    /// assert_eq!(greedy, ["cat", "sat"]);
+    ///
+    /// let sampled = model.generate(&[] as &[&str], 5, &Sampling::new().top_p(0.9), &mut rng);
+    /// assert!(sampled.len() <= 5);
+    /// ```
+    pub fn generate<S, R>(
+        &self,
+        context: &[S],
+        max_len: usize,
+        sampling: &Sampling,
+        rng: &mut R,
+    ) -> Vec<String>
+    where
+        S: AsRef<str>,
+        R: Rng + ?Sized,
+    {
+        let mut words: Vec<String> = context.iter().map(|w| w.as_ref().to_string()).collect();
+        let start = words.len();
+        while words.len() - start < max_len {
+            let history = words.len().saturating_sub(self.order() - 1);
+            let distribution = self.distribution(&words[history..]);
+            let Some(word) = sampling.pick(&distribution, rng) else {
+                break;
+            };
+            if word == EOS {
+                break;
+            }
+            words.push(word.to_string());
+        }
+        words.split_off(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// Tests greedy decoding, the top-k and top-p cuts and reproducibility
+    #[test]
+    fn test_sampling() {
+        let distribution = [("a", 0.5), ("b", 0.3), ("c", 0.15), ("d", 0.05)];
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut draw = |sampling: Sampling| -> Vec<&str> {
+            let mut seen: Vec<&str> = (0..500)
+                .map(|_| sampling.pick(&distribution, &mut rng).unwrap())
+                .collect();
+            seen.sort_unstable();
+            seen.dedup();
+            seen
+        };
+        assert_eq!(draw(Sampling::new().temperature(0.0)), ["a"]);
+        assert_eq!(draw(Sampling::new().top_k(2)), ["a", "b"]);
+        assert_eq!(draw(Sampling::new().top_p(0.8)), ["a", "b"]);
+        assert_eq!(draw(Sampling::new().top_p(0.81)), ["a", "b", "c"]);
+        assert_eq!(draw(Sampling::new()), ["a", "b", "c", "d"]);
+
+        // A low temperature concentrates the draws on the most probable word
+        let mut cold = 0;
+        for _ in 0..500 {
+            cold += usize::from(
+                Sampling::new()
+                    .temperature(0.1)
+                    .pick(&distribution, &mut rng)
+                    == Some("a"),
+            );
+        }
+        assert!(cold > 480, "{cold}");
+
+        // Every weight would underflow without scaling by the largest probability
+        let cold = Sampling::new().temperature(1e-3);
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..100 {
+            assert_eq!(cold.pick(&distribution, &mut rng), Some("a"));
+        }
+        let tiny = [("x", 1e-300), ("y", 1e-310)];
+        assert_eq!(cold.pick(&tiny, &mut rng), Some("x"));
+        assert_eq!(cold.pick(&[], &mut rng), None);
+
+        let model = LanguageModel::train(
+            2,
+            crate::Smoothing::Laplace,
+            [["x", "y", "z"], ["x", "z", "y"]],
+        );
+        let sampling = Sampling::new().temperature(1.5);
+        let first = model.generate(&["x"], 20, &sampling, &mut StdRng::seed_from_u64(3));
+        let second = model.generate(&["x"], 20, &sampling, &mut StdRng::seed_from_u64(3));
+        assert_eq!(first, second);
+        assert!(first.len() <= 20 && !first.iter().any(|w| w == "</s>"));
+        assert!(
+            model
+                .generate(&["x"], 0, &sampling, &mut StdRng::seed_from_u64(3))
+                .is_empty()
+        );
+    }
+}
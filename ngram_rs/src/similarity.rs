@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::generate_char_ngrams;
+
+/// Computes the Jaccard similarity between the sets of items in `a` and `b`.
+///
+/// Duplicates are ignored: the score is `|A ∩ B| / |A ∪ B|`. Two empty inputs are
+/// considered identical and score `1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::jaccard_similarity;
+///
+/// let score = jaccard_similarity(["a", "b", "c"], ["b", "c", "d"]);
+/// assert_eq!(score, 0.5);
+/// ```
+pub fn jaccard_similarity<T, A, B>(a: A, b: B) -> f64
+where
+    T: Hash + Eq,
+    A: IntoIterator<Item = T>,
+    B: IntoIterator<Item = T>,
+{
+    let a: HashSet<T> = a.into_iter().collect();
+    let b: HashSet<T> = b.into_iter().collect();
+
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Computes the Sørensen–Dice coefficient between the sets of items in `a` and `b`.
+///
+/// The score is `2 |A ∩ B| / (|A| + |B|)`, which weighs shared items more than
+/// [`jaccard_similarity`]. Duplicates are ignored and two empty inputs score `1.0`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::dice_similarity;
+///
+/// let score = dice_similarity(["a", "b", "c"], ["b", "c", "d"]);
+/// assert!((score - 2.0 / 3.0).abs() < 1e-12);
+/// ```
+pub fn dice_similarity<T, A, B>(a: A, b: B) -> f64
+where
+    T: Hash + Eq,
+    A: IntoIterator<Item = T>,
+    B: IntoIterator<Item = T>,
+{
+    let a: HashSet<T> = a.into_iter().collect();
+    let b: HashSet<T> = b.into_iter().collect();
+
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / total as f64
+}
+
+/// Computes the Jaccard similarity of the character n-grams of two strings.
+///
+/// This is the usual fuzzy-matching score: strings sharing many n-character substrings
+/// score close to `1.0`, unrelated strings close to `0.0`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::char_ngram_jaccard;
+///
+/// assert_eq!(char_ngram_jaccard("night", "night", 2), 1.0);
+/// assert!(char_ngram_jaccard("night", "nacht", 2) < 0.5);
+/// ```
+pub fn char_ngram_jaccard(a: &str, b: &str, n: usize) -> f64 {
+    jaccard_similarity(generate_char_ngrams(a, &[n]), generate_char_ngrams(b, &[n]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests Jaccard on overlapping and disjoint sets, ignoring duplicates
+    #[test]
+    fn test_jaccard_similarity() {
+        assert_eq!(jaccard_similarity(["a", "a", "b"], ["a", "b", "b"]), 1.0);
+        assert_eq!(jaccard_similarity(["a"], ["b"]), 0.0);
+        assert_eq!(jaccard_similarity(Vec::<&str>::new(), Vec::new()), 1.0);
+    }
+
+    /// Tests Dice on overlapping and disjoint sets
+    #[test]
+    fn test_dice_similarity() {
+        assert_eq!(dice_similarity(["a", "b"], ["b", "c"]), 0.5);
+        assert_eq!(dice_similarity(["a"], ["b"]), 0.0);
+        assert_eq!(dice_similarity(Vec::<&str>::new(), Vec::new()), 1.0);
+    }
+
+    /// Tests the character n-gram convenience wrapper
+    #[test]
+    fn test_char_ngram_jaccard() {
+        // bigrams: {ni, ig, gh, ht} vs {na, ac, ch, ht} share only "ht"
+        assert_eq!(char_ngram_jaccard("night", "nacht", 2), 1.0 / 7.0);
+    }
+}
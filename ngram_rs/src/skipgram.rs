@@ -0,0 +1,159 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Generates k-skip-n-grams: ordered selections of `n` tokens that skip at most `k`
+/// tokens in total between them.
+///
+/// Contiguous n-grams are the skip-grams with no skip, so `k = 0` gives the same
+/// result as [`generate_ngrams`](crate::generate_ngrams) for a single `n`.
+///
+/// # Arguments
+///
+/// * `words` - A slice of strings representing the input text as individual words
+/// * `n` - The number of tokens in each skip-gram
+/// * `k` - The maximum total number of tokens skipped inside one skip-gram
+/// * `delimiter` - Optional delimiter string to use between words (defaults to space)
+///
+/// # Returns
+///
+/// The skip-grams ordered by first token, then by the positions of the following
+/// tokens. Empty if `n` is 0 or larger than the number of words.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_skipgrams;
+///
+/// let skipgrams = generate_skipgrams(&["a", "b", "c", "d"], 2, 1, None);
+///
+/// assert_eq!(skipgrams, ["a b", "a c", "b c", "b d", "c d"]);
+/// ```
+pub fn generate_skipgrams<S: AsRef<str>>(
+    words: &[S],
+    n: usize,
+    k: usize,
+    delimiter: Option<&str>,
+) -> Vec<String> {
+    let mut result = Vec::new();
+    write_skipgrams_into(words, n, k, delimiter, &mut result);
+    result
+}
+
+/// Writes k-skip-n-grams into a caller-provided vector, reusing its existing
+/// allocations.
+///
+/// Works like [`write_ngrams_into`](crate::write_ngrams_into): strings already in
+/// `out` are overwritten in place and the vector is truncated to the number of
+/// skip-grams written, which is also returned. The skip-grams are the same, in the
+/// same order, as those of [`generate_skipgrams`].
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::write_skipgrams_into;
+///
+/// let mut out = Vec::new();
+/// for doc in [["a", "b", "c"], ["d", "e", "f"]] {
+///     write_skipgrams_into(&doc, 2, 1, None, &mut out);
+/// }
+///
+/// assert_eq!(out, ["d e", "d f", "e f"]);
+/// ```
+pub fn write_skipgrams_into<S: AsRef<str>>(
+    words: &[S],
+    n: usize,
+    k: usize,
+    delimiter: Option<&str>,
+    out: &mut Vec<String>,
+) -> usize {
+    let delimiter = delimiter.unwrap_or(" ");
+    let mut written = 0;
+    if n > 0 && n <= words.len() {
+        let mut positions = Vec::with_capacity(n);
+        for start in 0..=words.len() - n {
+            positions.push(start);
+            extend_skipgrams(words, n, k, delimiter, &mut positions, out, &mut written);
+            positions.pop();
+        }
+    }
+    out.truncate(written);
+    written
+}
+
+/// Completes the skip-grams starting with `positions`, spending at most `skips_left`
+/// skipped tokens.
+fn extend_skipgrams<S: AsRef<str>>(
+    words: &[S],
+    n: usize,
+    skips_left: usize,
+    delimiter: &str,
+    positions: &mut Vec<usize>,
+    out: &mut Vec<String>,
+    written: &mut usize,
+) {
+    if positions.len() == n {
+        if *written == out.len() {
+            out.push(String::new());
+        }
+        let skipgram = &mut out[*written];
+        skipgram.clear();
+        for (i, &position) in positions.iter().enumerate() {
+            if i > 0 {
+                skipgram.push_str(delimiter);
+            }
+            skipgram.push_str(words[position].as_ref());
+        }
+        *written += 1;
+        return;
+    }
+
+    let next = positions[positions.len() - 1] + 1;
+    // Leave room for the tokens still to be chosen after this one
+    let last = words.len() - (n - positions.len());
+    for position in next..=last.min(next.saturating_add(skips_left)) {
+        positions.push(position);
+        extend_skipgrams(
+            words,
+            n,
+            skips_left - (position - next),
+            delimiter,
+            positions,
+            out,
+            written,
+        );
+        positions.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the total skip budget is shared across the gaps of a skip-gram
+    #[test]
+    fn test_skipgrams_share_skip_budget() {
+        let words = ["a", "b", "c", "d", "e"];
+        assert_eq!(
+            generate_skipgrams(&words, 3, 1, Some("_")),
+            [
+                "a_b_c", "a_b_d", "a_c_d", "b_c_d", "b_c_e", "b_d_e", "c_d_e"
+            ]
+        );
+        assert_eq!(
+            generate_skipgrams(&words, 2, 0, None),
+            ["a b", "b c", "c d", "d e"]
+        );
+        assert!(generate_skipgrams(&words, 6, 2, None).is_empty());
+        assert!(generate_skipgrams(&words, 0, 2, None).is_empty());
+    }
+
+    /// Tests that an unbounded skip budget yields every ordered combination
+    #[test]
+    fn test_skipgrams_unbounded_skips() {
+        let words = ["a", "b", "c", "d"];
+        assert_eq!(
+            generate_skipgrams(&words, 2, usize::MAX, None),
+            ["a b", "a c", "a d", "b c", "b d", "c d"]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use rusqlite::{Connection, Error, params};
+
+use crate::NGramCounter;
+#[cfg(feature = "similarity")]
+use crate::NGramIndex;
+
+/// Rejects table names that would need quoting, since they are spliced into SQL.
+fn check_table(table: &str) -> Result<(), Error> {
+    let valid = !table.is_empty()
+        && !table.starts_with(|c: char| c.is_ascii_digit())
+        && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameterName(table.to_string()))
+    }
+}
+
+fn create_counts_table(conn: &Connection, table: &str) -> Result<(), Error> {
+    check_table(table)?;
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {table} (
+             ngram TEXT NOT NULL,
+             n INTEGER NOT NULL,
+             count INTEGER NOT NULL,
+             PRIMARY KEY (n, ngram)
+         )"
+    ))
+}
+
+impl NGramCounter {
+    /// Saves the counts to `table`, replacing its previous content.
+    ///
+    /// The table is created if needed with columns `(ngram, n, count)` and a primary key
+    /// on `(n, ngram)`, so it can be queried directly with SQL. Everything is written in
+    /// one transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramCounter;
+    /// use rusqlite::Connection;
+    ///
+    /// let conn = Connection::open_in_memory().unwrap();
+    /// let mut counter = NGramCounter::new();
+    /// counter.add_document(&["new", "york", "new", "york"], &[2], None);
+    /// counter.save_to_sqlite(&conn, "bigrams").unwrap();
+    ///
+    /// let count: i64 = conn
+    ///     .query_row("SELECT count FROM bigrams WHERE ngram = 'new york'", [], |r| r.get(0))
+    ///     .unwrap();
+    /// assert_eq!(count, 2);
+    /// assert_eq!(NGramCounter::load_from_sqlite(&conn, "bigrams").unwrap(), counter);
+    /// ```
+    pub fn save_to_sqlite(&self, conn: &Connection, table: &str) -> Result<(), Error> {
+        create_counts_table(conn, table)?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(&format!("DELETE FROM {table}"), [])?;
+        {
+            let mut insert = tx.prepare(&format!(
+                "INSERT INTO {table} (ngram, n, count) VALUES (?1, ?2, ?3)"
+            ))?;
+            for (ngram, n, count) in self.iter() {
+                insert.execute(params![ngram, n as i64, count as i64])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Adds the counts to `table`, summing with the rows already stored.
+    ///
+    /// Use it to persist counts incrementally, e.g. one batch of documents at a time.
+    pub fn upsert_into_sqlite(&self, conn: &Connection, table: &str) -> Result<(), Error> {
+        create_counts_table(conn, table)?;
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut upsert = tx.prepare(&format!(
+                "INSERT INTO {table} (ngram, n, count) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (n, ngram) DO UPDATE SET count = count + excluded.count"
+            ))?;
+            for (ngram, n, count) in self.iter() {
+                upsert.execute(params![ngram, n as i64, count as i64])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Loads all counts stored in `table`.
+    ///
+    /// Fails with [`Error::IntegralValueOutOfRange`] if a stored order or count is
+    /// negative or does not fit in a `usize`.
+    pub fn load_from_sqlite(conn: &Connection, table: &str) -> Result<Self, Error> {
+        check_table(table)?;
+        let mut select = conn.prepare(&format!("SELECT ngram, n, count FROM {table}"))?;
+        let mut rows = select.query([])?;
+
+        let mut counter = NGramCounter::new();
+        while let Some(row) = rows.next()? {
+            let ngram: String = row.get(0)?;
+            let n: i64 = row.get(1)?;
+            let count: i64 = row.get(2)?;
+            let n = usize::try_from(n).map_err(|_| Error::IntegralValueOutOfRange(1, n))?;
+            let count =
+                usize::try_from(count).map_err(|_| Error::IntegralValueOutOfRange(2, count))?;
+            counter.add(&ngram, n, count);
+        }
+        Ok(counter)
+    }
+}
+
+#[cfg(feature = "similarity")]
+impl NGramIndex {
+    /// Saves the indexed strings to `table` as `(id, text)` rows, replacing its content.
+    ///
+    /// Only the strings are stored; postings are rebuilt by
+    /// [`NGramIndex::load_from_sqlite`].
+    pub fn save_to_sqlite(&self, conn: &Connection, table: &str) -> Result<(), Error> {
+        check_table(table)?;
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {table} (id INTEGER PRIMARY KEY, text TEXT NOT NULL)"
+        ))?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(&format!("DELETE FROM {table}"), [])?;
+        {
+            let mut insert =
+                tx.prepare(&format!("INSERT INTO {table} (id, text) VALUES (?1, ?2)"))?;
+            for id in 0..self.len() {
+                insert.execute(params![id as i64, self.get(id)])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Rebuilds an index over character n-grams of size `n` from the strings in `table`.
+    ///
+    /// Strings are inserted by increasing stored id, so tables written by
+    /// [`NGramIndex::save_to_sqlite`] keep their ids. `n` does not have to match the
+    /// index that was saved, so a table can be re-indexed with another n-gram size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::NGramIndex;
+    /// use rusqlite::Connection;
+    ///
+    /// let conn = Connection::open_in_memory().unwrap();
+    /// let mut index = NGramIndex::new(2);
+    /// index.insert("paris");
+    /// index.insert("london");
+    /// index.save_to_sqlite(&conn, "cities").unwrap();
+    ///
+    /// let restored = NGramIndex::load_from_sqlite(&conn, "cities", 3).unwrap();
+    /// assert_eq!(restored.search("londres", 1, 0.0)[0].0, 1);
+    /// ```
+    pub fn load_from_sqlite(conn: &Connection, table: &str, n: usize) -> Result<Self, Error> {
+        check_table(table)?;
+        let mut select = conn.prepare(&format!("SELECT text FROM {table} ORDER BY id"))?;
+        let mut rows = select.query([])?;
+
+        let mut index = NGramIndex::new(n);
+        while let Some(row) = rows.next()? {
+            let text: String = row.get(0)?;
+            index.insert(&text);
+        }
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests saving, upserting and loading counts, and rejecting bad table names
+    #[test]
+    fn test_counter_sqlite_upsert() {
+        let conn = Connection::open_in_memory().unwrap();
+        let mut first = NGramCounter::new();
+        first.add_document(&["a", "b", "a"], &[1, 2], None);
+        first.save_to_sqlite(&conn, "counts").unwrap();
+        first.save_to_sqlite(&conn, "counts").unwrap();
+        assert_eq!(
+            NGramCounter::load_from_sqlite(&conn, "counts").unwrap(),
+            first
+        );
+
+        let mut second = NGramCounter::new();
+        second.add_document(&["a", "c"], &[1], None);
+        second.upsert_into_sqlite(&conn, "counts").unwrap();
+
+        let loaded = NGramCounter::load_from_sqlite(&conn, "counts").unwrap();
+        first.merge(&second);
+        assert_eq!(loaded, first);
+        assert_eq!(loaded.get_order("a", 1), 3);
+
+        assert!(first.save_to_sqlite(&conn, "counts; DROP TABLE x").is_err());
+        assert!(NGramCounter::load_from_sqlite(&conn, "1counts").is_err());
+    }
+
+    /// Tests that negative stored counts and orders are rejected instead of wrapping
+    #[test]
+    fn test_counter_sqlite_out_of_range() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_counts_table(&conn, "counts").unwrap();
+        conn.execute("INSERT INTO counts VALUES ('a', 1, -1)", [])
+            .unwrap();
+        assert!(matches!(
+            NGramCounter::load_from_sqlite(&conn, "counts"),
+            Err(Error::IntegralValueOutOfRange(2, -1))
+        ));
+
+        conn.execute("UPDATE counts SET n = -2, count = 1", [])
+            .unwrap();
+        assert!(matches!(
+            NGramCounter::load_from_sqlite(&conn, "counts"),
+            Err(Error::IntegralValueOutOfRange(1, -2))
+        ));
+    }
+
+    /// Tests that a reloaded index keeps ids and answers queries like the original
+    #[cfg(feature = "similarity")]
+    #[test]
+    fn test_index_sqlite_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        let mut index = NGramIndex::new(3);
+        for name in ["jonathan", "johnathan", "mary"] {
+            index.insert(name);
+        }
+        index.save_to_sqlite(&conn, "names").unwrap();
+
+        let restored = NGramIndex::load_from_sqlite(&conn, "names", 3).unwrap();
+        assert_eq!(restored.get(2), Some("mary"));
+        assert_eq!(
+            restored.search("jonathon", 5, 0.0),
+            index.search("jonathon", 5, 0.0)
+        );
+    }
+}
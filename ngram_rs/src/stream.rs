@@ -0,0 +1,145 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, Lines};
+
+use crate::{NGramCounter, NGramIntoIter, tokenize_whitespace};
+
+/// A [`Stream`] of n-grams backed by an [`NGramIntoIter`].
+///
+/// Every poll is immediately ready; n-grams are generated one at a time as the
+/// consumer pulls them, so a slow sink naturally throttles generation.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use ngram_rs::{NGramStream, ngrams_into_iter};
+///
+/// let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let stream = NGramStream::new(ngrams_into_iter(words, vec![2], None));
+///
+/// let ngrams: Vec<String> = futures::executor::block_on(stream.collect());
+/// assert_eq!(ngrams, ["a b", "b c"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NGramStream {
+    inner: NGramIntoIter,
+}
+
+impl NGramStream {
+    /// Wraps an owning n-gram iterator.
+    pub fn new(inner: NGramIntoIter) -> Self {
+        NGramStream { inner }
+    }
+}
+
+impl Stream for NGramStream {
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().inner.next())
+    }
+}
+
+/// A [`Stream`] of documents read line by line from an async reader.
+///
+/// Created by [`async_line_documents`]. A line is only read when the stream is polled,
+/// so a network source is never read ahead of its consumer.
+#[derive(Debug)]
+pub struct AsyncLineDocuments<R> {
+    lines: Lines<R>,
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for AsyncLineDocuments<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            return match Pin::new(&mut this.lines).poll_next_line(cx) {
+                Poll::Ready(Ok(Some(line))) if line.trim().is_empty() => continue,
+                Poll::Ready(Ok(line)) => Poll::Ready(line.map(Ok)),
+                Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
+                Poll::Pending => Poll::Pending,
+            };
+        }
+    }
+}
+
+/// Streams one document per non-blank line from a tokio reader.
+///
+/// # Examples
+///
+/// ```
+/// use futures::StreamExt;
+/// use ngram_rs::async_line_documents;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let data: &[u8] = b"first line\n\nsecond line\n";
+/// let docs: Vec<String> = async_line_documents(data)
+///     .map(Result::unwrap)
+///     .collect()
+///     .await;
+/// assert_eq!(docs, ["first line", "second line"]);
+/// # });
+/// ```
+pub fn async_line_documents<R: AsyncBufRead + Unpin>(reader: R) -> AsyncLineDocuments<R> {
+    AsyncLineDocuments {
+        lines: reader.lines(),
+    }
+}
+
+impl NGramCounter {
+    /// Reads documents from an async stream, tokenizes them on whitespace and counts
+    /// their n-grams.
+    ///
+    /// The async counterpart of `add_corpus`: documents are pulled one at a time and
+    /// counting stops at the first error.
+    ///
+    /// # Returns
+    ///
+    /// The number of documents counted.
+    pub async fn add_corpus_stream<S>(
+        &mut self,
+        mut documents: S,
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) -> io::Result<usize>
+    where
+        S: Stream<Item = io::Result<String>> + Unpin,
+    {
+        let mut count = 0;
+        while let Some(document) =
+            std::future::poll_fn(|cx| Pin::new(&mut documents).poll_next(cx)).await
+        {
+            let document = document?;
+            let tokens: Vec<&str> = tokenize_whitespace(&document).collect();
+            self.add_document(&tokens, n_range, delimiter);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests counting a corpus streamed from an async reader
+    #[tokio::test]
+    async fn test_add_corpus_stream() {
+        let data: &[u8] = b"the cat sat\n\nthe cat ran\n";
+        let mut counter = NGramCounter::new();
+        let docs = counter
+            .add_corpus_stream(async_line_documents(data), &[2], None)
+            .await
+            .unwrap();
+
+        assert_eq!(docs, 2);
+        assert_eq!(counter.get("the cat"), 2);
+        assert_eq!(counter.get("cat ran"), 1);
+    }
+}
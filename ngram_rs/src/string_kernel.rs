@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use crate::generate_char_ngrams;
+
+/// Counts the character `p`-grams of `text`.
+fn spectrum(text: &str, p: usize) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for ngram in generate_char_ngrams(text, &[p]) {
+        *counts.entry(ngram).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Returns the dot product of two count vectors, walking the smaller one.
+fn dot(a: &HashMap<&str, usize>, b: &HashMap<&str, usize>) -> f64 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(ngram, &count)| large.get(ngram).map(|&other| count * other))
+        .sum::<usize>() as f64
+}
+
+/// Computes the p-spectrum kernel between two strings.
+///
+/// The kernel is the dot product of the count vectors of the character `p`-grams of
+/// `a` and `b`, so it grows with every shared substring of length `p` and with how
+/// often it repeats. Strings shorter than `p` characters have an empty spectrum and
+/// score `0.0` against everything.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::spectrum_kernel;
+///
+/// // "ab" occurs twice in the first string and once in the second
+/// assert_eq!(spectrum_kernel("abab", "abc", 2), 2.0);
+/// assert_eq!(spectrum_kernel("abc", "xyz", 2), 0.0);
+/// ```
+pub fn spectrum_kernel(a: &str, b: &str, p: usize) -> f64 {
+    dot(&spectrum(a, p), &spectrum(b, p))
+}
+
+/// Builds the Gram matrix of the p-spectrum kernel over a batch of documents, as
+/// consumed by SVMs and other kernel methods.
+///
+/// Each spectrum is counted once and every pair is computed once, the matrix being
+/// symmetric.
+///
+/// # Arguments
+///
+/// * `documents` - The documents to compare
+/// * `p` - The length of the character n-grams
+/// * `normalize` - Whether to divide each entry by `sqrt(k(x, x) k(y, y))`, so every
+///   non-empty document has a self-similarity of `1.0`; entries involving a document
+///   with an empty spectrum are `0.0`
+///
+/// # Returns
+///
+/// The `documents.len()` × `documents.len()` matrix, row by row.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::spectrum_gram_matrix;
+///
+/// let gram = spectrum_gram_matrix(&["abab", "abc", "x"], 2, true);
+/// assert!((gram[0][0] - 1.0).abs() < 1e-12);
+/// assert!((gram[0][1] - 2.0 / 10f64.sqrt()).abs() < 1e-12);
+/// assert_eq!(gram[1][0], gram[0][1]);
+/// assert_eq!(gram[2], [0.0, 0.0, 0.0]);
+/// ```
+pub fn spectrum_gram_matrix<S: AsRef<str>>(
+    documents: &[S],
+    p: usize,
+    normalize: bool,
+) -> Vec<Vec<f64>> {
+    let spectra: Vec<HashMap<&str, usize>> = documents
+        .iter()
+        .map(|document| spectrum(document.as_ref(), p))
+        .collect();
+    gram_matrix(spectra.len(), normalize, |i, j| {
+        dot(&spectra[i], &spectra[j])
+    })
+}
+
+/// Computes the gap-weighted subsequence kernel between two strings.
+///
+/// Every subsequence of `p` characters shared by `a` and `b` contributes, even when
+/// other characters sit between its characters, so misspellings and insertions still
+/// match softly. An occurrence spanning `l` characters is weighted by `lambda^l`, and
+/// the kernel sums the products of the weights of every pair of occurrences. A
+/// `lambda` close to 1 counts gappy matches almost fully; a small one approaches the
+/// p-spectrum kernel, contiguous matches scoring `lambda^(2p)`.
+///
+/// The kernel is computed with the dynamic program of Lodhi et al. (2002) in
+/// `O(p |a| |b|)` time and `O(|a| |b|)` memory. A `p` of 0 or longer than either
+/// string scores `0.0`.
+///
+/// # Panics
+///
+/// Panics if `lambda` is not in `(0, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::subsequence_kernel;
+///
+/// let lambda: f64 = 0.5;
+/// // "ca", "ct" and "at" for "cat", spanning 2, 3 and 2 characters
+/// let expected = 2.0 * lambda.powi(4) + lambda.powi(6);
+/// assert!((subsequence_kernel("cat", "cat", 2, lambda) - expected).abs() < 1e-12);
+/// // "car" only shares "ca"
+/// assert!((subsequence_kernel("cat", "car", 2, lambda) - lambda.powi(4)).abs() < 1e-12);
+/// ```
+pub fn subsequence_kernel(a: &str, b: &str, p: usize, lambda: f64) -> f64 {
+    assert!(
+        lambda > 0.0 && lambda <= 1.0,
+        "the decay factor must be in (0, 1]"
+    );
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    subsequence(&a, &b, p, lambda)
+}
+
+/// Runs the subsequence kernel dynamic program over two character slices.
+///
+/// `prefix[m][n]` holds `K'_i` of the prefixes `a[..m]` and `b[..n]`: the weight of
+/// their common subsequences of length `i`, each occurrence counted up to the end of
+/// both prefixes.
+fn subsequence(a: &[char], b: &[char], p: usize, lambda: f64) -> f64 {
+    if p == 0 || p > a.len() || p > b.len() {
+        return 0.0;
+    }
+    let lambda2 = lambda * lambda;
+    let mut prefix = vec![vec![1.0; b.len() + 1]; a.len() + 1];
+    for i in 1..p {
+        let mut next = vec![vec![0.0; b.len() + 1]; a.len() + 1];
+        for m in i..=a.len() {
+            // K''_i of a[..m] and b[..n], updated as n grows
+            let mut last = 0.0;
+            for n in i..=b.len() {
+                last *= lambda;
+                if a[m - 1] == b[n - 1] {
+                    last += lambda2 * prefix[m - 1][n - 1];
+                }
+                next[m][n] = lambda * next[m - 1][n] + last;
+            }
+        }
+        prefix = next;
+    }
+
+    let mut kernel = 0.0;
+    for m in p..=a.len() {
+        for n in p..=b.len() {
+            if a[m - 1] == b[n - 1] {
+                kernel += lambda2 * prefix[m - 1][n - 1];
+            }
+        }
+    }
+    kernel
+}
+
+/// Builds the Gram matrix of the gap-weighted subsequence kernel over a batch of
+/// documents.
+///
+/// # Arguments
+///
+/// * `documents` - The documents to compare
+/// * `p` - The length of the subsequences
+/// * `lambda` - The decay factor, in `(0, 1]`
+/// * `normalize` - Whether to divide each entry by `sqrt(k(x, x) k(y, y))`; entries
+///   involving a document shorter than `p` are `0.0`
+///
+/// # Returns
+///
+/// The `documents.len()` × `documents.len()` matrix, row by row.
+///
+/// # Panics
+///
+/// Panics if `lambda` is not in `(0, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::subsequence_gram_matrix;
+///
+/// let gram = subsequence_gram_matrix(&["colour", "color", "flavour"], 3, 0.7, true);
+/// assert!(gram[0][1] > gram[0][2]);
+/// assert!((gram[2][2] - 1.0).abs() < 1e-12);
+/// ```
+pub fn subsequence_gram_matrix<S: AsRef<str>>(
+    documents: &[S],
+    p: usize,
+    lambda: f64,
+    normalize: bool,
+) -> Vec<Vec<f64>> {
+    assert!(
+        lambda > 0.0 && lambda <= 1.0,
+        "the decay factor must be in (0, 1]"
+    );
+    let chars: Vec<Vec<char>> = documents
+        .iter()
+        .map(|document| document.as_ref().chars().collect())
+        .collect();
+    gram_matrix(chars.len(), normalize, |i, j| {
+        subsequence(&chars[i], &chars[j], p, lambda)
+    })
+}
+
+/// Fills a symmetric `len` × `len` matrix with `kernel`, computing each pair once,
+/// and optionally normalizes it by the self-similarities on its diagonal.
+fn gram_matrix(len: usize, normalize: bool, kernel: impl Fn(usize, usize) -> f64) -> Vec<Vec<f64>> {
+    let mut gram = vec![vec![0.0; len]; len];
+    for (i, j) in (0..len).flat_map(|i| (i..len).map(move |j| (i, j))) {
+        let value = kernel(i, j);
+        gram[i][j] = value;
+        gram[j][i] = value;
+    }
+
+    if normalize {
+        let norms: Vec<f64> = (0..len).map(|i| gram[i][i].sqrt()).collect();
+        for (i, row) in gram.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                let norm = norms[i] * norms[j];
+                *value = if norm == 0.0 { 0.0 } else { *value / norm };
+            }
+        }
+    }
+    gram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the kernel on repeated, multi-byte and too-short inputs
+    #[test]
+    fn test_spectrum_kernel() {
+        // "aa" occurs 3 times in "aaaa" and twice in "aaa"
+        assert_eq!(spectrum_kernel("aaaa", "aaa", 2), 6.0);
+        assert_eq!(spectrum_kernel("café", "fécafé", 2), 4.0);
+        assert_eq!(spectrum_kernel("a", "a", 2), 0.0);
+        assert_eq!(spectrum_kernel("abc", "abc", 0), 0.0);
+    }
+
+    /// Tests that the Gram matrix matches pairwise kernels and is normalized
+    #[test]
+    fn test_spectrum_gram_matrix() {
+        let documents = ["the cat sat", "the cat", "a dog sat", ""];
+        let gram = spectrum_gram_matrix(&documents, 3, false);
+        for (i, a) in documents.iter().enumerate() {
+            for (j, b) in documents.iter().enumerate() {
+                assert_eq!(gram[i][j], spectrum_kernel(a, b, 3));
+            }
+        }
+
+        let normalized = spectrum_gram_matrix(&documents, 3, true);
+        for i in 0..3 {
+            assert!((normalized[i][i] - 1.0).abs() < 1e-12);
+            for j in 0..3 {
+                let expected = gram[i][j] / (gram[i][i] * gram[j][j]).sqrt();
+                assert!((normalized[i][j] - expected).abs() < 1e-12);
+            }
+        }
+        assert!(normalized[3].iter().all(|&value| value == 0.0));
+        assert!(spectrum_gram_matrix(&[] as &[&str], 3, true).is_empty());
+    }
+
+    /// Sums `lambda^(span in a + span in b)` over every pair of matching index
+    /// subsequences of length `p`, by enumerating them.
+    fn brute_force_subsequence(a: &str, b: &str, p: usize, lambda: f64) -> f64 {
+        fn occurrences(chars: &[char], p: usize) -> Vec<(String, i32)> {
+            let mut found = Vec::new();
+            for mask in 0u32..1 << chars.len() {
+                if mask.count_ones() as usize == p {
+                    let indices: Vec<usize> =
+                        (0..chars.len()).filter(|&i| mask >> i & 1 == 1).collect();
+                    let text = indices.iter().map(|&i| chars[i]).collect();
+                    let span = (indices[p - 1] - indices[0] + 1) as i32;
+                    found.push((text, span));
+                }
+            }
+            found
+        }
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        let b_occurrences = occurrences(&b, p);
+        occurrences(&a, p)
+            .iter()
+            .flat_map(|(u, span_a)| {
+                b_occurrences
+                    .iter()
+                    .filter(move |(v, _)| u == v)
+                    .map(move |(_, span_b)| lambda.powi(span_a + span_b))
+            })
+            .sum()
+    }
+
+    /// Tests the dynamic program against exhaustive enumeration
+    #[test]
+    fn test_subsequence_kernel() {
+        let pairs = [
+            ("science", "silence"),
+            ("abab", "baba"),
+            ("héllo", "hello"),
+            ("ab", "xy"),
+        ];
+        for (a, b) in pairs {
+            for p in 1..=4 {
+                for lambda in [0.3, 0.8, 1.0] {
+                    let expected = brute_force_subsequence(a, b, p, lambda);
+                    let kernel = subsequence_kernel(a, b, p, lambda);
+                    assert!((kernel - expected).abs() < 1e-9, "{a} {b} {p} {lambda}");
+                    assert!((subsequence_kernel(b, a, p, lambda) - kernel).abs() < 1e-9);
+                }
+            }
+        }
+        assert_eq!(subsequence_kernel("abc", "abc", 0, 0.5), 0.0);
+        assert_eq!(subsequence_kernel("ab", "abc", 3, 0.5), 0.0);
+    }
+
+    /// Tests that the Gram matrix matches pairwise kernels and ranks near spellings first
+    #[test]
+    fn test_subsequence_gram_matrix() {
+        let documents = ["kernel", "kernal", "colonel", "k"];
+        let gram = subsequence_gram_matrix(&documents, 2, 0.5, false);
+        for (i, a) in documents.iter().enumerate() {
+            for (j, b) in documents.iter().enumerate() {
+                assert_eq!(gram[i][j], subsequence_kernel(a, b, 2, 0.5));
+            }
+        }
+        let normalized = subsequence_gram_matrix(&documents, 2, 0.5, true);
+        assert!(normalized[0][1] > normalized[0][2]);
+        assert!(normalized[3].iter().all(|&value| value == 0.0));
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+
+use crate::{NGramCounter, join_window};
+
+/// An n-gram whose count in one time slice spikes above its recent baseline, found by
+/// [`TemporalCounter::bursts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Burst<'a> {
+    /// The n-gram
+    pub ngram: &'a str,
+    /// Its order
+    pub n: usize,
+    /// Its count in the slice
+    pub count: usize,
+    /// Its mean count over the baseline slices
+    pub baseline: f64,
+    /// `(count + 1) / (baseline + 1)`
+    pub ratio: f64,
+}
+
+/// N-gram counts kept separately for each fixed-width time slice.
+///
+/// Timestamps are plain integers in any unit, such as Unix seconds or days. A slice
+/// covers `[start, start + slice_width)` and is identified by its start, so counts can
+/// be read back as a time series of any n-gram for trend analysis.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::TemporalCounter;
+///
+/// // Daily slices over Unix timestamps
+/// let mut counter = TemporalCounter::new(86_400);
+/// counter.add_document(1_700_000_000, &["new", "model", "released"], &[1, 2], None);
+/// counter.add_document(1_700_086_400, &["new", "model", "benchmarks"], &[1, 2], None);
+/// counter.add_document(1_700_172_800, &["model", "weights"], &[1, 2], None);
+///
+/// let series = counter.series("new model");
+/// assert_eq!(series.iter().map(|&(_, count)| count).collect::<Vec<_>>(), [1, 1, 0]);
+/// assert_eq!(series[0].0, 1_699_920_000);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemporalCounter {
+    slice_width: u64,
+    slices: BTreeMap<u64, NGramCounter>,
+}
+
+impl TemporalCounter {
+    /// Creates an empty counter with slices `slice_width` time units wide.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice_width` is 0.
+    pub fn new(slice_width: u64) -> Self {
+        assert!(slice_width > 0, "time slices need a width of at least 1");
+        TemporalCounter {
+            slice_width,
+            slices: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the width of every slice.
+    pub fn slice_width(&self) -> u64 {
+        self.slice_width
+    }
+
+    /// Returns the start of the slice containing `timestamp`.
+    pub fn slice_start(&self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.slice_width
+    }
+
+    /// Counts the n-grams of one document into the slice containing `timestamp`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - When the document was written
+    /// * `words` - The tokens of the document
+    /// * `n_range` - The n-gram sizes to count
+    /// * `delimiter` - Optional delimiter placed between tokens (defaults to space)
+    pub fn add_document<S: AsRef<str>>(
+        &mut self,
+        timestamp: u64,
+        words: &[S],
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) {
+        let start = self.slice_start(timestamp);
+        self.slices
+            .entry(start)
+            .or_default()
+            .add_document(words, n_range, delimiter);
+    }
+
+    /// Counts the n-grams of a stream of tokens that each carry their own timestamp.
+    ///
+    /// Every n-gram is counted in the slice of its first token, so a stream crossing a
+    /// slice boundary still yields the n-grams spanning it, exactly once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::TemporalCounter;
+    ///
+    /// let mut counter = TemporalCounter::new(10);
+    /// counter.add_timed_tokens(&[(8, "good"), (9, "morning"), (12, "everyone")], &[2], None);
+    ///
+    /// assert_eq!(counter.series("morning everyone"), [(0, 1)]);
+    /// assert_eq!(counter.slice(15).map_or(0, |slice| slice.len()), 0);
+    /// ```
+    pub fn add_timed_tokens<S: AsRef<str>>(
+        &mut self,
+        tokens: &[(u64, S)],
+        n_range: &[usize],
+        delimiter: Option<&str>,
+    ) {
+        let delimiter = delimiter.unwrap_or(" ");
+        let words: Vec<&str> = tokens.iter().map(|(_, word)| word.as_ref()).collect();
+        for &n in n_range {
+            if n == 0 || n > words.len() {
+                continue;
+            }
+            for (i, window) in words.windows(n).enumerate() {
+                let start = self.slice_start(tokens[i].0);
+                let ngram = join_window(window, delimiter);
+                self.slices.entry(start).or_default().add(&ngram, n, 1);
+            }
+        }
+    }
+
+    /// Returns the counts of the slice containing `timestamp`, if anything was counted
+    /// in it.
+    pub fn slice(&self, timestamp: u64) -> Option<&NGramCounter> {
+        self.slices.get(&self.slice_start(timestamp))
+    }
+
+    /// Iterates over the non-empty slices as `(start, counts)`, earliest first.
+    pub fn slices(&self) -> impl Iterator<Item = (u64, &NGramCounter)> + '_ {
+        self.slices.iter().map(|(&start, counts)| (start, counts))
+    }
+
+    /// Finds the n-grams trending in the slice containing `timestamp`.
+    ///
+    /// Each n-gram of the slice is compared with its mean count over the
+    /// `baseline_slices` slices just before it, empty ones included. The ratio adds one
+    /// to both sides, so n-grams absent from the baseline get a finite score that grows
+    /// with their count rather than an infinite one. N-grams reaching `min_ratio` are
+    /// returned by decreasing ratio, ties broken by count, then order and n-gram.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - Any time within the slice to examine
+    /// * `baseline_slices` - How many preceding slices form the baseline
+    /// * `min_ratio` - The smallest ratio reported as a burst
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ngram_rs::TemporalCounter;
+    ///
+    /// let mut counter = TemporalCounter::new(1);
+    /// for day in 0..3 {
+    ///     counter.add_document(day, &["the", "weather"], &[1], None);
+    /// }
+    /// counter.add_document(3, &["the", "eclipse", "the", "eclipse", "eclipse"], &[1], None);
+    ///
+    /// let bursts = counter.bursts(3, 3, 2.0);
+    /// assert_eq!(bursts.len(), 1);
+    /// assert_eq!((bursts[0].ngram, bursts[0].count, bursts[0].ratio), ("eclipse", 3, 4.0));
+    /// ```
+    pub fn bursts(&self, timestamp: u64, baseline_slices: usize, min_ratio: f64) -> Vec<Burst<'_>> {
+        let start = self.slice_start(timestamp);
+        let Some(slice) = self.slices.get(&start) else {
+            return Vec::new();
+        };
+        let baseline_start =
+            start.saturating_sub((baseline_slices as u64).saturating_mul(self.slice_width));
+        let baseline: Vec<&NGramCounter> = self
+            .slices
+            .range(baseline_start..start)
+            .map(|(_, counts)| counts)
+            .collect();
+
+        let mut bursts: Vec<Burst> = slice
+            .iter()
+            .filter_map(|(ngram, n, count)| {
+                let total: usize = baseline
+                    .iter()
+                    .map(|counts| counts.get_order(ngram, n))
+                    .sum();
+                let mean = if baseline_slices == 0 {
+                    0.0
+                } else {
+                    total as f64 / baseline_slices as f64
+                };
+                let ratio = (count as f64 + 1.0) / (mean + 1.0);
+                (ratio >= min_ratio).then_some(Burst {
+                    ngram,
+                    n,
+                    count,
+                    baseline: mean,
+                    ratio,
+                })
+            })
+            .collect();
+        bursts.sort_by(|a, b| {
+            b.ratio
+                .total_cmp(&a.ratio)
+                .then(b.count.cmp(&a.count))
+                .then(a.n.cmp(&b.n))
+                .then(a.ngram.cmp(b.ngram))
+        });
+        bursts
+    }
+
+    /// Returns the time series of `ngram` as `(slice start, count)` pairs.
+    ///
+    /// The series covers every slice from the first to the last one holding any count,
+    /// with zeros where `ngram` does not occur, including slices where nothing was
+    /// counted, so the points are evenly spaced. Counts of all orders are summed, as in
+    /// [`NGramCounter::get`].
+    pub fn series(&self, ngram: &str) -> Vec<(u64, usize)> {
+        let (Some(&first), Some(&last)) = (self.slices.keys().next(), self.slices.keys().last())
+        else {
+            return Vec::new();
+        };
+        (first..=last)
+            .step_by(self.slice_width as usize)
+            .map(|start| {
+                let count = self.slices.get(&start).map_or(0, |slice| slice.get(ngram));
+                (start, count)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests slicing, gaps in the series and n-grams crossing a slice boundary
+    #[test]
+    fn test_temporal_counter() {
+        let mut counter = TemporalCounter::new(10);
+        counter.add_document(3, &["a", "b"], &[1, 2], None);
+        counter.add_document(7, &["a"], &[1], None);
+        counter.add_document(35, &["b", "a"], &[1, 2], None);
+
+        assert_eq!(counter.series("a"), [(0, 2), (10, 0), (20, 0), (30, 1)]);
+        assert_eq!(counter.series("a b"), [(0, 1), (10, 0), (20, 0), (30, 0)]);
+        assert_eq!(
+            counter.slices().map(|(start, _)| start).collect::<Vec<_>>(),
+            [0, 30]
+        );
+        assert_eq!(counter.slice(39).unwrap().get("b a"), 1);
+        assert!(counter.slice(12).is_none());
+
+        counter.add_timed_tokens(&[(19, "x"), (21, "y"), (22, "z")], &[1, 3, 5], Some("_"));
+        assert_eq!(counter.slice(10).unwrap().get("x_y_z"), 1);
+        assert_eq!(counter.slice(20).unwrap().total(1), 2);
+
+        assert!(TemporalCounter::new(1).series("a").is_empty());
+    }
+
+    /// Tests the baseline window, its empty slices and the ranking of bursts
+    #[test]
+    fn test_bursts() {
+        let mut counter = TemporalCounter::new(10);
+        counter.add_document(0, &["old"; 8], &[1], None);
+        counter.add_document(10, &["steady", "steady"], &[1], None);
+        counter.add_document(40, &["old", "steady", "steady", "new", "new"], &[1], None);
+
+        // Slices 10 to 30 form the baseline, so "old" at 0 is outside of it
+        let bursts = counter.bursts(45, 3, 1.0);
+        let found: Vec<(&str, usize)> = bursts.iter().map(|b| (b.ngram, b.count)).collect();
+        assert_eq!(found, [("new", 2), ("old", 1), ("steady", 2)]);
+        assert!((bursts[2].baseline - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(bursts[0].ratio, 3.0);
+
+        assert_eq!(counter.bursts(45, 3, 2.5).len(), 1);
+        assert!(counter.bursts(25, 3, 1.0).is_empty());
+        assert_eq!(counter.bursts(5, 0, 9.0)[0].ratio, 9.0);
+
+        // A baseline reaching back past the first slice starts at zero instead of overflowing
+        let wide = counter.bursts(45, usize::MAX, 0.0);
+        assert_eq!(wide.len(), 3);
+        assert!(wide.iter().all(|burst| burst.baseline < 1e-12));
+    }
+}
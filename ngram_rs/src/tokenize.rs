@@ -0,0 +1,617 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+#[cfg(feature = "memchr")]
+use memchr::memchr3;
+
+/// An iterator over the whitespace-separated tokens of a string.
+///
+/// With the `memchr` feature, token boundaries are located with `memchr3` on space,
+/// tab and newline, which uses SIMD where available, so long runs of token bytes are
+/// skipped in bulk rather than inspected one by one; without it they are found with a
+/// plain byte scan. The remaining ASCII whitespace (carriage return and form
+/// feed) is also treated as a separator. Non-ASCII whitespace is kept inside tokens.
+#[derive(Debug, Clone)]
+pub struct WhitespaceTokens<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for WhitespaceTokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if self.pos >= bytes.len() {
+            return None;
+        }
+
+        let start = self.pos;
+        #[cfg(feature = "memchr")]
+        let end = memchr3(b' ', b'\t', b'\n', &bytes[start..]).map_or(bytes.len(), |i| start + i);
+        #[cfg(not(feature = "memchr"))]
+        let end = bytes[start..]
+            .iter()
+            .position(|&b| b == b' ' || b == b'\t' || b == b'\n')
+            .map_or(bytes.len(), |i| start + i);
+        // Carriage returns and form feeds are rare enough to check with a plain scan
+        let end = bytes[start..end]
+            .iter()
+            .position(|&b| b == b'\r' || b == b'\x0c')
+            .map_or(end, |i| start + i);
+
+        self.pos = end;
+        Some(&self.text[start..end])
+    }
+}
+
+/// Splits `text` into tokens on ASCII whitespace.
+///
+/// Leading, trailing and repeated whitespace never yields empty tokens. Every token
+/// borrows from `text`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::tokenize_whitespace;
+///
+/// let tokens: Vec<&str> = tokenize_whitespace("  the quick\r\nbrown\tfox ").collect();
+/// assert_eq!(tokens, ["the", "quick", "brown", "fox"]);
+/// ```
+pub fn tokenize_whitespace(text: &str) -> WhitespaceTokens<'_> {
+    WhitespaceTokens { text, pos: 0 }
+}
+
+/// How [`tokenize_cjk`] splits the runs of one CJK script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptSplit {
+    /// Leave the script to whitespace tokenization, attached to neighbouring letters
+    Whitespace,
+    /// Make every run of the script one token, split from other scripts
+    Run,
+    /// Make every character of the script its own token
+    Characters,
+}
+
+/// The per-script policy of [`tokenize_cjk`].
+///
+/// The default splits Han and Kana into characters, since Chinese and Japanese are
+/// written without spaces, and keeps Hangul runs whole, since Korean separates its
+/// words with spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CjkPolicy {
+    /// Chinese characters, including Japanese kanji and Korean hanja
+    pub han: ScriptSplit,
+    /// Japanese hiragana and katakana
+    pub kana: ScriptSplit,
+    /// Korean hangul
+    pub hangul: ScriptSplit,
+}
+
+impl Default for CjkPolicy {
+    fn default() -> Self {
+        CjkPolicy {
+            han: ScriptSplit::Characters,
+            kana: ScriptSplit::Characters,
+            hangul: ScriptSplit::Run,
+        }
+    }
+}
+
+/// The class of a character in [`CjkTokens`]: a script split by the policy, the
+/// ideographic space, or anything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Script(u8, ScriptSplit),
+    Space,
+    Other,
+}
+
+impl CjkPolicy {
+    fn class(&self, c: char) -> CharClass {
+        let (script, split) = match c {
+            '\u{3000}' => return CharClass::Space,
+            '\u{3005}'
+            | '\u{3007}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{20000}'..='\u{323af}' => (0, self.han),
+            '\u{3040}'..='\u{30ff}' | '\u{31f0}'..='\u{31ff}' | '\u{ff66}'..='\u{ff9f}' => {
+                (1, self.kana)
+            }
+            '\u{1100}'..='\u{11ff}'
+            | '\u{3130}'..='\u{318f}'
+            | '\u{a960}'..='\u{a97f}'
+            | '\u{ac00}'..='\u{d7ff}' => (2, self.hangul),
+            _ => return CharClass::Other,
+        };
+        match split {
+            ScriptSplit::Whitespace => CharClass::Other,
+            split => CharClass::Script(script, split),
+        }
+    }
+}
+
+/// An iterator over the tokens of [`tokenize_cjk`].
+#[derive(Debug, Clone)]
+pub struct CjkTokens<'a> {
+    words: WhitespaceTokens<'a>,
+    rest: &'a str,
+    policy: CjkPolicy,
+}
+
+impl<'a> Iterator for CjkTokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                self.rest = self.words.next()?;
+                // Most words have no CJK character at all
+                if self.rest.is_ascii() {
+                    return Some(core::mem::take(&mut self.rest));
+                }
+            }
+
+            let mut chars = self.rest.char_indices();
+            let (_, first) = chars.next()?;
+            let class = self.policy.class(first);
+            let end = match class {
+                CharClass::Space => {
+                    self.rest = &self.rest[first.len_utf8()..];
+                    continue;
+                }
+                CharClass::Script(_, ScriptSplit::Characters) => first.len_utf8(),
+                _ => chars
+                    .find(|&(_, c)| self.policy.class(c) != class)
+                    .map_or(self.rest.len(), |(i, _)| i),
+            };
+            let (token, rest) = self.rest.split_at(end);
+            self.rest = rest;
+            return Some(token);
+        }
+    }
+}
+
+/// Splits `text` into tokens on ASCII whitespace, then cuts out runs of Chinese,
+/// Japanese and Korean characters following `policy`.
+///
+/// Whitespace tokenization leaves a whole CJK sentence as one "word", so its n-grams
+/// are useless. Here Han, Kana and Hangul runs are split from the surrounding text and
+/// kept whole or broken into single characters, script by script, so word n-grams over
+/// the result become character n-grams within CJK text while other text is still split
+/// into words. The ideographic space (U+3000) separates tokens too. Every token borrows
+/// from `text`.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{CjkPolicy, ScriptSplit, tokenize_cjk};
+///
+/// let tokens: Vec<&str> = tokenize_cjk("東京タワー is 333m", CjkPolicy::default()).collect();
+/// assert_eq!(tokens, ["東", "京", "タ", "ワ", "ー", "is", "333m"]);
+///
+/// let runs = CjkPolicy { kana: ScriptSplit::Run, ..CjkPolicy::default() };
+/// let tokens: Vec<&str> = tokenize_cjk("東京タワー", runs).collect();
+/// assert_eq!(tokens, ["東", "京", "タワー"]);
+///
+/// let tokens: Vec<&str> = tokenize_cjk("서울에서 Seoul까지", CjkPolicy::default()).collect();
+/// assert_eq!(tokens, ["서울에서", "Seoul", "까지"]);
+/// ```
+pub fn tokenize_cjk(text: &str, policy: CjkPolicy) -> CjkTokens<'_> {
+    CjkTokens {
+        words: tokenize_whitespace(text),
+        rest: "",
+        policy,
+    }
+}
+
+/// Input accepted by the main n-gram generators: pre-tokenized words or raw text.
+///
+/// Slices, arrays and vectors of anything viewable as a `str` are used as the tokens
+/// as they are, while a `&str` is split with [`tokenize_whitespace`] first. Borrowed
+/// inputs keep the unigrams of [`crate::generate_ngrams`] borrowed for `'a`; an owned
+/// `Vec<String>` is consumed and its unigrams are copied.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::generate_ngrams_owned;
+///
+/// let words = vec!["the".to_string(), "quick".to_string(), "fox".to_string()];
+/// let from_words = generate_ngrams_owned(&words, &[2], " ");
+/// assert_eq!(from_words, generate_ngrams_owned("the  quick fox", &[2], " "));
+/// assert_eq!(from_words, generate_ngrams_owned(words, &[2], " "));
+/// ```
+pub trait IntoTokens<'a> {
+    /// A single token.
+    type Token: AsRef<str>;
+    /// The tokens, borrowed from the input or owned.
+    type Tokens: Deref<Target = [Self::Token]>;
+
+    /// Converts the input into its tokens.
+    fn into_tokens(self) -> Self::Tokens;
+
+    /// Returns the `i`-th token as a unigram, borrowed for `'a` when the input is.
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str>;
+}
+
+impl<'a, S: AsRef<str>> IntoTokens<'a> for &'a [S] {
+    type Token = S;
+    type Tokens = &'a [S];
+
+    fn into_tokens(self) -> Self::Tokens {
+        self
+    }
+
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str> {
+        let tokens: &'a [S] = tokens;
+        Cow::Borrowed(tokens[i].as_ref())
+    }
+}
+
+impl<'a, S: AsRef<str>, const N: usize> IntoTokens<'a> for &'a [S; N] {
+    type Token = S;
+    type Tokens = &'a [S];
+
+    fn into_tokens(self) -> Self::Tokens {
+        self
+    }
+
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str> {
+        <&[S]>::unigram(tokens, i)
+    }
+}
+
+impl<'a, S: AsRef<str>> IntoTokens<'a> for &'a Vec<S> {
+    type Token = S;
+    type Tokens = &'a [S];
+
+    fn into_tokens(self) -> Self::Tokens {
+        self
+    }
+
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str> {
+        <&[S]>::unigram(tokens, i)
+    }
+}
+
+impl<'a> IntoTokens<'a> for &'a str {
+    type Token = &'a str;
+    type Tokens = Vec<&'a str>;
+
+    fn into_tokens(self) -> Self::Tokens {
+        tokenize_whitespace(self).collect()
+    }
+
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str> {
+        Cow::Borrowed(tokens[i])
+    }
+}
+
+impl<'a> IntoTokens<'a> for Vec<String> {
+    type Token = String;
+    type Tokens = Vec<String>;
+
+    fn into_tokens(self) -> Self::Tokens {
+        self
+    }
+
+    fn unigram(tokens: &Self::Tokens, i: usize) -> Cow<'a, str> {
+        Cow::Owned(tokens[i].clone())
+    }
+}
+
+/// Splits `text` into words following the Unicode word boundary rules (UAX #29).
+///
+/// Unlike [`tokenize_whitespace`], punctuation is not attached to words and is
+/// dropped, and contractions such as "can't" stay a single word.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::tokenize_unicode_words;
+///
+/// let tokens: Vec<&str> = tokenize_unicode_words("Hello, world! Can't stop.").collect();
+/// assert_eq!(tokens, ["Hello", "world", "Can't", "stop"]);
+/// ```
+#[cfg(feature = "unicode")]
+pub fn tokenize_unicode_words(text: &str) -> unicode_segmentation::UnicodeWords<'_> {
+    unicode_segmentation::UnicodeSegmentation::unicode_words(text)
+}
+
+/// A Unicode normalization form (UAX #15), see [`normalize_unicode`].
+#[cfg(feature = "unicode")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+/// Converts `text` to the normalization `form`, borrowing it when it is already
+/// normalized.
+///
+/// Normalizing tokens before forming n-grams makes composed and decomposed spellings
+/// of the same word, such as "café" written with a combining accent, yield the same
+/// n-grams.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{NormalizationForm, normalize_unicode};
+///
+/// assert_eq!(normalize_unicode("cafe\u{301}", NormalizationForm::Nfc), "caf\u{e9}");
+/// assert_eq!(normalize_unicode("\u{fb01}le", NormalizationForm::Nfkc), "file");
+/// ```
+#[cfg(feature = "unicode")]
+pub fn normalize_unicode(text: &str, form: NormalizationForm) -> Cow<'_, str> {
+    use unicode_normalization::{IsNormalized, UnicodeNormalization};
+
+    let quick = match form {
+        NormalizationForm::Nfc => unicode_normalization::is_nfc_quick(text.chars()),
+        NormalizationForm::Nfd => unicode_normalization::is_nfd_quick(text.chars()),
+        NormalizationForm::Nfkc => unicode_normalization::is_nfkc_quick(text.chars()),
+        NormalizationForm::Nfkd => unicode_normalization::is_nfkd_quick(text.chars()),
+    };
+    if quick == IsNormalized::Yes {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(match form {
+        NormalizationForm::Nfc => text.nfc().collect(),
+        NormalizationForm::Nfd => text.nfd().collect(),
+        NormalizationForm::Nfkc => text.nfkc().collect(),
+        NormalizationForm::Nfkd => text.nfkd().collect(),
+    })
+}
+
+/// Removes accents and other diacritics from `text`, borrowing it when it has none.
+///
+/// The text is decomposed (NFD), its combining marks are dropped and the rest is
+/// recomposed (NFC), so "café" and "cafe" yield the same n-grams for matching.
+/// Letters that do not decompose, such as "ø" or "ł", are kept as they are. Marks are
+/// dropped in every script, including the vowel signs of Indic scripts, so this suits
+/// text where diacritics are secondary, such as Latin, Greek or Cyrillic.
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::strip_diacritics;
+///
+/// assert_eq!(strip_diacritics("Crème brûlée"), "Creme brulee");
+/// assert_eq!(strip_diacritics("cafe\u{301}"), "cafe");
+/// assert_eq!(strip_diacritics("Ελληνικά"), "Ελληνικα");
+/// ```
+#[cfg(feature = "unicode")]
+pub fn strip_diacritics(text: &str) -> Cow<'_, str> {
+    use unicode_normalization::UnicodeNormalization;
+    use unicode_normalization::char::is_combining_mark;
+
+    if text.is_ascii() || !text.nfd().any(is_combining_mark) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.nfd()
+            .filter(|&c| !is_combining_mark(c))
+            .nfc()
+            .collect(),
+    )
+}
+
+/// The language whose rules [`lowercase`] follows.
+///
+/// Only a few languages lowercase differently from the Unicode default mapping, all of
+/// them around the letter I.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CaseLocale {
+    /// The Unicode default mapping, right for most languages
+    #[default]
+    Root,
+    /// Turkish and Azerbaijani, where `I` lowercases to dotless `ı` and `İ` to `i`
+    Turkish,
+}
+
+impl CaseLocale {
+    /// Returns the locale of a BCP 47 language tag such as `"tr"` or `"az-Latn-AZ"`.
+    ///
+    /// Only the language subtag is read, ignoring case, and languages without special
+    /// rules get [`CaseLocale::Root`].
+    pub fn from_language(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("tr") || language.eq_ignore_ascii_case("az") {
+            CaseLocale::Turkish
+        } else {
+            CaseLocale::Root
+        }
+    }
+}
+
+/// Lowercases `text` following the rules of `locale`, borrowing it when it has no
+/// uppercase letter.
+///
+/// The default mapping turns the Turkish `I` into `i` and `İ` into `i` followed by a
+/// combining dot, so Turkish words lose their spelling and stop matching their
+/// lowercase forms. With [`CaseLocale::Turkish`], `I` becomes `ı`, and `İ`, or `I`
+/// followed by a combining dot above, becomes `i`. Greek final sigma is handled in
+/// every locale, as in [`str::to_lowercase`].
+///
+/// # Examples
+///
+/// ```
+/// use ngram_rs::{CaseLocale, lowercase};
+///
+/// assert_eq!(lowercase("DİYARBAKIR", CaseLocale::Turkish), "diyarbakır");
+/// assert_eq!(lowercase("DİYARBAKIR", CaseLocale::Root), "di\u{307}yarbakir");
+/// assert_eq!(lowercase("ΟΔΟΣ", CaseLocale::from_language("el")), "οδος");
+/// ```
+pub fn lowercase(text: &str, locale: CaseLocale) -> Cow<'_, str> {
+    if !text.chars().any(|c| !c.to_lowercase().eq([c])) {
+        return Cow::Borrowed(text);
+    }
+    match locale {
+        CaseLocale::Root => Cow::Owned(text.to_lowercase()),
+        CaseLocale::Turkish => {
+            // The dotted and dotless i are already lowercase, so the default mapping
+            // leaves them alone and still handles final sigma over the whole text
+            let mut dotted = String::with_capacity(text.len());
+            let mut chars = text.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    'I' if chars.next_if_eq(&'\u{307}').is_some() => dotted.push('i'),
+                    'I' => dotted.push('ı'),
+                    '\u{130}' => dotted.push('i'),
+                    c => dotted.push(c),
+                }
+            }
+            Cow::Owned(dotted.to_lowercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the tokenizer agrees with the standard library on ASCII whitespace
+    #[test]
+    fn test_matches_split_ascii_whitespace() {
+        let text = "\x0cNew\u{a0}York  is\r\nbig\t\t\n a\x0cb\rc ";
+
+        let tokens: Vec<&str> = tokenize_whitespace(text).collect();
+        let expected: Vec<&str> = text.split_ascii_whitespace().collect();
+        assert_eq!(tokens, expected);
+        assert_eq!(tokens[0], "New\u{a0}York");
+    }
+
+    /// Tests inputs without any token
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(tokenize_whitespace("").next(), None);
+        assert_eq!(tokenize_whitespace(" \n\t ").next(), None);
+    }
+
+    /// Tests script boundaries, the per-script policies and the ideographic space
+    #[test]
+    fn test_tokenize_cjk() {
+        let tokens = |text, policy| tokenize_cjk(text, policy).collect::<Vec<&str>>();
+        let default = CjkPolicy::default();
+
+        assert_eq!(
+            tokens("私は学生です。 I'm a student", default),
+            [
+                "私", "は", "学", "生", "で", "す", "。", "I'm", "a", "student"
+            ]
+        );
+        assert_eq!(
+            tokens("漢字\u{3000}かな", default),
+            ["漢", "字", "か", "な"]
+        );
+        assert_eq!(tokens("abc中文def", default), ["abc", "中", "文", "def"]);
+        assert_eq!(tokens("한국어 텍스트", default), ["한국어", "텍스트"]);
+        assert_eq!(tokens("𠀋x", default), ["𠀋", "x"]);
+        assert!(tokens(" \u{3000} ", default).is_empty());
+
+        let whitespace = CjkPolicy {
+            han: ScriptSplit::Whitespace,
+            kana: ScriptSplit::Whitespace,
+            hangul: ScriptSplit::Whitespace,
+        };
+        let text = "abc中文def 東京タワー\tcafé";
+        assert_eq!(
+            tokens(text, whitespace),
+            tokenize_whitespace(text).collect::<Vec<_>>()
+        );
+        let han_runs = CjkPolicy {
+            han: ScriptSplit::Run,
+            ..whitespace
+        };
+        assert_eq!(tokens("東京タワー", han_runs), ["東京", "タワー"]);
+    }
+
+    /// Tests that every kind of input yields the same n-grams and which unigrams borrow
+    #[test]
+    fn test_into_tokens() {
+        let owned: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+        let expected = crate::generate_ngrams(&owned, &[1, 2], None);
+        assert_eq!(crate::generate_ngrams(&owned[..], &[1, 2], None), expected);
+        assert_eq!(
+            crate::generate_ngrams(&["a", "b", "c"], &[1, 2], None),
+            expected
+        );
+
+        let from_text = crate::generate_ngrams(" a\tb\nc ", &[1, 2], None);
+        assert_eq!(from_text, expected);
+        assert!(matches!(from_text[0], Cow::Borrowed("a")));
+
+        let from_owned = crate::generate_ngrams(owned, &[1], None);
+        assert!(matches!(&from_owned[0], Cow::Owned(token) if token == "a"));
+    }
+
+    /// Tests that already normalized text is borrowed and that forms differ
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_normalize_unicode() {
+        let composed = "caf\u{e9}";
+        assert!(matches!(
+            normalize_unicode(composed, NormalizationForm::Nfc),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            normalize_unicode(composed, NormalizationForm::Nfd),
+            "cafe\u{301}"
+        );
+        assert_eq!(
+            normalize_unicode("\u{2460}", NormalizationForm::Nfc),
+            "\u{2460}"
+        );
+        assert_eq!(normalize_unicode("\u{2460}", NormalizationForm::Nfkd), "1");
+    }
+
+    /// Tests composed and decomposed accents, stacked marks and borrowed input
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(
+            strip_diacritics("caf\u{e9}"),
+            strip_diacritics("cafe\u{301}")
+        );
+        assert_eq!(strip_diacritics("a\u{323}\u{302}ệ"), "ae");
+        assert_eq!(strip_diacritics("Øresund Łódź"), "Øresund Łodz");
+        // Hangul syllables decompose into letters, not marks, and are recomposed
+        assert_eq!(strip_diacritics("한국 café"), "한국 cafe");
+        assert!(matches!(strip_diacritics("plain"), Cow::Borrowed(_)));
+        assert!(matches!(strip_diacritics("日本"), Cow::Borrowed(_)));
+    }
+
+    /// Tests the Turkish i rules, the borrowed fast path and language tag parsing
+    #[test]
+    fn test_lowercase_locale() {
+        assert!(matches!(
+            lowercase("istanbul", CaseLocale::Turkish),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(lowercase("ISPARTA", CaseLocale::Turkish), "ısparta");
+        assert_eq!(lowercase("İstanbul", CaseLocale::Turkish), "istanbul");
+        assert_eq!(lowercase("I\u{307}zmir", CaseLocale::Turkish), "izmir");
+        assert_eq!(lowercase("İstanbul", CaseLocale::Root), "i\u{307}stanbul");
+        assert_eq!(lowercase("ISPARTA", CaseLocale::Root), "isparta");
+        assert_eq!(lowercase("IΣ", CaseLocale::Turkish), "ıς");
+
+        assert_eq!(CaseLocale::from_language("TR"), CaseLocale::Turkish);
+        assert_eq!(CaseLocale::from_language("az_Latn"), CaseLocale::Turkish);
+        assert_eq!(CaseLocale::from_language("en-US"), CaseLocale::Root);
+        assert_eq!(CaseLocale::from_language(""), CaseLocale::Root);
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+/// Return the n-grams of a list of words.
+#[pyfunction]
+#[pyo3(signature = (words, n_range = vec![1], delimiter = " "))]
+fn generate_ngrams(words: Vec<String>, n_range: Vec<usize>, delimiter: &str) -> Vec<String> {
+    ngram_rs::generate_ngrams_owned(&words, &n_range, delimiter)
+}
+
+/// Return the character n-grams of a string.
+#[pyfunction]
+#[pyo3(signature = (text, n_range = vec![1]))]
+fn char_ngrams(text: &str, n_range: Vec<usize>) -> Vec<String> {
+    ngram_rs::generate_char_ngrams(text, &n_range)
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Split a string into tokens on ASCII whitespace.
+#[pyfunction]
+fn tokenize(text: &str) -> Vec<String> {
+    ngram_rs::tokenize_whitespace(text)
+        .map(String::from)
+        .collect()
+}
+
+/// Count the n-grams of every document in a list of token lists.
+#[pyfunction]
+#[pyo3(signature = (documents, n_range = vec![1], delimiter = " "))]
+fn count_ngrams(
+    documents: Vec<Vec<String>>,
+    n_range: Vec<usize>,
+    delimiter: &str,
+) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for words in &documents {
+        ngram_rs::count_ngrams_into(words, &n_range, Some(delimiter), &mut counts);
+    }
+    counts
+}
+
+/// Return the Jaccard similarity of two token lists, ignoring duplicates.
+#[pyfunction]
+fn jaccard_similarity(a: Vec<String>, b: Vec<String>) -> f64 {
+    ngram_rs::jaccard_similarity(a, b)
+}
+
+/// Return the Jaccard similarity of the character n-grams of two strings.
+#[pyfunction]
+#[pyo3(signature = (a, b, n = 3))]
+fn char_ngram_jaccard(a: &str, b: &str, n: usize) -> f64 {
+    ngram_rs::char_ngram_jaccard(a, b, n)
+}
+
+#[pymodule]
+fn ngram_rs_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(generate_ngrams, m)?)?;
+    m.add_function(wrap_pyfunction!(char_ngrams, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize, m)?)?;
+    m.add_function(wrap_pyfunction!(count_ngrams, m)?)?;
+    m.add_function(wrap_pyfunction!(jaccard_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(char_ngram_jaccard, m)?)?;
+    Ok(())
+}
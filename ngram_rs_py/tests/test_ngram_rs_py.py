@@ -0,0 +1,26 @@
+import ngram_rs_py
+
+
+def test_generate_ngrams():
+    """Test word n-grams with default and custom delimiters"""
+    words = ["the", "quick", "fox"]
+    assert ngram_rs_py.generate_ngrams(words, [2]) == ["the quick", "quick fox"]
+    assert ngram_rs_py.generate_ngrams(words, [3], "_") == ["the_quick_fox"]
+    assert ngram_rs_py.generate_ngrams(words) == words
+
+
+def test_char_ngrams():
+    """Test character n-grams"""
+    assert ngram_rs_py.char_ngrams("café", [3]) == ["caf", "afé"]
+
+
+def test_count_ngrams():
+    """Test counting across documents"""
+    counts = ngram_rs_py.count_ngrams([["to", "be"], ["to", "go"]], [1, 2])
+    assert counts == {"to": 2, "be": 1, "go": 1, "to be": 1, "to go": 1}
+
+
+def test_similarity():
+    """Test Jaccard similarity helpers"""
+    assert ngram_rs_py.jaccard_similarity(["a", "b"], ["b", "c"]) == 1 / 3
+    assert ngram_rs_py.char_ngram_jaccard("night", "night") == 1.0
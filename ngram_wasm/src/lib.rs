@@ -0,0 +1,88 @@
+//! WebAssembly bindings for `ngram_rs`.
+//!
+//! The functions exported here take and return plain JavaScript arrays and numbers, so
+//! browser front-ends get the same generation and similarity logic as the Rust backend:
+//!
+//! ```js
+//! import { ngrams, charNgrams, charNgramJaccard } from "ngram_wasm";
+//!
+//! ngrams(["the", "quick", "fox"], [2], " "); // ["the quick", "quick fox"]
+//! charNgrams("night", [2]);                   // ["ni", "ig", "gh", "ht"]
+//! charNgramJaccard("night", "nacht", 2);      // 0.142857...
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+/// Generates word n-grams from an array of tokens.
+///
+/// `delimiter` defaults to a single space when omitted or `undefined`.
+#[wasm_bindgen(js_name = ngrams)]
+pub fn ngrams(words: Vec<String>, n_range: Vec<usize>, delimiter: Option<String>) -> Vec<String> {
+    ngram_rs::generate_ngrams_owned(&words, &n_range, delimiter.as_deref().unwrap_or(" "))
+}
+
+/// Generates character n-grams from a string.
+#[wasm_bindgen(js_name = charNgrams)]
+pub fn char_ngrams(text: &str, n_range: Vec<usize>) -> Vec<String> {
+    ngram_rs::generate_char_ngrams(text, &n_range)
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Computes the Jaccard similarity between two token arrays, ignoring duplicates.
+#[wasm_bindgen(js_name = jaccardSimilarity)]
+pub fn jaccard_similarity(a: Vec<String>, b: Vec<String>) -> f64 {
+    ngram_rs::jaccard_similarity(a, b)
+}
+
+/// Computes the Jaccard similarity of the character n-grams of two strings.
+#[wasm_bindgen(js_name = charNgramJaccard)]
+pub fn char_ngram_jaccard(a: &str, b: &str, n: usize) -> f64 {
+    ngram_rs::char_ngram_jaccard(a, b, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// Tests that word n-grams use the given delimiter and default to a space
+    #[test]
+    fn test_ngrams_delimiter() {
+        let words = strings(&["the", "quick", "fox"]);
+        assert_eq!(
+            ngrams(words.clone(), vec![2], None),
+            ["the quick", "quick fox"]
+        );
+        assert_eq!(
+            ngrams(words.clone(), vec![1, 3], Some("_".to_string())),
+            ["the", "quick", "fox", "the_quick_fox"]
+        );
+        assert!(ngrams(words, vec![0, 4], None).is_empty());
+    }
+
+    /// Tests that character n-grams come back as owned strings, by characters
+    #[test]
+    fn test_char_ngrams() {
+        assert_eq!(char_ngrams("night", vec![2]), ["ni", "ig", "gh", "ht"]);
+        assert_eq!(char_ngrams("día", vec![1]), ["d", "í", "a"]);
+        assert!(char_ngrams("", vec![1]).is_empty());
+    }
+
+    /// Tests the similarity functions against the `ngram_rs` results
+    #[test]
+    fn test_similarity() {
+        let a = strings(&["a", "b", "b", "c"]);
+        let b = strings(&["b", "c", "d"]);
+        assert_eq!(jaccard_similarity(a, b), 0.5);
+        assert_eq!(
+            char_ngram_jaccard("night", "nacht", 2),
+            ngram_rs::char_ngram_jaccard("night", "nacht", 2)
+        );
+        assert!((char_ngram_jaccard("night", "nacht", 2) - 1.0 / 7.0).abs() < 1e-12);
+    }
+}